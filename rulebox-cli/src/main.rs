@@ -0,0 +1,211 @@
+// `rulebox` command-line tool: label JSONL files, validate and lint rule
+// files, and score them against a golden corpus without going through Python.
+
+use clap::{Parser, Subcommand};
+use rulebox_rust::{validate_dir, RuleBox, RuleBoxError};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(
+    name = "rulebox",
+    version,
+    about = "Label texts with RuleBox rule files"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Label JSONL texts: one JSON string or `{"text": ...}` object per line.
+    Label {
+        /// Rule file (JSON, YAML or TOML).
+        #[arg(long)]
+        rules: PathBuf,
+        /// JSONL to label; defaults to stdin.
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Where to write the labelled JSONL; defaults to stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Check that rule files, or directories of them, load, compile and pass
+    /// their inline examples.
+    Validate {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// List rules that compile but look like mistakes, such as duplicate or
+    /// redundant patterns and flags that do nothing.
+    Lint {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Exit with failure if any warnings are found.
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Score a rule file against a golden corpus: JSONL with the `text` and
+    /// expected `labels` of each line. Fails if any label falls below the
+    /// minimum precision or recall, which by default means any mismatch.
+    TestCorpus {
+        /// Rule file (JSON, YAML or TOML).
+        #[arg(long)]
+        rules: PathBuf,
+        /// Golden corpus JSONL.
+        #[arg(long)]
+        corpus: PathBuf,
+        #[arg(long, default_value_t = 1.0)]
+        min_precision: f64,
+        #[arg(long, default_value_t = 1.0)]
+        min_recall: f64,
+    },
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Label {
+            rules,
+            input,
+            output,
+        } => label(&rules, input.as_deref(), output.as_deref()),
+        Command::Validate { paths } => Ok(validate(&paths)),
+        Command::Lint { paths, strict } => Ok(lint(&paths, strict)),
+        Command::TestCorpus {
+            rules,
+            corpus,
+            min_precision,
+            min_recall,
+        } => test_corpus(&rules, &corpus, min_precision, min_recall),
+    };
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn label(
+    rules: &Path,
+    input: Option<&Path>,
+    output: Option<&Path>,
+) -> Result<ExitCode, RuleBoxError> {
+    let rulebox = RuleBox::from_path(&rules.to_string_lossy())?;
+    let reader: Box<dyn BufRead> = match input {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(io::stdin().lock()),
+    };
+    let writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let count = rulebox.label_stream(reader, writer)?;
+    eprintln!("labelled {} texts", count);
+    Ok(ExitCode::SUCCESS)
+}
+
+// Every file is checked even after a failure, so one run lists them all
+fn validate(paths: &[PathBuf]) -> ExitCode {
+    let mut failed = false;
+    for path in paths {
+        let results = if path.is_dir() {
+            validate_dir(&path.to_string_lossy())
+        } else {
+            let result = RuleBox::from_path(&path.to_string_lossy()).map(|_| ());
+            vec![(path.clone(), result)]
+        };
+        for (file, result) in results {
+            match result {
+                Ok(()) => println!("ok {}", file.display()),
+                Err(e) => {
+                    failed = true;
+                    println!("FAIL {}: {}", file.display(), e);
+                }
+            }
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn lint(paths: &[PathBuf], strict: bool) -> ExitCode {
+    let mut failed = false;
+    let mut warned = false;
+    for path in paths {
+        match RuleBox::from_path(&path.to_string_lossy()) {
+            Ok(rulebox) => {
+                for warning in rulebox.lint() {
+                    warned = true;
+                    println!("{}: {}", path.display(), warning);
+                }
+            }
+            Err(e) => {
+                failed = true;
+                println!("FAIL {}: {}", path.display(), e);
+            }
+        }
+    }
+    if failed || (strict && warned) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn test_corpus(
+    rules: &Path,
+    corpus: &Path,
+    min_precision: f64,
+    min_recall: f64,
+) -> Result<ExitCode, RuleBoxError> {
+    let rulebox = RuleBox::from_path(&rules.to_string_lossy())?;
+    let report = rulebox.test_corpus(BufReader::new(File::open(corpus)?))?;
+    for mismatch in &report.mismatches {
+        println!(
+            "line {}: missing [{}], unexpected [{}]: {:?}",
+            mismatch.line,
+            mismatch.missing.join(", "),
+            mismatch.unexpected.join(", "),
+            mismatch.text
+        );
+    }
+    println!("label\tprecision\trecall\ttp\tfp\tfn");
+    for score in &report.labels {
+        println!(
+            "{}\t{:.3}\t{:.3}\t{}\t{}\t{}",
+            score.label,
+            score.precision(),
+            score.recall(),
+            score.true_positives,
+            score.false_positives,
+            score.false_negatives
+        );
+    }
+    let failing = report.below(min_precision, min_recall);
+    for score in &failing {
+        println!(
+            "FAIL {}: precision {:.3}, recall {:.3}",
+            score.label,
+            score.precision(),
+            score.recall()
+        );
+    }
+    println!(
+        "{} texts, {} mismatched",
+        report.texts,
+        report.mismatches.len()
+    );
+    Ok(if failing.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
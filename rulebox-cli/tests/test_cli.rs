@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rulebox-cli-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn rulebox() -> Command {
+        Command::new(env!("CARGO_BIN_EXE_rulebox"))
+    }
+
+    #[test]
+    fn test_label_writes_jsonl() {
+        let dir = temp_dir("label");
+        let rules = dir.join("rules.yaml");
+        fs::write(
+            &rules,
+            "- label: greeting\n  rule:\n    or_patterns:\n      - pattern: '(?i)\\bhello\\b'\n",
+        )
+        .unwrap();
+        let input = dir.join("texts.jsonl");
+        fs::write(&input, "\"Hello there\"\n{\"id\": 2, \"text\": \"bye\"}\n").unwrap();
+        let output = dir.join("labels.jsonl");
+
+        let status = rulebox()
+            .args(["label", "--rules"])
+            .arg(&rules)
+            .arg("--input")
+            .arg(&input)
+            .arg("--output")
+            .arg(&output)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            concat!(
+                "{\"labels\":[\"greeting\"],\"text\":\"Hello there\"}\n",
+                "{\"id\":2,\"labels\":[],\"text\":\"bye\"}\n",
+            )
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate_reports_every_file() {
+        let dir = temp_dir("validate");
+        fs::write(
+            dir.join("good.json"),
+            r#"[{"label": "ok", "rule": {"or_patterns": [{"pattern": "fine"}]}}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("bad.json"),
+            r#"[{"label": "broken", "rule": {"or_patterns": [{"pattern": "(unclosed"}]}}]"#,
+        )
+        .unwrap();
+
+        let out = rulebox().arg("validate").arg(&dir).output().unwrap();
+        assert!(!out.status.success());
+        let stdout = String::from_utf8(out.stdout).unwrap();
+        assert!(stdout.contains("FAIL") && stdout.contains("bad.json"));
+        assert!(stdout.contains("ok ") && stdout.contains("good.json"));
+
+        let out = rulebox()
+            .arg("validate")
+            .arg(dir.join("good.json"))
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_test_corpus_gates_on_recall() {
+        let dir = temp_dir("corpus");
+        let rules = dir.join("rules.json");
+        fs::write(
+            &rules,
+            r#"[{"label": "greeting", "rule": {"or_patterns": [{"pattern": "(?i)hello"}]}}]"#,
+        )
+        .unwrap();
+        let corpus = dir.join("golden.jsonl");
+        fs::write(
+            &corpus,
+            concat!(
+                "{\"text\": \"Hello\", \"labels\": [\"greeting\"]}\n",
+                "{\"text\": \"Hi\", \"labels\": [\"greeting\"]}\n",
+            ),
+        )
+        .unwrap();
+
+        let run = |min_recall: &str| {
+            rulebox()
+                .args(["test-corpus", "--rules"])
+                .arg(&rules)
+                .arg("--corpus")
+                .arg(&corpus)
+                .args(["--min-recall", min_recall])
+                .output()
+                .unwrap()
+        };
+        let out = run("1.0");
+        assert!(!out.status.success());
+        let stdout = String::from_utf8(out.stdout).unwrap();
+        assert!(stdout.contains("line 2: missing [greeting]"));
+        assert!(stdout.contains("FAIL greeting"));
+
+        assert!(run("0.5").status.success());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
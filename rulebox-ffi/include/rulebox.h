@@ -0,0 +1,72 @@
+#ifndef RULEBOX_H
+#define RULEBOX_H
+
+/* Generated by cbindgen from rulebox-ffi; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ A compiled set of rules. Create with `rulebox_new_from_path` or
+ `rulebox_new_from_json` and release with `rulebox_free`.
+ */
+typedef struct RuleBox RuleBox;
+
+/*
+ Load and compile a rule file (JSON, YAML or TOML, by extension). Returns
+ NULL on failure; `rulebox_last_error` then says why.
+
+ # Safety
+ `path` must be NULL or a NUL-terminated string.
+ */
+struct RuleBox *rulebox_new_from_path(const char *path);
+
+/*
+ Load and compile rules from a JSON string. Returns NULL on failure;
+ `rulebox_last_error` then says why.
+
+ # Safety
+ `json` must be NULL or a NUL-terminated string.
+ */
+struct RuleBox *rulebox_new_from_json(const char *json);
+
+/*
+ The labels for `text` as a sorted JSON array of strings, e.g.
+ `["farewell","greeting"]`. Free the result with `rulebox_string_free`.
+ Returns NULL if either argument is NULL or `text` isn't valid UTF-8.
+
+ # Safety
+ `rulebox` must be NULL or a pointer returned by a `rulebox_new_*`
+ function and not yet freed; `text` must be NULL or a NUL-terminated
+ string.
+ */
+char *rulebox_assign_labels(const struct RuleBox *rulebox, const char *text);
+
+/*
+ Free a box. NULL is ignored.
+
+ # Safety
+ `rulebox` must be NULL or a pointer returned by a `rulebox_new_*`
+ function, and not used again afterwards.
+ */
+void rulebox_free(struct RuleBox *rulebox);
+
+/*
+ Free a string returned by this library. NULL is ignored.
+
+ # Safety
+ `s` must be NULL or a string returned by `rulebox_assign_labels`, and not
+ used again afterwards.
+ */
+void rulebox_string_free(char *s);
+
+/*
+ Why the last call on this thread failed, or NULL if it succeeded. The
+ string belongs to the library and stays valid until the next call on this
+ thread; don't free it.
+ */
+const char *rulebox_last_error(void);
+
+#endif  /* RULEBOX_H */
@@ -0,0 +1,142 @@
+// C API for apps that reach the labeller through FFI (PHP's FFI extension,
+// Ruby's ffi gem). Boxes are opaque pointers; labels come back as a JSON
+// array string, which every such language can already parse. The header is
+// include/rulebox.h, generated by cbindgen.
+
+use rulebox_rust::RuleBox as RustRuleBox;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+/// A compiled set of rules. Create with `rulebox_new_from_path` or
+/// `rulebox_new_from_json` and release with `rulebox_free`.
+pub struct RuleBox {
+    inner: RustRuleBox,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // Interior NULs would truncate the message on the C side anyway
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+}
+
+/// Borrow a C string argument, recording an error for NULL or invalid UTF-8.
+unsafe fn str_arg<'a>(s: *const c_char, name: &str) -> Option<&'a str> {
+    if s.is_null() {
+        set_last_error(format!("{} is NULL", name));
+        return None;
+    }
+    match CStr::from_ptr(s).to_str() {
+        Ok(s) => Some(s),
+        Err(_) => {
+            set_last_error(format!("{} is not valid UTF-8", name));
+            None
+        }
+    }
+}
+
+fn new_box(loaded: Result<RustRuleBox, rulebox_rust::RuleBoxError>) -> *mut RuleBox {
+    match loaded {
+        Ok(inner) => {
+            clear_last_error();
+            Box::into_raw(Box::new(RuleBox { inner }))
+        }
+        Err(e) => {
+            set_last_error(e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Load and compile a rule file (JSON, YAML or TOML, by extension). Returns
+/// NULL on failure; `rulebox_last_error` then says why.
+///
+/// # Safety
+/// `path` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rulebox_new_from_path(path: *const c_char) -> *mut RuleBox {
+    match str_arg(path, "path") {
+        Some(path) => new_box(RustRuleBox::from_path(path)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Load and compile rules from a JSON string. Returns NULL on failure;
+/// `rulebox_last_error` then says why.
+///
+/// # Safety
+/// `json` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rulebox_new_from_json(json: *const c_char) -> *mut RuleBox {
+    match str_arg(json, "json") {
+        Some(json) => new_box(RustRuleBox::from_json(json)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// The labels for `text` as a sorted JSON array of strings, e.g.
+/// `["farewell","greeting"]`. Free the result with `rulebox_string_free`.
+/// Returns NULL if either argument is NULL or `text` isn't valid UTF-8.
+///
+/// # Safety
+/// `rulebox` must be NULL or a pointer returned by a `rulebox_new_*`
+/// function and not yet freed; `text` must be NULL or a NUL-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn rulebox_assign_labels(
+    rulebox: *const RuleBox,
+    text: *const c_char,
+) -> *mut c_char {
+    let Some(rulebox) = rulebox.as_ref() else {
+        set_last_error("rulebox is NULL".to_string());
+        return ptr::null_mut();
+    };
+    let Some(text) = str_arg(text, "text") else {
+        return ptr::null_mut();
+    };
+    let mut labels = rulebox.inner.assign_labels(text);
+    labels.sort();
+    let json = serde_json::to_string(&labels).expect("labels serialize to JSON");
+    // JSON escapes control characters, so there's no NUL to trip over
+    CString::new(json).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Free a box. NULL is ignored.
+///
+/// # Safety
+/// `rulebox` must be NULL or a pointer returned by a `rulebox_new_*`
+/// function, and not used again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rulebox_free(rulebox: *mut RuleBox) {
+    if !rulebox.is_null() {
+        drop(Box::from_raw(rulebox));
+    }
+}
+
+/// Free a string returned by this library. NULL is ignored.
+///
+/// # Safety
+/// `s` must be NULL or a string returned by `rulebox_assign_labels`, and not
+/// used again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rulebox_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Why the last call on this thread failed, or NULL if it succeeded. The
+/// string belongs to the library and stays valid until the next call on this
+/// thread; don't free it.
+#[no_mangle]
+pub extern "C" fn rulebox_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_ffi::*;
+    use std::ffi::{CStr, CString};
+    use std::ptr;
+
+    const RULES: &str = r#"[
+        {"label": "greeting", "rule": {"or_patterns": [{"pattern": "(?i)hello"}]}},
+        {"label": "farewell", "rule": {"or_patterns": [{"pattern": "(?i)bye"}]}}
+    ]"#;
+
+    unsafe fn labels(rulebox: *const RuleBox, text: &str) -> String {
+        let text = CString::new(text).unwrap();
+        let labels = rulebox_assign_labels(rulebox, text.as_ptr());
+        assert!(!labels.is_null());
+        let json = CStr::from_ptr(labels).to_str().unwrap().to_string();
+        rulebox_string_free(labels);
+        json
+    }
+
+    unsafe fn last_error() -> Option<String> {
+        let error = rulebox_last_error();
+        (!error.is_null()).then(|| CStr::from_ptr(error).to_string_lossy().into_owned())
+    }
+
+    #[test]
+    fn test_assign_labels_from_json() {
+        unsafe {
+            let json = CString::new(RULES).unwrap();
+            let rulebox = rulebox_new_from_json(json.as_ptr());
+            assert!(!rulebox.is_null());
+            assert!(last_error().is_none());
+            assert_eq!(
+                labels(rulebox, "Hello and bye"),
+                r#"["farewell","greeting"]"#
+            );
+            assert_eq!(labels(rulebox, "nothing"), "[]");
+            rulebox_free(rulebox);
+        }
+    }
+
+    #[test]
+    fn test_new_from_path() {
+        let path = std::env::temp_dir().join(format!("rulebox-ffi-{}.json", std::process::id()));
+        std::fs::write(&path, RULES).unwrap();
+        unsafe {
+            let c_path = CString::new(path.to_str().unwrap()).unwrap();
+            let rulebox = rulebox_new_from_path(c_path.as_ptr());
+            assert!(!rulebox.is_null());
+            assert_eq!(labels(rulebox, "bye"), r#"["farewell"]"#);
+            rulebox_free(rulebox);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        unsafe {
+            let json =
+                CString::new(r#"[{"label": "x", "rule": {"or_patterns": [{"pattern": "("}]}}]"#)
+                    .unwrap();
+            assert!(rulebox_new_from_json(json.as_ptr()).is_null());
+            assert!(last_error().unwrap().contains("Invalid regex"));
+
+            assert!(rulebox_new_from_path(ptr::null()).is_null());
+            assert_eq!(last_error().unwrap(), "path is NULL");
+
+            let text = CString::new("hello").unwrap();
+            assert!(rulebox_assign_labels(ptr::null(), text.as_ptr()).is_null());
+            assert_eq!(last_error().unwrap(), "rulebox is NULL");
+
+            // Freeing NULL is a no-op
+            rulebox_free(ptr::null_mut());
+            rulebox_string_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,10 @@
+// Generate the gRPC server and client from the published .proto, using the
+// bundled protoc unless `PROTOC` points at another.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+    tonic_prost_build::compile_protos("proto/rulebox.proto")?;
+    Ok(())
+}
@@ -0,0 +1,100 @@
+// gRPC API over a rule file, for services that standardise on gRPC rather
+// than HTTP. The service is published as `proto/rulebox.proto`:
+//
+//   Label        one text, its labels
+//   LabelBatch   many texts, the labels for each in request order
+//   ReloadRules  re-read the rule file now
+//
+// As with `rulebox-server`, the file is also reloaded whenever it changes on
+// disk.
+
+use rulebox_rust::{RuleBoxError, WatchedRuleBox};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+/// Messages, server and client generated from `proto/rulebox.proto`.
+pub mod proto {
+    tonic::include_proto!("rulebox.v1");
+}
+
+use proto::rule_box_server::{RuleBox as RuleBoxRpc, RuleBoxServer};
+use proto::{
+    LabelBatchRequest, LabelBatchResponse, LabelRequest, LabelResponse, ReloadRulesRequest,
+    ReloadRulesResponse,
+};
+
+/// Build the gRPC service for the rule file behind `rules`, ready to add to a
+/// `tonic::transport::Server`.
+pub fn service(rules: Arc<WatchedRuleBox>) -> RuleBoxServer<LabelService> {
+    RuleBoxServer::new(LabelService { rules })
+}
+
+/// The `rulebox.v1.RuleBox` service.
+pub struct LabelService {
+    rules: Arc<WatchedRuleBox>,
+}
+
+#[tonic::async_trait]
+impl RuleBoxRpc for LabelService {
+    async fn label(
+        &self,
+        request: Request<LabelRequest>,
+    ) -> Result<Response<LabelResponse>, Status> {
+        let rulebox = self.rules.current();
+        let text = request.into_inner().text;
+        // Labelling is CPU-bound, so keep it off the async workers
+        let labels = tokio::task::spawn_blocking(move || sorted(rulebox.assign_labels(&text)))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(LabelResponse { labels }))
+    }
+
+    async fn label_batch(
+        &self,
+        request: Request<LabelBatchRequest>,
+    ) -> Result<Response<LabelBatchResponse>, Status> {
+        let rulebox = self.rules.current();
+        let texts = request.into_inner().texts;
+        let results = tokio::task::spawn_blocking(move || {
+            rulebox
+                .assign_labels_vector(&texts)
+                .into_iter()
+                .map(|labels| LabelResponse {
+                    labels: sorted(labels),
+                })
+                .collect()
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(LabelBatchResponse { results }))
+    }
+
+    /// A file that fails to load is `INVALID_ARGUMENT` (`INTERNAL` if it
+    /// couldn't be read) and the previous rules keep serving.
+    async fn reload_rules(
+        &self,
+        _request: Request<ReloadRulesRequest>,
+    ) -> Result<Response<ReloadRulesResponse>, Status> {
+        let rules = Arc::clone(&self.rules);
+        let reloaded = tokio::task::spawn_blocking(move || rules.reload())
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .map_err(|e| reload_error(&e))?;
+        Ok(Response::new(ReloadRulesResponse {
+            reloaded,
+            generation: self.rules.generation(),
+        }))
+    }
+}
+
+fn sorted(mut labels: Vec<String>) -> Vec<String> {
+    labels.sort();
+    labels
+}
+
+fn reload_error(e: &RuleBoxError) -> Status {
+    match e {
+        RuleBoxError::Io(_) => Status::internal(e.to_string()),
+        _ => Status::invalid_argument(e.to_string()),
+    }
+}
@@ -0,0 +1,44 @@
+// `rulebox-grpc`: serve a rule file over gRPC. See the library and
+// `proto/rulebox.proto` for the API.
+
+use clap::Parser;
+use rulebox_rust::WatchedRuleBox;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use tonic::transport::Server;
+
+#[derive(Parser)]
+#[command(
+    name = "rulebox-grpc",
+    version,
+    about = "Serve RuleBox labelling over gRPC"
+)]
+struct Args {
+    /// Rule file (JSON, YAML or TOML), reloaded whenever it changes.
+    #[arg(long)]
+    rules: PathBuf,
+    /// Address to listen on.
+    #[arg(long, default_value = "127.0.0.1:50051")]
+    listen: SocketAddr,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    let rules = match WatchedRuleBox::new(&args.rules.to_string_lossy()) {
+        Ok(rules) => Arc::new(rules),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("serving {} on grpc://{}", args.rules.display(), args.listen);
+    let server = Server::builder().add_service(rulebox_grpc::service(rules));
+    if let Err(e) = server.serve(args.listen).await {
+        eprintln!("error: {}", e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
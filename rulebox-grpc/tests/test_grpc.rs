@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_grpc::proto::rule_box_client::RuleBoxClient;
+    use rulebox_grpc::proto::{LabelBatchRequest, LabelRequest, ReloadRulesRequest};
+    use rulebox_rust::WatchedRuleBox;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::transport::{Channel, Server};
+    use tonic::Code;
+
+    const RULES: &str = r#"[
+        {"uuid": "r1", "label": "greeting", "rule": {"or_patterns": [{"pattern": "(?i)hello"}]}},
+        {"uuid": "r2", "label": "farewell", "rule": {"or_patterns": [{"pattern": "(?i)bye"}]}}
+    ]"#;
+
+    fn rules_file(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rulebox-grpc-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rules.json");
+        fs::write(&path, RULES).unwrap();
+        path
+    }
+
+    // Serve the rules on a free local port and connect a client to it
+    async fn client(path: &Path) -> RuleBoxClient<Channel> {
+        let rules = WatchedRuleBox::new(path.to_str().unwrap()).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(rulebox_grpc::service(Arc::new(rules)))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        RuleBoxClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_label_and_label_batch() {
+        let mut client = client(&rules_file("label")).await;
+
+        let request = LabelRequest {
+            text: "Bye, hello".to_string(),
+        };
+        let response = client.label(request).await.unwrap().into_inner();
+        assert_eq!(response.labels, vec!["farewell", "greeting"]);
+
+        let request = LabelBatchRequest {
+            texts: vec![
+                "hello".to_string(),
+                "nothing".to_string(),
+                "BYE".to_string(),
+            ],
+        };
+        let results = client
+            .label_batch(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .results;
+        let labels: Vec<Vec<String>> = results.into_iter().map(|r| r.labels).collect();
+        assert_eq!(
+            labels,
+            vec![
+                vec!["greeting".to_string()],
+                vec![],
+                vec!["farewell".to_string()]
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reload_rules() {
+        let path = rules_file("reload");
+        let mut client = client(&path).await;
+
+        let response = client.reload_rules(ReloadRulesRequest {}).await.unwrap();
+        assert!(!response.into_inner().reloaded);
+
+        fs::write(
+            &path,
+            r#"[{"label": "broken", "rule": {"or_patterns": [{"pattern": "("}]}}]"#,
+        )
+        .unwrap();
+        let status = client
+            .reload_rules(ReloadRulesRequest {})
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert!(status.message().contains("Invalid regex"));
+
+        // The previous rules are still serving
+        let request = LabelRequest {
+            text: "hello".to_string(),
+        };
+        let response = client.label(request).await.unwrap().into_inner();
+        assert_eq!(response.labels, vec!["greeting"]);
+    }
+}
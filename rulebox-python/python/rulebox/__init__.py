@@ -5,7 +5,7 @@ This module provides Python bindings for the RuleBox Rust library,
 which allows for fast regex-based text classification using JSON rule definitions.
 """
 
-from .rulebox import RuleBox
+from .rulebox import CompileError, LabelResult, LabelSpan, RuleBox
 
-__all__ = ["RuleBox"]
+__all__ = ["CompileError", "LabelResult", "LabelSpan", "RuleBox"]
 __version__ = "0.1.0"
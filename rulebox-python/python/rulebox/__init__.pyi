@@ -4,9 +4,88 @@ Type stubs for the rulebox module.
 This module provides Python bindings for the Rust-based RuleBox text labeling engine.
 """
 
-from typing import List, Union, Collection
+from typing import Any, Collection, Dict, List, Optional, TypedDict, Union
 from pathlib import Path
 
+class Span(TypedDict):
+    """One pattern match behind a label, as returned by `check_detailed`."""
+
+    uuid: str
+    pattern: str
+    start: int
+    end: int
+    char_start: int
+    char_end: int
+    text: str
+    captures: Dict[str, str]
+    """Values of the rule's ``captures`` groups that took part in this match."""
+
+class LabelDetail(TypedDict):
+    """Everything `check_detailed` found for one label."""
+
+    spans: List[Span]
+    captures: Dict[str, List[str]]
+    """Every value captured for each ``captures`` group, in document order."""
+
+class LintWarning(TypedDict):
+    """One likely mistake found by `lint`."""
+
+    uuid: str
+    kind: str
+    """``matches_everything``, ``duplicate_pattern``, ``duplicate_rule``,
+    ``unused_flag`` or ``redundant_pattern``."""
+    message: str
+
+class LabelSpan:
+    """One pattern match behind a label in a `LabelResult`."""
+
+    @property
+    def label(self) -> str: ...
+    @property
+    def uuid(self) -> str:
+        """UUID of the rule that matched."""
+        ...
+    @property
+    def pattern(self) -> str: ...
+    @property
+    def start(self) -> int:
+        """Byte offset of the match in the UTF-8 text."""
+        ...
+    @property
+    def end(self) -> int: ...
+    @property
+    def char_start(self) -> int:
+        """Character offset of the match, for slicing the Python string."""
+        ...
+    @property
+    def char_end(self) -> int: ...
+    @property
+    def text(self) -> str: ...
+
+class LabelResult:
+    """The outcome of labelling one text with `label` or `label_vector`."""
+
+    @property
+    def labels(self) -> List[str]:
+        """The labels, in the same order as `assign_labels`."""
+        ...
+    @property
+    def rule_ids(self) -> List[str]:
+        """UUIDs of the rules behind the labels, as `assign_rule_ids_vector`."""
+        ...
+    @property
+    def spans(self) -> List[LabelSpan]:
+        """Every match of those rules' patterns, in document order."""
+        ...
+
+class CompileError(ValueError):
+    """A rule that failed to compile, raised by `from_json` and `from_path`."""
+
+    uuid: Optional[str]
+    """UUID of the rule that failed, when known."""
+    pattern: Optional[str]
+    """The offending pattern, for errors about a single pattern."""
+
 class RuleBox:
     """
     A text labeling engine that applies regex-based rules to classify text.
@@ -22,13 +101,34 @@ class RuleBox:
         """
         ...
 
+    @staticmethod
+    def from_dict(obj: Union[List[Dict[str, Any]], Dict[str, Any]]) -> "RuleBox":
+        """
+        Load a RuleBox from rules already parsed into Python objects, e.g. a
+        database row or request payload, without writing a file first.
+
+        Args:
+            obj: A list of rule dicts, or a dict with ``rules`` and box
+                 settings, in the same shape as a JSON rule file.
+
+        Raises:
+            CompileError: If a rule fails to compile.
+            TypeError: If ``obj`` holds values JSON can't represent.
+            ValueError: If ``obj`` isn't a valid rulebox.
+
+        Example:
+            >>> rulebox = RuleBox.from_dict([{"label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}}])
+        """
+        ...
+
     @staticmethod
     def from_path(path: Union[str, Path]) -> "RuleBox":
         """
-        Load a RuleBox from a JSON rules file.
+        Load a RuleBox from a rules file in JSON, YAML (``.yaml``/``.yml``)
+        or TOML (``.toml``), chosen by extension.
 
         Args:
-            path: Path to the JSON file containing rule definitions.
+            path: Path to the file containing rule definitions.
                   Can be a string path or pathlib.Path object.
 
         Returns:
@@ -43,6 +143,34 @@ class RuleBox:
         """
         ...
 
+    def __len__(self) -> int:
+        """Number of rules in the box, active or not."""
+        ...
+
+    def rules(self) -> List[Dict[str, Any]]:
+        """
+        Every rule as a dict in the rule file format: ``uuid``, ``label``,
+        ``active``, the patterns under ``rule`` and any other rule options,
+        in box order.
+
+        Example:
+            >>> [(r["uuid"], r["label"], r["active"]) for r in rulebox.rules()]
+        """
+        ...
+
+    def get_rule(self, uuid: str) -> Optional[Dict[str, Any]]:
+        """The rule with this UUID as a dict (see `rules`), or None."""
+        ...
+
+    def set_active(self, uuid: str, active: bool) -> None:
+        """
+        Turn a rule on or off without reloading the box.
+
+        Raises:
+            KeyError: If no rule has this UUID.
+        """
+        ...
+
     def assign_labels(self, text: str) -> List[str]:
         """
         Assign labels to a single text string.
@@ -61,6 +189,36 @@ class RuleBox:
         """
         ...
 
+    def label(self, text: str) -> LabelResult:
+        """
+        Label a single text and say why: the labels, the rules behind them
+        and where those rules matched.
+
+        Args:
+            text: The text to analyze and label.
+
+        Returns:
+            A LabelResult with ``labels``, ``rule_ids`` and ``spans``.
+
+        Example:
+            >>> result = rulebox.label("Hello world!")
+            >>> result.labels, result.spans[0].text  # (['greeting'], 'Hello')
+        """
+        ...
+
+    def label_vector(self, texts: Collection[str]) -> List[LabelResult]:
+        """
+        `label` for each of several texts, in input order. The GIL is
+        released while the batch is labelled.
+
+        Args:
+            texts: A list of text strings to analyze.
+
+        Returns:
+            One LabelResult per text.
+        """
+        ...
+
     def assign_labels_vector(self, texts: Collection[str]) -> Collection[List[str]]:
         """
         Assign labels to multiple text strings efficiently.
@@ -79,3 +237,94 @@ class RuleBox:
             >>> print(labels)  # [['greeting'], [], ['greeting']]
         """
         ...
+
+    def assign_rule_ids_vector(self, texts: Collection[str]) -> List[List[str]]:
+        """
+        Return the UUIDs of the rules behind each text's labels, rather than
+        the labels, for storing decisions keyed by rule. Each list holds a
+        rule once, in box order; when several rules share a label, only the
+        ones that matched are listed.
+
+        Args:
+            texts: A list of text strings to analyze.
+
+        Returns:
+            A list of UUID lists, one per input text, in input order.
+
+        Example:
+            >>> rulebox.assign_rule_ids_vector(["Hello there", "Goodbye"])
+            [['0190f6d2-...'], []]
+        """
+        ...
+
+    def assign_labels_series(self, series: Any) -> Any:
+        """
+        Assign labels to every row of a pandas or polars Series, or a pyarrow
+        string array, read through Arrow without converting each row to a
+        Python string.
+
+        Args:
+            series: A Series (or Arrow array) of strings. pandas input needs
+                    pyarrow installed.
+
+        Returns:
+            The same kind of object holding a list of labels per row: a polars
+            Series with the same name, a pandas Series with the same index and
+            name, or a ``pyarrow.ChunkedArray``. Null texts get null labels.
+
+        Raises:
+            TypeError: If the input isn't a column of strings.
+
+        Example:
+            >>> rulebox.assign_labels_series(pl.Series("text", ["Hello", "Bye"]))
+        """
+        ...
+
+    def assign_labels_dataframe(self, df: Any, column: str, output: str = "labels") -> Any:
+        """
+        Return a copy of a pandas or polars DataFrame with the labels for its
+        ``column`` of texts added as ``output``.
+
+        Example:
+            >>> labelled = rulebox.assign_labels_dataframe(df, "text")
+            >>> labelled["labels"]
+        """
+        ...
+
+    def check_detailed(self, text: str) -> Dict[str, LabelDetail]:
+        """
+        Label a text and report where each label matched.
+
+        Args:
+            text: The text to analyze and label.
+
+        Returns:
+            A dict mapping each assigned label to {"spans": [...], "captures":
+            {...}}, where each span gives the rule uuid, the pattern, byte
+            offsets (start/end), character offsets (char_start/char_end), the
+            matched text and its captures. ``captures`` maps each group named
+            in the rules' ``captures`` list to the values it matched, so
+            regexes needn't be re-run in Python. Labels added by count rules
+            have no spans.
+
+        Example:
+            >>> rulebox = RuleBox.from_path("rules.json")
+            >>> detail = rulebox.check_detailed("Hello world!")
+            >>> detail["greeting"]["spans"][0]["text"]  # 'Hello'
+        """
+        ...
+
+    def lint(self) -> List[LintWarning]:
+        """
+        Check the rules for likely mistakes that still compile: rules that
+        match every text, duplicate or redundant patterns, copies of another
+        rule, and flags that don't change what a pattern matches.
+
+        Returns:
+            One dict per warning, in rule order; empty if nothing was found.
+
+        Example:
+            >>> rulebox.lint()
+            [{'uuid': 'r1', 'kind': 'unused_flag', 'message': 'flag i has no effect on "[0-9]+"'}]
+        """
+        ...
@@ -0,0 +1,88 @@
+// Labelling pandas, polars and pyarrow string columns through Arrow, so rows
+// are read as borrowed `&str`s instead of being converted to Python strings
+// and then copied into a `Vec<String>`.
+
+use arrow_array::ArrayRef;
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_arrow::{PyArray, PyChunkedArray};
+use rulebox_rust::RuleBox;
+use std::sync::Arc;
+
+/// The library a column came from, so labels are handed back in kind.
+#[derive(Clone, Copy)]
+pub(crate) enum Origin {
+    Polars,
+    Pandas,
+    Arrow,
+}
+
+impl Origin {
+    pub(crate) fn of(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let module: String = value.get_type().getattr("__module__")?.extract()?;
+        Ok(if module.starts_with("polars") {
+            Origin::Polars
+        } else if module.starts_with("pandas") {
+            Origin::Pandas
+        } else {
+            Origin::Arrow
+        })
+    }
+}
+
+/// Read a Series, or anything exporting the Arrow PyCapsule interface, as
+/// Arrow chunks. pandas Series go through `pyarrow.array` first since they
+/// don't export Arrow themselves.
+pub(crate) fn read_column(column: &Bound<'_, PyAny>, origin: Origin) -> PyResult<Vec<ArrayRef>> {
+    let exported = match origin {
+        Origin::Pandas => column
+            .py()
+            .import_bound("pyarrow")?
+            .call_method1("array", (column,))?,
+        _ => column.clone(),
+    };
+    if let Ok(chunked) = exported.extract::<PyChunkedArray>() {
+        return Ok(chunked.into_inner().0);
+    }
+    let array = exported.extract::<PyArray>().map_err(|_| {
+        PyTypeError::new_err("expected a pandas or polars Series, or an Arrow string array")
+    })?;
+    Ok(vec![array.into_inner().0])
+}
+
+/// Labels for every row as an Arrow `list<string>` array; null texts get
+/// null labels.
+pub(crate) fn label_chunks(rulebox: &RuleBox, chunks: &[ArrayRef]) -> PyResult<ArrayRef> {
+    match rulebox.label_arrow(chunks) {
+        Ok(labels) => Ok(Arc::new(labels)),
+        Err(e) => Err(PyTypeError::new_err(e.to_string())),
+    }
+}
+
+/// Hand labels back as the same kind of object the texts came in: a polars
+/// Series with the same name, a pandas Series with the same index and name
+/// (holding lists of str), or otherwise a `pyarrow.ChunkedArray`.
+pub(crate) fn write_column(
+    py: Python<'_>,
+    labels: ArrayRef,
+    column: &Bound<'_, PyAny>,
+    origin: Origin,
+) -> PyResult<PyObject> {
+    let chunked = PyChunkedArray::from_array_refs(vec![labels])?.to_pyarrow(py)?;
+    match origin {
+        Origin::Polars => {
+            let series = py.import_bound("polars")?.getattr("Series")?;
+            Ok(series.call1((column.getattr("name")?, chunked))?.into())
+        }
+        Origin::Pandas => {
+            let kwargs = PyDict::new_bound(py);
+            kwargs.set_item("index", column.getattr("index")?)?;
+            kwargs.set_item("name", column.getattr("name")?)?;
+            let values = chunked.call_method0(py, "to_pylist")?;
+            let series = py.import_bound("pandas")?.getattr("Series")?;
+            Ok(series.call((values,), Some(&kwargs))?.into())
+        }
+        Origin::Arrow => Ok(chunked),
+    }
+}
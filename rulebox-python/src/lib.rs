@@ -1,47 +1,257 @@
+mod arrow;
+mod result;
+
+use arrow::{label_chunks, read_column, write_column, Origin};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyIOError, PyKeyError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyAny;
-use rulebox_rust::RuleBox as RustRuleBox;
+use pyo3::types::{PyAny, PyDict, PyList};
+use result::{LabelResult, LabelSpan};
+use rulebox_rust::{LabelRule, RuleBox as RustRuleBox, RuleBoxError};
 use std::path::PathBuf;
 
+create_exception!(
+    rulebox,
+    CompileError,
+    PyValueError,
+    "A rule that failed to compile; `uuid` and `pattern` say where, when known."
+);
+
+// Compile failures carry the rule and pattern; I/O failures stay `IOError`
+// and anything else is a `ValueError`
+fn load_error(py: Python<'_>, error: &RuleBoxError, message: String) -> PyErr {
+    match error {
+        RuleBoxError::Compile(e) => {
+            let err = CompileError::new_err(message);
+            let value = err.value_bound(py);
+            let attached = value
+                .setattr("uuid", e.uuid())
+                .and_then(|_| value.setattr("pattern", e.pattern()));
+            attached.err().unwrap_or(err)
+        }
+        RuleBoxError::Io(_) => PyIOError::new_err(message),
+        _ => PyValueError::new_err(message),
+    }
+}
+
 /// A Python wrapper for the Rust RuleBox
 #[pyclass]
 pub struct RuleBox {
     inner: RustRuleBox,
 }
 
+// pyo3 0.22's #[pymethods] expansion wraps PyResult returns in a no-op
+// `.into()`, which newer clippy flags on every method
+#[allow(clippy::useless_conversion)]
 #[pymethods]
 impl RuleBox {
     /// Create a RuleBox from a JSON string
     #[staticmethod]
-    fn from_json(json: String) -> PyResult<Self> {
-        let rulebox = RustRuleBox::from_json(&json)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    fn from_json(py: Python<'_>, json: String) -> PyResult<Self> {
+        let rulebox =
+            RustRuleBox::from_json(&json).map_err(|e| load_error(py, &e, e.to_string()))?;
         Ok(RuleBox { inner: rulebox })
     }
 
+    /// Create a RuleBox from rules already parsed into Python objects: a
+    /// list of rule dicts, or a dict with "rules" and box settings, as in a
+    /// rule file. Raises TypeError for values JSON can't represent.
+    #[staticmethod]
+    fn from_dict(py: Python<'_>, obj: Bound<'_, PyAny>) -> PyResult<Self> {
+        let json: String = py
+            .import_bound("json")?
+            .call_method1("dumps", (obj,))?
+            .extract()?;
+        Self::from_json(py, json)
+    }
+
     /// Create a RuleBox from a JSON file path (accepts either string or Path object)
     #[staticmethod]
-    fn from_path(path: Bound<'_, PyAny>) -> PyResult<Self> {
-        let path_str = extract_path_string(&path)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyTypeError, _>(e.to_string()))?;
+    fn from_path(py: Python<'_>, path: Bound<'_, PyAny>) -> PyResult<Self> {
+        let path_str =
+            extract_path_string(&path).map_err(|e| PyTypeError::new_err(e.to_string()))?;
 
         match RustRuleBox::from_path(&path_str) {
             Ok(rulebox) => Ok(RuleBox { inner: rulebox }),
-            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyIOError, _>(
-                format!("Failed to load RuleBox from path '{}': {}", path_str, e).to_string(),
+            Err(e) => Err(load_error(
+                py,
+                &e,
+                format!("Failed to load RuleBox from path '{}': {}", path_str, e),
             )),
         }
     }
 
-    /// Assign labels to a single text and return them as a list of strings
+    /// Number of rules in the box, active or not
+    fn __len__(&self) -> usize {
+        self.inner.rules.len()
+    }
+
+    /// Every rule as a dict in the rule file format (uuid, label, active,
+    /// the patterns under "rule", ...), in box order
+    fn rules(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.inner
+            .rules
+            .iter()
+            .map(|rule| rule_dict(py, rule))
+            .collect()
+    }
+
+    /// The rule with this UUID as a dict, or None
+    fn get_rule(&self, py: Python<'_>, uuid: &str) -> PyResult<Option<PyObject>> {
+        self.inner
+            .rules
+            .iter()
+            .find(|rule| rule.uuid == uuid)
+            .map(|rule| rule_dict(py, rule))
+            .transpose()
+    }
+
+    /// Turn a rule on or off without reloading the box. Raises KeyError for
+    /// an unknown UUID.
+    fn set_active(&mut self, uuid: &str, active: bool) -> PyResult<()> {
+        if self.inner.set_active(uuid, active) {
+            Ok(())
+        } else {
+            Err(PyKeyError::new_err(uuid.to_string()))
+        }
+    }
+
+    /// Assign labels to a single text and return them as a list of strings,
+    /// in rule order (the same order on every run)
     fn assign_labels(&self, text: String) -> PyResult<Vec<String>> {
         Ok(self.inner.assign_labels(&text))
     }
 
-    /// Assign labels to multiple texts and return them as a list of lists of strings
-    fn assign_labels_vector(&self, texts: Vec<String>) -> PyResult<Vec<Vec<String>>> {
-        Ok(self.inner.assign_labels_vector(&texts))
+    /// Label a single text, returning a LabelResult with its `labels`, the
+    /// `rule_ids` behind them and the `spans` those rules matched
+    fn label(&self, text: String) -> LabelResult {
+        LabelResult::new(&self.inner, &text)
     }
+
+    /// `label` for each of several texts, in input order, with the GIL
+    /// released while labelling
+    fn label_vector(&self, py: Python<'_>, texts: Vec<String>) -> Vec<LabelResult> {
+        py.allow_threads(|| {
+            texts
+                .iter()
+                .map(|text| LabelResult::new(&self.inner, text))
+                .collect()
+        })
+    }
+
+    /// Assign labels to multiple texts and return them as a list of lists of
+    /// strings. The GIL is released while the batch is labelled, so other
+    /// Python threads keep running.
+    fn assign_labels_vector(
+        &self,
+        py: Python<'_>,
+        texts: Vec<String>,
+    ) -> PyResult<Vec<Vec<String>>> {
+        Ok(py.allow_threads(|| self.inner.assign_labels_vector(&texts)))
+    }
+
+    /// UUIDs of the rules behind each text's labels, as a list of lists in
+    /// input order, for storing decisions keyed by rule rather than label.
+    /// Like `assign_labels_vector`, the GIL is released while labelling.
+    fn assign_rule_ids_vector(
+        &self,
+        py: Python<'_>,
+        texts: Vec<String>,
+    ) -> PyResult<Vec<Vec<String>>> {
+        Ok(py.allow_threads(|| self.inner.assign_rule_ids_vector(&texts)))
+    }
+
+    /// Labels for every row of a pandas or polars Series (or a pyarrow
+    /// array), read through Arrow without converting rows to Python strings.
+    /// Returns the same kind of Series with a list of labels per row; null
+    /// texts get null labels. pandas input needs pyarrow installed.
+    fn assign_labels_series(&self, py: Python<'_>, series: Bound<'_, PyAny>) -> PyResult<PyObject> {
+        let origin = Origin::of(&series)?;
+        let chunks = read_column(&series, origin)?;
+        let labels = py.allow_threads(|| label_chunks(&self.inner, &chunks))?;
+        write_column(py, labels, &series, origin)
+    }
+
+    /// A copy of a pandas or polars DataFrame with the labels for its `column`
+    /// of texts added as `output`
+    #[pyo3(signature = (df, column, output = "labels"))]
+    fn assign_labels_dataframe(
+        &self,
+        py: Python<'_>,
+        df: Bound<'_, PyAny>,
+        column: &str,
+        output: &str,
+    ) -> PyResult<PyObject> {
+        let labels = self
+            .assign_labels_series(py, df.get_item(column)?)?
+            .into_bound(py);
+        match Origin::of(&df)? {
+            Origin::Polars => {
+                let labels = labels.call_method1("alias", (output,))?;
+                Ok(df.call_method1("with_columns", (labels,))?.into())
+            }
+            Origin::Pandas => {
+                let kwargs = PyDict::new_bound(py);
+                kwargs.set_item(output, labels)?;
+                Ok(df.call_method("assign", (), Some(&kwargs))?.into())
+            }
+            Origin::Arrow => Err(PyTypeError::new_err(
+                "expected a pandas or polars DataFrame",
+            )),
+        }
+    }
+
+    /// Labels for a single text as a dict of label -> {"spans": [...],
+    /// "captures": {...}}, where each span is a dict describing one pattern
+    /// match behind the label and captures maps each of the rules' `captures`
+    /// groups to its values
+    fn check_detailed(&self, py: Python<'_>, text: String) -> PyResult<PyObject> {
+        let result = PyDict::new_bound(py);
+        for (label, detail) in self.inner.check_detailed(&text) {
+            let spans = PyList::empty_bound(py);
+            for span in detail.spans {
+                let item = PyDict::new_bound(py);
+                item.set_item("uuid", span.uuid)?;
+                item.set_item("pattern", span.pattern)?;
+                item.set_item("start", span.start)?;
+                item.set_item("end", span.end)?;
+                item.set_item("char_start", span.char_start)?;
+                item.set_item("char_end", span.char_end)?;
+                item.set_item("text", span.text)?;
+                item.set_item("captures", span.captures)?;
+                spans.append(item)?;
+            }
+            let entry = PyDict::new_bound(py);
+            entry.set_item("spans", spans)?;
+            entry.set_item("captures", detail.captures)?;
+            result.set_item(label, entry)?;
+        }
+        Ok(result.into())
+    }
+
+    /// Warnings about rules that compile but look like mistakes, as dicts of
+    /// {"uuid", "kind", "message"}
+    fn lint(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        let json = py.import_bound("json")?;
+        self.inner
+            .lint()
+            .iter()
+            .map(|warning| {
+                let text = serde_json::to_string(warning)
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+                Ok(json.call_method1("loads", (text,))?.unbind())
+            })
+            .collect()
+    }
+}
+
+/// A rule as a dict in the rule file format, by way of its JSON
+fn rule_dict(py: Python<'_>, rule: &LabelRule) -> PyResult<PyObject> {
+    let json = serde_json::to_string(rule).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(py
+        .import_bound("json")?
+        .call_method1("loads", (json,))?
+        .unbind())
 }
 
 /// Helper function to extract a path string from either a String or PathBuf
@@ -64,5 +274,8 @@ fn extract_path_string(path: &Bound<'_, PyAny>) -> Result<String, &'static str>
 #[pymodule]
 fn rulebox(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<RuleBox>()?;
+    m.add_class::<LabelResult>()?;
+    m.add_class::<LabelSpan>()?;
+    m.add("CompileError", m.py().get_type_bound::<CompileError>())?;
     Ok(())
 }
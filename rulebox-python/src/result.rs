@@ -0,0 +1,89 @@
+// Result objects for the detailed labelling APIs, so type-checked Python
+// code gets attributes with real types rather than nested lists and dicts.
+
+use pyo3::prelude::*;
+use rulebox_rust::{Match, RuleBox};
+
+/// One pattern match behind a label, with byte and character offsets into
+/// the labelled text.
+#[pyclass(frozen, get_all)]
+#[derive(Clone)]
+pub struct LabelSpan {
+    label: String,
+    uuid: String,
+    pattern: String,
+    start: usize,
+    end: usize,
+    char_start: usize,
+    char_end: usize,
+    text: String,
+}
+
+#[pymethods]
+impl LabelSpan {
+    fn __repr__(&self) -> String {
+        format!(
+            "LabelSpan(label={:?}, uuid={:?}, start={}, end={}, text={:?})",
+            self.label, self.uuid, self.start, self.end, self.text
+        )
+    }
+}
+
+impl LabelSpan {
+    fn new(text: &str, m: Match) -> Self {
+        let matched = m.text(text).unwrap_or_default().to_string();
+        let char_start = text
+            .get(..m.start)
+            .map_or(0, |prefix| prefix.chars().count());
+        Self {
+            char_end: char_start + matched.chars().count(),
+            char_start,
+            text: matched,
+            label: m.label,
+            uuid: m.uuid,
+            pattern: m.pattern,
+            start: m.start,
+            end: m.end,
+        }
+    }
+}
+
+/// The labels for one text, the UUIDs of the rules behind them, and the
+/// matches of those rules.
+#[pyclass(frozen, get_all)]
+pub struct LabelResult {
+    labels: Vec<String>,
+    rule_ids: Vec<String>,
+    spans: Vec<LabelSpan>,
+}
+
+#[pymethods]
+impl LabelResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "LabelResult(labels={:?}, rule_ids={:?}, spans=<{} spans>)",
+            self.labels,
+            self.rule_ids,
+            self.spans.len()
+        )
+    }
+}
+
+impl LabelResult {
+    pub(crate) fn new(rulebox: &RuleBox, text: &str) -> Self {
+        let labels = rulebox.assign_labels(text);
+        let rule_ids = rulebox.assign_rule_ids(text);
+        // Rules whose label was suppressed don't explain any label
+        let spans = rulebox
+            .all_matches(text)
+            .into_iter()
+            .filter(|m| rule_ids.contains(&m.uuid))
+            .map(|m| LabelSpan::new(text, m))
+            .collect();
+        Self {
+            labels,
+            rule_ids,
+            spans,
+        }
+    }
+}
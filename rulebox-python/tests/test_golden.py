@@ -0,0 +1,38 @@
+"""
+Golden tests shared with the Rust core (rulebox-rust/tests/test_golden.rs).
+
+Both suites read the same fixture, so any difference between the bindings and
+the core shows up as a failure on one side.
+"""
+
+import json
+from pathlib import Path
+
+import pytest
+from rulebox import RuleBox
+
+GOLDEN_PATH = (
+    Path(__file__).resolve().parents[2] / "rulebox-rust" / "tests" / "fixtures" / "golden.json"
+)
+
+
+@pytest.fixture(scope="module")
+def golden():
+    """Load the shared golden fixture and build a RuleBox from its rules."""
+    fixture = json.loads(GOLDEN_PATH.read_text())
+    rulebox = RuleBox.from_json(json.dumps(fixture["rules"]))
+    return rulebox, fixture["cases"]
+
+
+def test_golden_assign_labels(golden):
+    """Each text gets exactly the expected label set."""
+    rulebox, cases = golden
+    for case in cases:
+        assert sorted(rulebox.assign_labels(case["text"])) == sorted(case["labels"]), case["text"]
+
+
+def test_golden_assign_labels_vector(golden):
+    """Batch labeling returns the expected labels, in rule order."""
+    rulebox, cases = golden
+    texts = [case["text"] for case in cases]
+    assert rulebox.assign_labels_vector(texts) == [case["labels"] for case in cases]
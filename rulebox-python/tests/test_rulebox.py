@@ -7,7 +7,7 @@ import tempfile
 import os
 from pathlib import Path
 import pytest
-from rulebox import RuleBox
+from rulebox import CompileError, RuleBox
 
 
 @pytest.fixture
@@ -133,6 +133,30 @@ class TestRuleBoxBasic:
         labels = rulebox.assign_labels("Hello world")
         assert "greeting" in labels
 
+    def test_from_path_yaml(self, tmp_path):
+        """Test YAML rule files are detected by extension."""
+        rules_file = tmp_path / "rules.yaml"
+        rules_file.write_text(
+            "# comments are allowed\n"
+            "- label: greeting\n"
+            "  rule:\n"
+            "    or_patterns:\n"
+            "      - pattern: '\\bhello\\b'\n"
+            "        flags: [i]\n"
+        )
+        rulebox = RuleBox.from_path(rules_file)
+        assert rulebox.assign_labels("Hello world") == ["greeting"]
+
+    def test_from_path_toml(self, tmp_path):
+        """Test TOML rule files are detected by extension."""
+        rules_file = tmp_path / "rules.toml"
+        rules_file.write_text(
+            '[[rules]]\nlabel = "greeting"\n'
+            "rule = { or_patterns = [{ pattern = '\\bhello\\b', flags = ['i'] }] }\n"
+        )
+        rulebox = RuleBox.from_path(rules_file)
+        assert rulebox.assign_labels("Hello world") == ["greeting"]
+
     def test_from_path_with_pathlib_path(self, simple_rules_file):
         """Test from_path works with pathlib.Path objects."""
         path_obj = Path(simple_rules_file)
@@ -197,6 +221,23 @@ class TestRuleBoxBasic:
         labels = rulebox.assign_labels("Hello world")
         assert len(labels) == 0
 
+    def test_from_dict_list_and_document(self):
+        """from_dict takes the same shapes as a rule file."""
+        rules = [{"label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}}]
+        rulebox = RuleBox.from_dict(rules)
+        assert rulebox.assign_labels("hello") == ["greeting"]
+
+        rulebox = RuleBox.from_dict({"default_flags": ["i"], "rules": rules})
+        assert rulebox.assign_labels("HELLO") == ["greeting"]
+
+    def test_from_dict_errors(self):
+        with pytest.raises(CompileError):
+            RuleBox.from_dict([{"label": "x", "rule": {"or_patterns": [{"pattern": "("}]}}])
+        with pytest.raises(ValueError):
+            RuleBox.from_dict({"not": "rules"})
+        with pytest.raises(TypeError):
+            RuleBox.from_dict([{"label": object()}])
+
     def test_from_json_vs_from_path_equivalence(self, simple_rules_file):
         """Test that from_json and from_path produce equivalent results."""
         # Load from file
@@ -328,6 +369,38 @@ class TestAssignLabelsVector:
         assert len(all_labels) == 1
         assert "greeting" in all_labels[0]
 
+    def test_assign_labels_vector_releases_gil(self, simple_rules_file):
+        """Other Python threads keep running while a batch is labelled."""
+        import threading
+        import time
+
+        rulebox = RuleBox.from_path(simple_rules_file)
+        texts = ["Hello, is this test@example.com? " * 20] * 200_000
+        done = threading.Event()
+        timings = {}
+        results = []
+
+        def label():
+            timings["start"] = time.perf_counter()
+            results.append(rulebox.assign_labels_vector(texts))
+            timings["end"] = time.perf_counter()
+            done.set()
+
+        worker = threading.Thread(target=label)
+        worker.start()
+        ticks = []
+        while not done.is_set():
+            ticks.append(time.perf_counter())
+            time.sleep(0.001)
+        worker.join()
+
+        # The main thread ran during the middle of the call, not just around it
+        margin = (timings["end"] - timings["start"]) / 4
+        middle = [t for t in ticks if timings["start"] + margin < t < timings["end"] - margin]
+        assert middle
+        assert len(results[0]) == len(texts)
+        assert sorted(results[0][0]) == ["email", "greeting", "question"]
+
 
 class TestComplexRules:
     """Test more complex rule patterns."""
@@ -405,5 +478,230 @@ class TestEdgeCases:
         assert "greeting" in labels
 
 
+class TestCompileError:
+    """Test compile failures are reported with the rule and pattern."""
+
+    def test_invalid_regex(self):
+        rules = [{"uuid": "r1", "label": "x", "rule": {"or_patterns": [{"pattern": "(open"}]}}]
+        with pytest.raises(CompileError) as exc_info:
+            RuleBox.from_json(json.dumps(rules))
+        assert exc_info.value.uuid == "r1"
+        assert exc_info.value.pattern == "(open"
+
+    def test_rule_level_error_has_no_pattern(self, tmp_path):
+        rules = [
+            {
+                "uuid": "r2",
+                "label": "x",
+                "rule": {"not_patterns": [{"pattern": "a"}]},
+            }
+        ]
+        rules_file = tmp_path / "rules.json"
+        rules_file.write_text(json.dumps(rules))
+        with pytest.raises(CompileError) as exc_info:
+            RuleBox.from_path(rules_file)
+        assert exc_info.value.uuid == "r2"
+        assert exc_info.value.pattern is None
+        assert "Failed to load RuleBox" in str(exc_info.value)
+
+    def test_is_a_value_error(self):
+        assert issubclass(CompileError, ValueError)
+
+
+class TestRuleInspection:
+    """Test listing and toggling rules."""
+
+    RULES = [
+        {"uuid": "r1", "label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}},
+        {
+            "uuid": "r2",
+            "label": "farewell",
+            "active": False,
+            "rule": {"or_patterns": [{"pattern": "bye", "flags": ["i"]}]},
+        },
+    ]
+
+    def test_len_and_rules(self):
+        """Rules are listed in box order, inactive ones included."""
+        rulebox = RuleBox.from_json(json.dumps(self.RULES))
+        assert len(rulebox) == 2
+        rules = rulebox.rules()
+        assert [(r["uuid"], r["label"], r["active"]) for r in rules] == [
+            ("r1", "greeting", True),
+            ("r2", "farewell", False),
+        ]
+        assert rules[1]["rule"]["or_patterns"] == [{"pattern": "bye", "flags": ["i"]}]
+
+    def test_get_rule(self):
+        rulebox = RuleBox.from_json(json.dumps(self.RULES))
+        assert rulebox.get_rule("r2")["label"] == "farewell"
+        assert rulebox.get_rule("missing") is None
+
+    def test_set_active(self):
+        """Toggling a rule changes labelling straight away."""
+        rulebox = RuleBox.from_json(json.dumps(self.RULES))
+        assert rulebox.assign_labels("hello, bye") == ["greeting"]
+        rulebox.set_active("r2", True)
+        rulebox.set_active("r1", False)
+        assert rulebox.assign_labels("hello, bye") == ["farewell"]
+        assert rulebox.get_rule("r1")["active"] is False
+        with pytest.raises(KeyError):
+            rulebox.set_active("missing", True)
+
+    def test_assign_rule_ids_vector(self):
+        """Rule UUIDs rather than labels, one list per text."""
+        rules = self.RULES + [
+            {"uuid": "r3", "label": "greeting", "rule": {"or_patterns": [{"pattern": "hi"}]}},
+        ]
+        rulebox = RuleBox.from_json(json.dumps(rules))
+        assert rulebox.assign_rule_ids_vector(["hello", "hi, bye", "quiet"]) == [
+            ["r1"],
+            ["r3"],
+            [],
+        ]
+
+
+class TestLabelResult:
+    """Test the LabelResult objects returned by label and label_vector."""
+
+    def test_label(self, simple_rules_file):
+        """Labels, rule ids and spans come back as attributes."""
+        rulebox = RuleBox.from_path(simple_rules_file)
+        result = rulebox.label("¡Hello! Are you there?")
+
+        assert result.labels == rulebox.assign_labels("¡Hello! Are you there?")
+        assert len(result.rule_ids) == 2
+        span = result.spans[0]
+        assert (span.label, span.text) == ("greeting", "Hello")
+        assert (span.char_start, span.char_end) == (1, 6)
+        assert (span.start, span.end) == (2, 7)
+        assert "LabelResult" in repr(result)
+
+    def test_label_vector(self, simple_rules_file):
+        rulebox = RuleBox.from_path(simple_rules_file)
+        results = rulebox.label_vector(["hi", "nothing here"])
+        assert [r.labels for r in results] == [["greeting"], []]
+        assert results[1].rule_ids == []
+        assert results[1].spans == []
+
+
+class TestLint:
+    """Test warnings from lint."""
+
+    def test_warnings(self):
+        """Each warning names its rule and what looks wrong."""
+        rules = [
+            {"uuid": "r1", "label": "number", "rule": {"or_patterns": [{"pattern": "[0-9]+", "flags": ["i"]}]}},
+            {"uuid": "r2", "label": "pet", "rule": {"or_patterns": [{"pattern": "cat"}, {"pattern": "cats"}]}},
+        ]
+        warnings = RuleBox.from_json(json.dumps(rules)).lint()
+        assert [(w["uuid"], w["kind"]) for w in warnings] == [
+            ("r1", "unused_flag"),
+            ("r2", "redundant_pattern"),
+        ]
+
+    def test_clean_rules(self, simple_rules_file):
+        assert RuleBox.from_path(simple_rules_file).lint() == []
+
+
+class TestCheckDetailed:
+    """Test match spans returned by check_detailed."""
+
+    def test_spans_for_each_label(self, simple_rules_file):
+        """Each label lists the matches that produced it, with byte and char offsets."""
+        rulebox = RuleBox.from_path(simple_rules_file)
+
+        detail = rulebox.check_detailed("¡Hello! Mail test@example.com?")
+        assert sorted(detail) == ["email", "greeting", "question"]
+
+        span = detail["greeting"]["spans"][0]
+        assert span["text"] == "Hello"
+        assert (span["char_start"], span["char_end"]) == (1, 6)
+        assert (span["start"], span["end"]) == (2, 7)
+        assert detail["email"]["spans"][0]["text"] == "test@example.com"
+
+    def test_no_matches(self, simple_rules_file):
+        """Texts with no labels give an empty dict."""
+        rulebox = RuleBox.from_path(simple_rules_file)
+        assert rulebox.check_detailed("nothing to see here") == {}
+
+    def test_captures(self):
+        """Named groups listed in a rule's captures are returned per label."""
+        rulebox = RuleBox.from_json(
+            json.dumps(
+                [
+                    {
+                        "label": "case",
+                        "captures": ["number"],
+                        "rule": {"or_patterns": [{"pattern": "case (?P<number>\\d+)"}]},
+                    }
+                ]
+            )
+        )
+        detail = rulebox.check_detailed("See case 12 and case 345")
+        assert detail["case"]["captures"] == {"number": ["12", "345"]}
+        assert detail["case"]["spans"][1]["captures"] == {"number": "345"}
+
+
+class TestSeries:
+    """Test labelling pandas and polars columns through Arrow."""
+
+    def test_pandas_series(self, simple_rules_file):
+        """Labels come back as a pandas Series with the same index and name."""
+        pd = pytest.importorskip("pandas")
+        pytest.importorskip("pyarrow")
+        rulebox = RuleBox.from_path(simple_rules_file)
+
+        texts = pd.Series(["Hello there", None, "nothing"], index=[10, 20, 30], name="body")
+        labels = rulebox.assign_labels_series(texts)
+        assert isinstance(labels, pd.Series)
+        assert labels.name == "body"
+        assert list(labels.index) == [10, 20, 30]
+        assert labels[10] == ["greeting"]
+        assert labels[20] is None
+        assert labels[30] == []
+
+    def test_polars_series(self, simple_rules_file):
+        """Labels come back as a polars list column with the same name."""
+        pl = pytest.importorskip("polars")
+        pytest.importorskip("pyarrow")
+        rulebox = RuleBox.from_path(simple_rules_file)
+
+        texts = pl.Series("body", ["Hi?", "nothing", None])
+        labels = rulebox.assign_labels_series(texts)
+        assert isinstance(labels, pl.Series)
+        assert labels.name == "body"
+        assert [sorted(l) if l is not None else None for l in labels.to_list()] == [
+            ["greeting", "question"],
+            [],
+            None,
+        ]
+
+    def test_dataframes(self, simple_rules_file):
+        """A labels column is added to a copy of the frame."""
+        pd = pytest.importorskip("pandas")
+        pl = pytest.importorskip("polars")
+        pytest.importorskip("pyarrow")
+        rulebox = RuleBox.from_path(simple_rules_file)
+
+        df = pd.DataFrame({"text": ["hey", "bye"]})
+        labelled = rulebox.assign_labels_dataframe(df, "text")
+        assert list(labelled["labels"]) == [["greeting"], []]
+        assert "labels" not in df
+
+        df = pl.DataFrame({"text": ["hey", "bye"]})
+        labelled = rulebox.assign_labels_dataframe(df, "text", output="tags")
+        assert labelled["tags"].to_list() == [["greeting"], []]
+
+    def test_rejects_non_strings(self, simple_rules_file):
+        """Columns that aren't strings raise TypeError."""
+        pa = pytest.importorskip("pyarrow")
+        rulebox = RuleBox.from_path(simple_rules_file)
+        with pytest.raises(TypeError):
+            rulebox.assign_labels_series(pa.array([1, 2, 3]))
+        with pytest.raises(TypeError):
+            rulebox.assign_labels_series(["a list"])
+
+
 if __name__ == "__main__":
     pytest.main([__file__])
@@ -11,13 +11,14 @@ fn create_test_rulebox() -> RuleBox {
                     pattern: r"\bemail\b".to_string(),
                     flags: vec!["i".to_string()],
                     compiled: None,
+                    ..Default::default()
                 }],
-                and_patterns: vec![],
-                not_patterns: vec![],
+                ..Default::default()
             },
             label: "contains_email".to_string(),
             proto_text: "".to_string(),
             active: true,
+            ..Default::default()
         },
         LabelRule {
             uuid: "test2".to_string(),
@@ -26,13 +27,14 @@ fn create_test_rulebox() -> RuleBox {
                     pattern: r"\bphone\b".to_string(),
                     flags: vec!["i".to_string()],
                     compiled: None,
+                    ..Default::default()
                 }],
-                and_patterns: vec![],
-                not_patterns: vec![],
+                ..Default::default()
             },
             label: "contains_phone".to_string(),
             proto_text: "".to_string(),
             active: true,
+            ..Default::default()
         },
         LabelRule {
             uuid: "test3".to_string(),
@@ -41,13 +43,14 @@ fn create_test_rulebox() -> RuleBox {
                     pattern: r"\baddress\b".to_string(),
                     flags: vec!["i".to_string()],
                     compiled: None,
+                    ..Default::default()
                 }],
-                and_patterns: vec![],
-                not_patterns: vec![],
+                ..Default::default()
             },
             label: "contains_address".to_string(),
             proto_text: "".to_string(),
             active: true,
+            ..Default::default()
         },
         // Add an inactive rule to test filtering
         LabelRule {
@@ -57,17 +60,18 @@ fn create_test_rulebox() -> RuleBox {
                     pattern: r"\binactive\b".to_string(),
                     flags: vec!["i".to_string()],
                     compiled: None,
+                    ..Default::default()
                 }],
-                and_patterns: vec![],
-                not_patterns: vec![],
+                ..Default::default()
             },
             label: "inactive_rule".to_string(),
             proto_text: "".to_string(),
             active: false,
+            ..Default::default()
         },
     ];
 
-    let mut rulebox = RuleBox(rules);
+    let mut rulebox = RuleBox::new(rules);
     rulebox.compile().expect("Failed to compile rules");
     rulebox
 }
@@ -0,0 +1,116 @@
+// Labelling Arrow string columns in place of `Vec<String>` batches: rows are
+// read as borrowed `&str`s and the labels written straight into an Arrow
+// `list<utf8>` column, so a large batch is never copied row by row.
+
+use crate::{RuleBox, RuleBoxError};
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, ListArray, RecordBatch};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use std::sync::Arc;
+
+/// Name of the column `label_record_batch` appends.
+pub const LABELS_COLUMN: &str = "labels";
+
+impl RuleBox {
+    /// Labels for every row of one or more Arrow string arrays (`utf8`,
+    /// `large_utf8` or `utf8_view`), as a single `list<utf8>` array in
+    /// `assign_labels` order. Null texts get null labels.
+    pub fn label_arrow(&self, chunks: &[ArrayRef]) -> Result<ListArray, RuleBoxError> {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        let mut labels = Vec::new();
+        for chunk in chunks {
+            let texts = string_values(chunk)?;
+            let present: Vec<&str> = texts.iter().flatten().copied().collect();
+            self.assign_labels_vector_into(&present, &mut labels);
+            let mut labelled = labels.iter();
+            for text in &texts {
+                if text.is_some() {
+                    for label in labelled.next().into_iter().flatten() {
+                        builder.values().append_value(label);
+                    }
+                }
+                builder.append(text.is_some());
+            }
+        }
+        Ok(builder.finish())
+    }
+
+    /// `batch` with a `labels` column of `list<utf8>` appended, holding the
+    /// labels for each row's `text_column`. Fails if the column is missing,
+    /// isn't a string column, or `batch` already has a `labels` column.
+    pub fn label_record_batch(
+        &self,
+        batch: &RecordBatch,
+        text_column: &str,
+    ) -> Result<RecordBatch, RuleBoxError> {
+        let texts = batch
+            .column_by_name(text_column)
+            .ok_or_else(|| ArrowError::SchemaError(format!("no column named {:?}", text_column)))?;
+        if batch.schema().column_with_name(LABELS_COLUMN).is_some() {
+            let message = format!("batch already has a {:?} column", LABELS_COLUMN);
+            return Err(ArrowError::SchemaError(message).into());
+        }
+        let labels: ArrayRef = Arc::new(self.label_arrow(std::slice::from_ref(texts))?);
+        let mut fields = batch.schema().fields().to_vec();
+        fields.push(Arc::new(Field::new(
+            LABELS_COLUMN,
+            labels.data_type().clone(),
+            true,
+        )));
+        let mut columns = batch.columns().to_vec();
+        columns.push(labels);
+        let schema = Schema::new_with_metadata(fields, batch.schema().metadata().clone());
+        Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+    }
+}
+
+fn string_values(array: &ArrayRef) -> Result<Vec<Option<&str>>, ArrowError> {
+    match array.data_type() {
+        DataType::Utf8 => Ok(array.as_string::<i32>().iter().collect()),
+        DataType::LargeUtf8 => Ok(array.as_string::<i64>().iter().collect()),
+        DataType::Utf8View => Ok(array.as_string_view().iter().collect()),
+        other => Err(ArrowError::InvalidArgumentError(format!(
+            "expected a column of strings, got Arrow type {}",
+            other
+        ))),
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl RuleBox {
+    /// Label a Parquet file batch by batch, writing every row with its
+    /// `labels` to a new Snappy-compressed Parquet file at `output`, so files
+    /// of any size stream through in bounded memory. Returns the number of
+    /// rows written.
+    pub fn label_parquet(
+        &self,
+        input: &str,
+        output: &str,
+        text_column: &str,
+    ) -> Result<usize, RuleBoxError> {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+        use parquet::arrow::ArrowWriter;
+        use parquet::basic::Compression;
+        use parquet::file::properties::WriterProperties;
+        use std::fs::File;
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(input)?)?;
+        // Labelling an empty batch checks the column before `output` is
+        // created, and gives the schema to write
+        let empty = RecordBatch::new_empty(reader.schema().clone());
+        let schema = self.label_record_batch(&empty, text_column)?.schema();
+        let props = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let mut writer = ArrowWriter::try_new(File::create(output)?, schema, Some(props))?;
+        let mut rows = 0;
+        for batch in reader.build()? {
+            let labelled = self.label_record_batch(&batch?, text_column)?;
+            writer.write(&labelled)?;
+            rows += labelled.num_rows();
+        }
+        writer.close()?;
+        Ok(rows)
+    }
+}
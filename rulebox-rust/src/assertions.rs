@@ -0,0 +1,136 @@
+// Inline examples on each rule (`should_match` / `should_not_match`) that are
+// checked whenever a rule file is loaded, and the softer `proto_text` check,
+// which only warns.
+
+use crate::{AssertionFailure, LabelRule, RegexCache, RuleBox, RuleBoxError};
+
+/// The outcome of `run_self_tests`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// Rules with at least one example.
+    pub rules_tested: usize,
+    /// Examples checked across all rules.
+    pub examples_run: usize,
+    /// Every example a rule got wrong.
+    pub failures: Vec<AssertionFailure>,
+    /// UUIDs of rules with no examples at all.
+    pub untested: Vec<String>,
+    /// Rules that don't match their own `proto_text`. Warnings only: they
+    /// don't fail the report.
+    pub proto_mismatches: Vec<AssertionFailure>,
+}
+
+impl SelfTestReport {
+    /// No example failed. Untested rules don't count as failures.
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl LabelRule {
+    /// Check that the (compiled) rule matches its own `proto_text`, on its
+    /// own and without any box `preprocess` steps. A rule without a
+    /// `proto_text` passes.
+    pub fn validate_proto(&self) -> Result<(), AssertionFailure> {
+        if self.proto_text.is_empty() || self.rule.check(&self.proto_text) {
+            return Ok(());
+        }
+        Err(self.proto_failure())
+    }
+
+    fn proto_failure(&self) -> AssertionFailure {
+        AssertionFailure {
+            uuid: self.uuid.clone(),
+            label: self.label.clone(),
+            text: self.proto_text.clone(),
+            should_match: true,
+        }
+    }
+}
+
+impl RuleBox {
+    /// Compile every rule, then check each rule's inline examples, reporting
+    /// all failures at once. Examples are checked against the rule on its
+    /// own, whether or not it's active, after the box's `preprocess` steps.
+    pub fn compile_and_test(&mut self) -> Result<(), RuleBoxError> {
+        self.compile()?;
+        self.test_assertions()
+    }
+
+    /// `compile_and_test`, reusing regexes from `cache` as
+    /// `compile_with_cache` does.
+    pub fn compile_and_test_with_cache(&mut self, cache: &RegexCache) -> Result<(), RuleBoxError> {
+        self.compile_with_cache(cache)?;
+        self.test_assertions()
+    }
+
+    fn test_assertions(&self) -> Result<(), RuleBoxError> {
+        let failures = self.failed_assertions();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(RuleBoxError::Assertions(failures))
+        }
+    }
+
+    /// The inline examples that the (compiled) rules currently get wrong.
+    pub fn failed_assertions(&self) -> Vec<AssertionFailure> {
+        let mut failures = Vec::new();
+        for rule in &self.rules {
+            let examples = rule
+                .should_match
+                .iter()
+                .map(|text| (text, true))
+                .chain(rule.should_not_match.iter().map(|text| (text, false)));
+            for (text, should_match) in examples {
+                if rule.rule.check(&self.preprocessed(text)) != should_match {
+                    failures.push(AssertionFailure {
+                        uuid: rule.uuid.clone(),
+                        label: rule.label.clone(),
+                        text: text.clone(),
+                        should_match,
+                    });
+                }
+            }
+        }
+        failures
+    }
+
+    /// The rules, active or not, that don't match their own `proto_text`
+    /// after the box's `preprocess` steps. A rule that can't match its
+    /// example sentence is almost certainly broken, but unlike the inline
+    /// examples this doesn't stop the box loading; list the text under
+    /// `should_match` as well to make it do so.
+    pub fn proto_mismatches(&self) -> Vec<AssertionFailure> {
+        self.rules
+            .iter()
+            .filter(|rule| {
+                !rule.proto_text.is_empty()
+                    && !rule.rule.check(&self.preprocessed(&rule.proto_text))
+            })
+            .map(LabelRule::proto_failure)
+            .collect()
+    }
+
+    /// Check every rule's examples (`should_match`/`positive_examples` and
+    /// `should_not_match`/`negative_examples`) and report the failures along
+    /// with which rules have no examples, e.g. for a review gate. Rules that
+    /// don't match their `proto_text` are reported as warnings.
+    pub fn run_self_tests(&self) -> SelfTestReport {
+        let mut report = SelfTestReport {
+            failures: self.failed_assertions(),
+            proto_mismatches: self.proto_mismatches(),
+            ..Default::default()
+        };
+        for rule in &self.rules {
+            let examples = rule.should_match.len() + rule.should_not_match.len();
+            if examples == 0 {
+                report.untested.push(rule.uuid.clone());
+            } else {
+                report.rules_tested += 1;
+                report.examples_run += examples;
+            }
+        }
+        report
+    }
+}
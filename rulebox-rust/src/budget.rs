@@ -0,0 +1,130 @@
+// Time and size budgets for labelling untrusted text. The regex crate never
+// backtracks, but a huge text run through many patterns (or fuzzy ones) can
+// still take seconds; with a budget that text fails on its own, naming the
+// rule it was stuck on, and the rest of the batch carries on.
+//
+// The budget is cooperative: it's checked between rules, so a rule that's
+// already running finishes before the text is abandoned.
+
+use crate::{BudgetExceeded, LabelRule, RuleBox};
+use std::time::{Duration, Instant};
+
+/// Limits for `assign_labels_with_budget`. Unset limits aren't checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeBudget {
+    /// Texts longer than this many bytes are rejected before any rule runs.
+    pub max_text_bytes: Option<usize>,
+    /// Longest a single rule may take on one text.
+    pub per_rule: Option<Duration>,
+    /// Longest all the rules together may take on one text.
+    pub per_text: Option<Duration>,
+}
+
+impl TimeBudget {
+    pub fn with_max_text_bytes(mut self, limit: usize) -> Self {
+        self.max_text_bytes = Some(limit);
+        self
+    }
+
+    pub fn with_per_rule(mut self, limit: Duration) -> Self {
+        self.per_rule = Some(limit);
+        self
+    }
+
+    pub fn with_per_text(mut self, limit: Duration) -> Self {
+        self.per_text = Some(limit);
+        self
+    }
+}
+
+impl RuleBox {
+    /// Like `assign_labels`, but give up on `text` once it breaks `budget`,
+    /// reporting the rule that was running. `Instant` isn't available on
+    /// wasm32, so time limits can't be used there.
+    pub fn assign_labels_with_budget(
+        &self,
+        text: &str,
+        budget: &TimeBudget,
+    ) -> Result<Vec<String>, BudgetExceeded> {
+        let mut labels = Vec::new();
+        self.fill_labels_within(&self.labeling_rules(), text, budget, &mut labels)?;
+        Ok(labels)
+    }
+
+    /// `assign_labels_with_budget` for each text, in input order. A text that
+    /// breaks the budget gets an error and doesn't hold up the others.
+    pub fn assign_labels_vector_with_budget<S: AsRef<str>>(
+        &self,
+        texts: &[S],
+        budget: &TimeBudget,
+    ) -> Vec<Result<Vec<String>, BudgetExceeded>> {
+        let active_rules = self.labeling_rules();
+        texts
+            .iter()
+            .map(|text| {
+                let mut labels = Vec::new();
+                self.fill_labels_within(&active_rules, text.as_ref(), budget, &mut labels)?;
+                Ok(labels)
+            })
+            .collect()
+    }
+
+    fn fill_labels_within(
+        &self,
+        active_rules: &[(usize, &LabelRule)],
+        text: &str,
+        budget: &TimeBudget,
+        labels: &mut Vec<String>,
+    ) -> Result<(), BudgetExceeded> {
+        if let Some(limit) = budget.max_text_bytes {
+            if text.len() > limit {
+                return Err(BudgetExceeded::TextTooLong {
+                    len: text.len(),
+                    limit,
+                });
+            }
+        }
+        if budget.per_rule.is_none() && budget.per_text.is_none() {
+            self.fill_labels(active_rules, text, labels);
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        let mut exceeded = None;
+        self.fill_labels_with(active_rules, text, labels, |rule, text| {
+            // Once over budget, the remaining rules are skipped
+            if exceeded.is_some() {
+                return false;
+            }
+            let rule_start = Instant::now();
+            let matched = self.rule_matches(rule, text);
+            let elapsed = rule_start.elapsed();
+            if let Some(limit) = budget.per_rule.filter(|&limit| elapsed > limit) {
+                exceeded = Some(BudgetExceeded::RuleTooSlow {
+                    uuid: rule.uuid.clone(),
+                    label: rule.label.clone(),
+                    elapsed,
+                    limit,
+                });
+            } else if let Some(limit) = budget.per_text {
+                let elapsed = start.elapsed();
+                if elapsed > limit {
+                    exceeded = Some(BudgetExceeded::TextTooSlow {
+                        uuid: rule.uuid.clone(),
+                        label: rule.label.clone(),
+                        elapsed,
+                        limit,
+                    });
+                }
+            }
+            matched
+        });
+        match exceeded {
+            Some(e) => {
+                labels.clear();
+                Err(e)
+            }
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+// Bounding the structural complexity of untrusted patterns before compiling them.
+
+use crate::error::CompileError;
+use regex_syntax::ast::{self, Ast, RepetitionKind, RepetitionRange};
+
+/// Upper bounds on the shape of a pattern, counted over its parsed syntax tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComplexityLimits {
+    /// Total number of `|` alternatives across the pattern.
+    pub max_alternations: usize,
+    /// Number of repetition operators (`*`, `+`, `?`, `{m,n}`).
+    pub max_repetitions: usize,
+    /// Largest explicit count allowed in a `{m,n}` repetition.
+    pub max_repetition_bound: u32,
+    /// Number of groups, capturing or not.
+    pub max_groups: usize,
+    /// How deeply groups and repetitions may nest inside each other.
+    pub max_nesting_depth: usize,
+}
+
+impl Default for ComplexityLimits {
+    fn default() -> Self {
+        Self {
+            max_alternations: 100,
+            max_repetitions: 100,
+            max_repetition_bound: 1000,
+            max_groups: 100,
+            max_nesting_depth: 16,
+        }
+    }
+}
+
+/// Parse `pattern` and reject it if it exceeds any of `limits`.
+pub fn validate_pattern_complexity(
+    pattern: &str,
+    limits: &ComplexityLimits,
+) -> Result<(), CompileError> {
+    let parsed = ast::parse::Parser::new()
+        .parse(pattern)
+        .map_err(|e| CompileError::Regex {
+            pattern: pattern.to_string(),
+            source: regex::Error::Syntax(e.to_string()),
+        })?;
+    let counter = ComplexityCounter {
+        limits,
+        alternations: 0,
+        repetitions: 0,
+        groups: 0,
+        depth: 0,
+    };
+    ast::visit(&parsed, counter).map_err(|reason| CompileError::TooComplex {
+        pattern: pattern.to_string(),
+        reason,
+    })
+}
+
+struct ComplexityCounter<'a> {
+    limits: &'a ComplexityLimits,
+    alternations: usize,
+    repetitions: usize,
+    groups: usize,
+    depth: usize,
+}
+
+fn over(what: &str, count: usize, limit: usize) -> Result<(), String> {
+    if count > limit {
+        Err(format!("{} {} exceeds the limit of {}", count, what, limit))
+    } else {
+        Ok(())
+    }
+}
+
+impl ast::Visitor for ComplexityCounter<'_> {
+    type Output = ();
+    type Err = String;
+
+    fn finish(self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn visit_pre(&mut self, ast: &Ast) -> Result<(), String> {
+        match ast {
+            Ast::Alternation(alt) => {
+                self.alternations += alt.asts.len().saturating_sub(1);
+                over(
+                    "alternations",
+                    self.alternations,
+                    self.limits.max_alternations,
+                )
+            }
+            Ast::Repetition(rep) => {
+                self.repetitions += 1;
+                over("repetitions", self.repetitions, self.limits.max_repetitions)?;
+                let bound = match rep.op.kind {
+                    RepetitionKind::Range(RepetitionRange::Exactly(n))
+                    | RepetitionKind::Range(RepetitionRange::AtLeast(n)) => n,
+                    RepetitionKind::Range(RepetitionRange::Bounded(m, n)) => m.max(n),
+                    _ => 0,
+                };
+                if bound > self.limits.max_repetition_bound {
+                    return Err(format!(
+                        "repetition bound {} exceeds the limit of {}",
+                        bound, self.limits.max_repetition_bound
+                    ));
+                }
+                self.enter()
+            }
+            Ast::Group(_) => {
+                self.groups += 1;
+                over("groups", self.groups, self.limits.max_groups)?;
+                self.enter()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn visit_post(&mut self, ast: &Ast) -> Result<(), String> {
+        if matches!(ast, Ast::Repetition(_) | Ast::Group(_)) {
+            self.depth -= 1;
+        }
+        Ok(())
+    }
+}
+
+impl ComplexityCounter<'_> {
+    fn enter(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        over(
+            "levels of nesting",
+            self.depth,
+            self.limits.max_nesting_depth,
+        )
+    }
+}
@@ -0,0 +1,125 @@
+// Corpus-level analysis built on top of `check_many`.
+
+use crate::RuleBox;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How many texts one rule matched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleCoverage {
+    pub uuid: String,
+    pub label: String,
+    pub matches: usize,
+}
+
+/// Result of `RuleBox::coverage`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub texts: usize,
+    /// Every active rule (shadow rules included), in box order.
+    pub rules: Vec<RuleCoverage>,
+    /// UUIDs of the rules that matched none of the texts, in box order.
+    pub never_fired: Vec<String>,
+    /// UUID pairs of rules that matched exactly the same texts (and at least
+    /// one), so one of each pair may be redundant. Pairs are in box order.
+    pub always_together: Vec<(String, String)>,
+}
+
+impl RuleBox {
+    /// Run every active rule over `texts` and report how often each fired,
+    /// which never did and which always fired together. Rules are judged on
+    /// their own patterns, before groups, thresholds and implied labels.
+    pub fn coverage(&self, texts: &[String]) -> CoverageReport {
+        let rules: Vec<_> = self.rules.iter().filter(|rule| rule.is_active()).collect();
+        let mut matched: Vec<Vec<usize>> = vec![Vec::new(); rules.len()];
+        for (i, text) in texts.iter().enumerate() {
+            let text = &*self.preprocessed(text);
+            for (rule, matched) in rules.iter().zip(matched.iter_mut()) {
+                if rule.rule.check(text) {
+                    matched.push(i);
+                }
+            }
+        }
+
+        // Rules with identical match sets, keyed by the set
+        let mut same_texts: HashMap<&[usize], Vec<usize>> = HashMap::new();
+        for (r, texts) in matched.iter().enumerate() {
+            if !texts.is_empty() {
+                same_texts.entry(texts).or_default().push(r);
+            }
+        }
+        let mut pairs: Vec<(usize, usize)> = same_texts
+            .values()
+            .flat_map(|group| {
+                group
+                    .iter()
+                    .enumerate()
+                    .flat_map(move |(i, &a)| group[i + 1..].iter().map(move |&b| (a, b)))
+            })
+            .collect();
+        pairs.sort_unstable();
+
+        CoverageReport {
+            texts: texts.len(),
+            rules: rules
+                .iter()
+                .zip(&matched)
+                .map(|(rule, matched)| RuleCoverage {
+                    uuid: rule.uuid.clone(),
+                    label: rule.label.clone(),
+                    matches: matched.len(),
+                })
+                .collect(),
+            never_fired: rules
+                .iter()
+                .zip(&matched)
+                .filter(|(_, matched)| matched.is_empty())
+                .map(|(rule, _)| rule.uuid.clone())
+                .collect(),
+            always_together: pairs
+                .into_iter()
+                .map(|(a, b)| (rules[a].uuid.clone(), rules[b].uuid.clone()))
+                .collect(),
+        }
+    }
+
+    /// How many texts each unordered pair of labels appears on together.
+    /// Keys are ordered so the smaller label comes first.
+    pub fn cooccurrence(&self, texts: &[String]) -> HashMap<(String, String), usize> {
+        let mut counts = HashMap::new();
+        for labels in self.check_many(texts) {
+            let mut labels: Vec<String> = labels.into_iter().collect();
+            labels.sort();
+            for (i, first) in labels.iter().enumerate() {
+                for second in &labels[i + 1..] {
+                    *counts.entry((first.clone(), second.clone())).or_insert(0) += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Count `(gold, predicted)` label pairs over `(text, gold_label)`
+    /// examples, predicting with `classify`. Texts with no prediction are
+    /// counted under `"none"`.
+    pub fn confusion_matrix(&self, gold: &[(String, String)]) -> HashMap<(String, String), usize> {
+        self.confusion_matrix_with_none(gold, "none")
+    }
+
+    /// `confusion_matrix` with a custom label for texts `classify` leaves
+    /// unlabeled.
+    pub fn confusion_matrix_with_none(
+        &self,
+        gold: &[(String, String)],
+        none_label: &str,
+    ) -> HashMap<(String, String), usize> {
+        let mut counts = HashMap::new();
+        for (text, gold_label) in gold {
+            let predicted = self
+                .classify(text)
+                .unwrap_or_else(|| none_label.to_string());
+            *counts.entry((gold_label.clone(), predicted)).or_insert(0) += 1;
+        }
+        counts
+    }
+}
@@ -0,0 +1,76 @@
+// Post-pass rules that derive a label from how many distinct labels the
+// pattern rules assigned.
+
+use crate::RuleBox;
+use serde::{Deserialize, Serialize};
+
+/// Assign `then` when at least `if_labels_count_at_least` distinct labels
+/// matching the `among` globs were assigned. An empty `among` counts every
+/// label.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CountRule {
+    pub if_labels_count_at_least: usize,
+    #[serde(default)]
+    pub among: Vec<String>,
+    pub then: String,
+}
+
+impl CountRule {
+    fn counts(&self, label: &str) -> bool {
+        self.among.is_empty() || self.among.iter().any(|glob| glob_match(glob, label))
+    }
+}
+
+impl RuleBox {
+    /// Labels added by count rules, given the labels from the pattern rules.
+    /// Count rules only see pattern labels, never each other's output.
+    pub(crate) fn count_rule_labels<'a, I>(&self, labels: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = &'a String> + Clone,
+    {
+        self.count_rules
+            .iter()
+            .filter(|rule| {
+                let count = labels
+                    .clone()
+                    .into_iter()
+                    .filter(|l| rule.counts(l))
+                    .count();
+                count >= rule.if_labels_count_at_least
+            })
+            .map(|rule| rule.then.clone())
+            .collect()
+    }
+}
+
+/// Match `text` against a glob where `*` is any run of characters and `?` is
+/// any single character.
+fn glob_match(glob: &str, text: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut g, mut t) = (0, 0);
+    // Position of the last `*` and the text position it was tried against
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match glob.get(g) {
+            Some('*') => {
+                backtrack = Some((g, t));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                g += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, start)) => {
+                    g = star + 1;
+                    t = start + 1;
+                    backtrack = Some((star, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,95 @@
+// Dry-run comparison of two versions of a rulebox over a sample of texts, for
+// reviewing what a rule change would do before it's deployed.
+
+use crate::RuleBox;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The labels one text gains and loses going from the old box to the new.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextDiff {
+    /// Position of the text in the input.
+    pub index: usize,
+    pub text: String,
+    /// Labels only the new box assigns, sorted.
+    pub gained: Vec<String>,
+    /// Labels only the old box assigns, sorted.
+    pub lost: Vec<String>,
+}
+
+/// How many texts gain and lose a label.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelChange {
+    pub gained: usize,
+    pub lost: usize,
+}
+
+/// Result of `RuleBox::diff_labels`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelDiffReport {
+    pub texts_compared: usize,
+    /// Only the texts whose labels changed, in input order.
+    pub changes: Vec<TextDiff>,
+}
+
+impl LabelDiffReport {
+    /// Whether the two boxes label every text the same.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Gains and losses per label, across all the texts.
+    pub fn by_label(&self) -> BTreeMap<String, LabelChange> {
+        let mut counts: BTreeMap<String, LabelChange> = BTreeMap::new();
+        for change in &self.changes {
+            for label in &change.gained {
+                counts.entry(label.clone()).or_default().gained += 1;
+            }
+            for label in &change.lost {
+                counts.entry(label.clone()).or_default().lost += 1;
+            }
+        }
+        counts
+    }
+}
+
+impl RuleBox {
+    /// Label `texts` with this box (the old version) and `other` (the new
+    /// one) and report which texts gain or lose which labels.
+    pub fn diff_labels(&self, other: &RuleBox, texts: &[String]) -> LabelDiffReport {
+        let before = self.assign_labels_vector(texts);
+        let after = other.assign_labels_vector(texts);
+        let changes = texts
+            .iter()
+            .zip(before.iter().zip(&after))
+            .enumerate()
+            .filter_map(|(index, (text, (before, after)))| {
+                let mut gained: Vec<String> = after
+                    .iter()
+                    .filter(|l| !before.contains(l))
+                    .cloned()
+                    .collect();
+                let mut lost: Vec<String> = before
+                    .iter()
+                    .filter(|l| !after.contains(l))
+                    .cloned()
+                    .collect();
+                if gained.is_empty() && lost.is_empty() {
+                    return None;
+                }
+                gained.sort();
+                lost.sort();
+                Some(TextDiff {
+                    index,
+                    text: text.clone(),
+                    gained,
+                    lost,
+                })
+            })
+            .collect();
+        LabelDiffReport {
+            texts_compared: texts.len(),
+            changes,
+        }
+    }
+}
@@ -0,0 +1,226 @@
+// On-disk format for a RuleBox. A file is either a bare array of rules or, when
+// box-level settings are needed, an object with a `rules` key alongside them.
+
+#[cfg(feature = "fs")]
+use crate::format::Format;
+use crate::{CountRule, LabelRule, Preprocess, RegexLimits, RuleBox};
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+#[cfg(feature = "fs")]
+use std::collections::HashSet;
+use std::fmt;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Deserialize)]
+pub(crate) struct RuleBoxDocument {
+    rules: Vec<LabelRule>,
+    #[serde(default)]
+    default_flags: Vec<String>,
+    #[serde(default)]
+    flag_presets: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    definitions: BTreeMap<String, String>,
+    #[serde(default)]
+    allow_negative_scores: bool,
+    #[serde(default)]
+    score_thresholds: BTreeMap<String, f64>,
+    #[serde(default)]
+    implies: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    preprocess: Preprocess,
+    #[serde(default)]
+    regex_limits: RegexLimits,
+    #[serde(default)]
+    count_rules: Vec<CountRule>,
+    #[serde(default)]
+    shadow_inactive: bool,
+}
+
+#[derive(Serialize)]
+struct RuleBoxDocumentRef<'a> {
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    default_flags: &'a [String],
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    flag_presets: &'a BTreeMap<String, Vec<String>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    definitions: &'a BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "is_false")]
+    allow_negative_scores: bool,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    score_thresholds: &'a BTreeMap<String, f64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    implies: &'a BTreeMap<String, Vec<String>>,
+    #[serde(skip_serializing_if = "Preprocess::is_empty")]
+    preprocess: &'a Preprocess,
+    #[serde(skip_serializing_if = "RegexLimits::is_empty")]
+    regex_limits: &'a RegexLimits,
+    rules: &'a [LabelRule],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    count_rules: &'a [CountRule],
+    #[serde(skip_serializing_if = "is_false")]
+    shadow_inactive: bool,
+}
+
+impl RuleBoxDocumentRef<'_> {
+    fn has_settings(&self) -> bool {
+        !self.default_flags.is_empty()
+            || !self.flag_presets.is_empty()
+            || !self.definitions.is_empty()
+            || self.allow_negative_scores
+            || !self.score_thresholds.is_empty()
+            || !self.implies.is_empty()
+            || !self.preprocess.is_empty()
+            || !self.regex_limits.is_empty()
+            || !self.count_rules.is_empty()
+            || self.shadow_inactive
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl From<RuleBoxDocument> for RuleBox {
+    fn from(doc: RuleBoxDocument) -> Self {
+        let mut rulebox = RuleBox::new(doc.rules);
+        rulebox.default_flags = doc.default_flags;
+        rulebox.flag_presets = doc.flag_presets;
+        rulebox.definitions = doc.definitions;
+        rulebox.allow_negative_scores = doc.allow_negative_scores;
+        rulebox.score_thresholds = doc.score_thresholds;
+        rulebox.implies = doc.implies;
+        rulebox.preprocess = doc.preprocess;
+        rulebox.regex_limits = doc.regex_limits;
+        rulebox.count_rules = doc.count_rules;
+        rulebox.shadow_inactive = doc.shadow_inactive;
+        rulebox
+    }
+}
+
+impl Serialize for RuleBox {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let doc = RuleBoxDocumentRef {
+            default_flags: &self.default_flags,
+            flag_presets: &self.flag_presets,
+            definitions: &self.definitions,
+            allow_negative_scores: self.allow_negative_scores,
+            score_thresholds: &self.score_thresholds,
+            implies: &self.implies,
+            preprocess: &self.preprocess,
+            regex_limits: &self.regex_limits,
+            rules: &self.rules,
+            count_rules: &self.count_rules,
+            shadow_inactive: self.shadow_inactive,
+        };
+        // Keep plain rule lists in the original array format
+        if doc.has_settings() {
+            doc.serialize(serializer)
+        } else {
+            doc.rules.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RuleBox {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(RuleBoxVisitor)
+    }
+}
+
+struct RuleBoxVisitor;
+
+impl<'de> Visitor<'de> for RuleBoxVisitor {
+    type Value = RuleBox;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of rules or an object with a `rules` array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<RuleBox, A::Error> {
+        let rules = Vec::<LabelRule>::deserialize(de::value::SeqAccessDeserializer::new(seq))?;
+        Ok(RuleBox::new(rules))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<RuleBox, A::Error> {
+        let doc = RuleBoxDocument::deserialize(de::value::MapAccessDeserializer::new(map))?;
+        Ok(doc.into())
+    }
+}
+
+impl RuleBox {
+    /// The box as pretty-printed JSON that `from_json` reads back to the same
+    /// rules: UUIDs and flags are kept as they are, fields are written in
+    /// declaration order, and a box without settings stays a bare array.
+    pub fn to_json_string(&self) -> Result<String, crate::RuleBoxError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Write the box to a JSON file in the `to_json_string` layout.
+    #[cfg(feature = "fs")]
+    pub fn to_path(&self, path: &str) -> Result<(), crate::RuleBoxError> {
+        require_json(path, "written")?;
+        let mut json = self.to_json_string()?;
+        json.push('\n');
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Rewrite a rule file in canonical form for stable diffs: rules sorted
+    /// by label then UUID, flags sorted and deduplicated, object keys in
+    /// sorted order and two-space indentation. Rule order breaks ties between
+    /// rules in a group or with the same priority (in `classify`, groups and
+    /// `check_topk`), so a box with either keeps its rules in file order.
+    ///
+    /// JSON files only: rewriting YAML or TOML would drop their comments.
+    #[cfg(feature = "fs")]
+    pub fn canonicalize_file(path: &str) -> Result<(), crate::RuleBoxError> {
+        require_json(path, "canonicalized")?;
+        let mut rulebox = RuleBox::from_path(path)?;
+        if !order_matters(&rulebox.rules) {
+            rulebox
+                .rules
+                .sort_by(|a, b| (&a.label, &a.uuid).cmp(&(&b.label, &b.uuid)));
+        }
+        normalize_flags(&mut rulebox.default_flags);
+        for rule in &mut rulebox.rules {
+            for pattern in rule.rule.patterns_mut() {
+                normalize_flags(&mut pattern.flags);
+            }
+        }
+        // Going through a Value sorts object keys
+        let value = serde_json::to_value(&rulebox)?;
+        let mut json = serde_json::to_string_pretty(&value)?;
+        json.push('\n');
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+// Only JSON is written back: YAML and TOML files would lose their comments
+#[cfg(feature = "fs")]
+fn require_json(path: &str, action: &str) -> Result<(), crate::RuleBoxError> {
+    if Format::from_path(Path::new(path)) != Format::Json {
+        let message = format!("only JSON rule files can be {}", action);
+        return Err(std::io::Error::new(std::io::ErrorKind::Unsupported, message).into());
+    }
+    Ok(())
+}
+
+// Whether reordering the rules could change a result: rule order only breaks
+// ties, between rules in a group or with the same priority
+#[cfg(feature = "fs")]
+fn order_matters(rules: &[LabelRule]) -> bool {
+    let mut priorities = HashSet::new();
+    rules
+        .iter()
+        .any(|rule| rule.group.is_some() || !priorities.insert(rule.priority))
+}
+
+#[cfg(feature = "fs")]
+fn normalize_flags(flags: &mut Vec<String>) {
+    flags.sort();
+    flags.dedup();
+}
@@ -0,0 +1,437 @@
+use crate::FieldType;
+use std::fmt;
+use std::io;
+use std::time::Duration;
+
+/// Why a pattern, rule or box failed to compile.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompileError {
+    /// The pattern exceeds a `ComplexityLimits` bound.
+    TooComplex {
+        pattern: String,
+        reason: String,
+    },
+    /// The pattern isn't valid regex syntax, or the regex crate rejected it
+    /// for another reason. `validate_pattern_complexity` reports syntax
+    /// errors here too, as `regex::Error::Syntax`.
+    Regex {
+        pattern: String,
+        source: regex::Error,
+    },
+    UnknownFlag {
+        pattern: String,
+        flag: String,
+    },
+    /// Flags that contradict each other, e.g. `a` (ASCII) with `u` (Unicode).
+    ConflictingFlags {
+        pattern: String,
+        flags: String,
+    },
+    /// A flag `@name` with no matching entry in `flag_presets`.
+    UnknownFlagPreset {
+        pattern: String,
+        preset: String,
+    },
+    /// A `{{NAME}}` reference with no matching entry in `definitions`.
+    UnknownDefinition {
+        pattern: String,
+        name: String,
+    },
+    /// A fuzzy pattern that can't be used, e.g. one that would match anything.
+    InvalidFuzzy {
+        pattern: String,
+        reason: String,
+    },
+    /// The compiled pattern is bigger than the `regex_limits` size limit
+    /// (or the regex crate's default), in bytes.
+    SizeLimitExceeded {
+        pattern: String,
+        limit: usize,
+    },
+    /// A `keyword_patterns` list that can't be built, e.g. with an empty
+    /// keyword.
+    InvalidKeywords {
+        reason: String,
+    },
+    /// A `preprocess` setting this build can't apply, e.g. `nfkc` without
+    /// the `unicode` feature.
+    InvalidPreprocess {
+        reason: String,
+    },
+    /// A rule with both `and_patterns` and `or_patterns` (or keywords).
+    MixedAndOr,
+    /// A rule with `not_patterns` and nothing for them to veto.
+    OnlyNotPatterns,
+    /// A rule in stage 0 with `requires_labels` or `excludes_labels`, which
+    /// only see the labels of earlier stages.
+    LabelConditionInFirstStage,
+    /// A rule whose `active_until` isn't after its `active_from`, so it could
+    /// never be active.
+    EmptyActiveWindow,
+    /// Any of the above, in the rule with this UUID.
+    InRule {
+        uuid: String,
+        source: Box<CompileError>,
+    },
+}
+
+impl CompileError {
+    /// The UUID of the rule that failed, when it's known.
+    pub fn uuid(&self) -> Option<&str> {
+        match self {
+            CompileError::InRule { uuid, .. } => Some(uuid),
+            _ => None,
+        }
+    }
+
+    /// The pattern that failed, for errors about a single pattern.
+    pub fn pattern(&self) -> Option<&str> {
+        match self.root() {
+            CompileError::TooComplex { pattern, .. }
+            | CompileError::Regex { pattern, .. }
+            | CompileError::UnknownFlag { pattern, .. }
+            | CompileError::ConflictingFlags { pattern, .. }
+            | CompileError::UnknownFlagPreset { pattern, .. }
+            | CompileError::UnknownDefinition { pattern, .. }
+            | CompileError::InvalidFuzzy { pattern, .. }
+            | CompileError::SizeLimitExceeded { pattern, .. } => Some(pattern),
+            _ => None,
+        }
+    }
+
+    /// The error without the rule it happened in.
+    pub fn root(&self) -> &CompileError {
+        match self {
+            CompileError::InRule { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    pub(crate) fn in_rule(self, uuid: &str) -> Self {
+        CompileError::InRule {
+            uuid: uuid.to_string(),
+            source: Box::new(self),
+        }
+    }
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::TooComplex { pattern, reason } => {
+                write!(f, "Pattern '{}' is too complex: {}", pattern, reason)
+            }
+            CompileError::Regex { pattern, source } => {
+                write!(f, "Invalid regex '{}': {}", pattern, source)
+            }
+            CompileError::UnknownFlag { pattern, flag } => {
+                write!(f, "Unknown regex flag: {} (in '{}')", flag, pattern)
+            }
+            CompileError::ConflictingFlags { pattern, flags } => {
+                write!(f, "Conflicting regex flags: {} (in '{}')", flags, pattern)
+            }
+            CompileError::UnknownFlagPreset { pattern, preset } => {
+                write!(f, "Unknown flag preset: {} (in '{}')", preset, pattern)
+            }
+            CompileError::UnknownDefinition { pattern, name } => {
+                write!(f, "Unknown definition: {{{{{}}}}} (in '{}')", name, pattern)
+            }
+            CompileError::InvalidFuzzy { pattern, reason } => {
+                write!(f, "Fuzzy pattern '{}' {}", pattern, reason)
+            }
+            CompileError::SizeLimitExceeded { pattern, limit } => write!(
+                f,
+                "Pattern '{}' compiles to more than the size limit of {} bytes",
+                pattern, limit
+            ),
+            CompileError::InvalidKeywords { reason } => {
+                write!(f, "Invalid keyword_patterns: {}", reason)
+            }
+            CompileError::InvalidPreprocess { reason } => {
+                write!(f, "Invalid preprocess: {}", reason)
+            }
+            CompileError::MixedAndOr => {
+                write!(f, "Rule can't have both and_patterns and or_patterns")
+            }
+            CompileError::OnlyNotPatterns => {
+                write!(f, "Rule cannot consist solely of not_patterns")
+            }
+            CompileError::LabelConditionInFirstStage => write!(
+                f,
+                "requires_labels and excludes_labels need a stage after 0 to see earlier labels"
+            ),
+            CompileError::EmptyActiveWindow => {
+                write!(f, "active_until must be after active_from")
+            }
+            CompileError::InRule { uuid, source } => write!(f, "rule {}: {}", uuid, source),
+        }
+    }
+}
+
+impl std::error::Error for CompileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CompileError::Regex { source, .. } => Some(source),
+            CompileError::InRule { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// A `should_match` or `should_not_match` example, or a `proto_text`, that a
+/// rule got wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionFailure {
+    pub uuid: String,
+    pub label: String,
+    pub text: String,
+    /// Whether the rule was expected to match `text`.
+    pub should_match: bool,
+}
+
+impl fmt::Display for AssertionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let expected = if self.should_match {
+            "match"
+        } else {
+            "not match"
+        };
+        write!(
+            f,
+            "rule {} ({}) should {} {:?}",
+            self.uuid, self.label, expected, self.text
+        )
+    }
+}
+
+/// Why `RuleBox::assign_labels_with_budget` gave up on a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetExceeded {
+    /// The text is longer than `max_text_bytes`; no rule was evaluated.
+    TextTooLong { len: usize, limit: usize },
+    /// This rule alone took longer than `per_rule`.
+    RuleTooSlow {
+        uuid: String,
+        label: String,
+        elapsed: Duration,
+        limit: Duration,
+    },
+    /// The text's total time passed `per_text` while evaluating this rule.
+    TextTooSlow {
+        uuid: String,
+        label: String,
+        elapsed: Duration,
+        limit: Duration,
+    },
+}
+
+impl BudgetExceeded {
+    /// The UUID of the rule that ran over, if a rule did.
+    pub fn uuid(&self) -> Option<&str> {
+        match self {
+            BudgetExceeded::TextTooLong { .. } => None,
+            BudgetExceeded::RuleTooSlow { uuid, .. } | BudgetExceeded::TextTooSlow { uuid, .. } => {
+                Some(uuid)
+            }
+        }
+    }
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetExceeded::TextTooLong { len, limit } => {
+                write!(f, "Text is {} bytes, over the limit of {}", len, limit)
+            }
+            BudgetExceeded::RuleTooSlow {
+                uuid,
+                label,
+                elapsed,
+                limit,
+            } => write!(
+                f,
+                "rule {} ({}) took {:?}, over the per-rule budget of {:?}",
+                uuid, label, elapsed, limit
+            ),
+            BudgetExceeded::TextTooSlow {
+                uuid,
+                label,
+                elapsed,
+                limit,
+            } => write!(
+                f,
+                "text took {:?}, over the budget of {:?}, at rule {} ({})",
+                elapsed, limit, uuid, label
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// A field in a rule file that strict loading rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaProblem {
+    /// Where the problem is, e.g. `$.rules[0].rule.or_pattern`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Why a rulebox couldn't be loaded.
+#[derive(Debug)]
+pub enum RuleBoxError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    /// A line of JSONL input that couldn't be read, numbered from 1.
+    InvalidLine {
+        line: usize,
+        source: serde_json::Error,
+    },
+    #[cfg(feature = "yaml")]
+    Yaml(serde_yaml::Error),
+    #[cfg(feature = "toml")]
+    Toml(toml::de::Error),
+    Compile(CompileError),
+    /// Every inline rule example that failed.
+    Assertions(Vec<AssertionFailure>),
+    /// Every unknown field found by a strict load, followed by the error that
+    /// stopped parsing, if any.
+    Schema(Vec<SchemaProblem>),
+    /// Two boxes that can't be merged, e.g. with different `preprocess`
+    /// steps or a rule UUID in both.
+    MergeConflict(String),
+    /// An Arrow batch that can't be labelled, e.g. without the text column.
+    #[cfg(feature = "arrow")]
+    Arrow(arrow_schema::ArrowError),
+    #[cfg(feature = "parquet")]
+    Parquet(parquet::errors::ParquetError),
+}
+
+impl fmt::Display for RuleBoxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleBoxError::Io(e) => write!(f, "{}", e),
+            RuleBoxError::Parse(e) => write!(f, "{}", e),
+            RuleBoxError::InvalidLine { line, source } => write!(f, "line {}: {}", line, source),
+            #[cfg(feature = "yaml")]
+            RuleBoxError::Yaml(e) => write!(f, "{}", e),
+            #[cfg(feature = "toml")]
+            RuleBoxError::Toml(e) => write!(f, "{}", e),
+            RuleBoxError::Compile(e) => write!(f, "{}", e),
+            RuleBoxError::Assertions(failures) => {
+                write!(f, "{} rule example(s) failed", failures.len())?;
+                for failure in failures {
+                    write!(f, "\n  {}", failure)?;
+                }
+                Ok(())
+            }
+            RuleBoxError::Schema(problems) => {
+                write!(f, "{} schema problem(s)", problems.len())?;
+                for problem in problems {
+                    write!(f, "\n  {}", problem)?;
+                }
+                Ok(())
+            }
+            RuleBoxError::MergeConflict(reason) => write!(f, "Can't merge rules: {}", reason),
+            #[cfg(feature = "arrow")]
+            RuleBoxError::Arrow(e) => write!(f, "{}", e),
+            #[cfg(feature = "parquet")]
+            RuleBoxError::Parquet(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RuleBoxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RuleBoxError::Io(e) => Some(e),
+            RuleBoxError::Parse(e) => Some(e),
+            RuleBoxError::InvalidLine { source, .. } => Some(source),
+            #[cfg(feature = "yaml")]
+            RuleBoxError::Yaml(e) => Some(e),
+            #[cfg(feature = "toml")]
+            RuleBoxError::Toml(e) => Some(e),
+            RuleBoxError::Compile(e) => Some(e),
+            #[cfg(feature = "arrow")]
+            RuleBoxError::Arrow(e) => Some(e),
+            #[cfg(feature = "parquet")]
+            RuleBoxError::Parquet(e) => Some(e),
+            RuleBoxError::Assertions(_)
+            | RuleBoxError::Schema(_)
+            | RuleBoxError::MergeConflict(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for RuleBoxError {
+    fn from(e: io::Error) -> Self {
+        RuleBoxError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for RuleBoxError {
+    fn from(e: serde_json::Error) -> Self {
+        RuleBoxError::Parse(e)
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for RuleBoxError {
+    fn from(e: serde_yaml::Error) -> Self {
+        RuleBoxError::Yaml(e)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::de::Error> for RuleBoxError {
+    fn from(e: toml::de::Error) -> Self {
+        RuleBoxError::Toml(e)
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<arrow_schema::ArrowError> for RuleBoxError {
+    fn from(e: arrow_schema::ArrowError) -> Self {
+        RuleBoxError::Arrow(e)
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for RuleBoxError {
+    fn from(e: parquet::errors::ParquetError) -> Self {
+        RuleBoxError::Parquet(e)
+    }
+}
+
+impl From<CompileError> for RuleBoxError {
+    fn from(e: CompileError) -> Self {
+        RuleBoxError::Compile(e)
+    }
+}
+
+/// A captured group that didn't parse as the type its rule's schema declares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractError {
+    pub uuid: String,
+    pub field: String,
+    pub value: String,
+    pub expected: FieldType,
+}
+
+impl fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rule {}: field '{}' value {:?} is not a valid {:?}",
+            self.uuid, self.field, self.value, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ExtractError {}
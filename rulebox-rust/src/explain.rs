@@ -0,0 +1,78 @@
+// Per-rule explanations of a check, for working out why a text did or didn't
+// get a label.
+
+use crate::normalize::NormalizedText;
+use crate::{RegexRule, RuleBox, RuleOutcome};
+
+/// Whether one pattern matched the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternCheck {
+    pub pattern: String,
+    pub matched: bool,
+}
+
+/// How one rule fared against a text, pattern by pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelExplanation {
+    pub label: String,
+    pub uuid: String,
+    pub outcome: RuleOutcome,
+    pub and_patterns: Vec<PatternCheck>,
+    pub or_patterns: Vec<PatternCheck>,
+    /// Whether each of the rule's `near_patterns` found its two patterns
+    /// close enough together.
+    pub near_patterns: Vec<bool>,
+    /// A matching entry here is what blocked a `Vetoed` rule.
+    pub not_patterns: Vec<PatternCheck>,
+    /// Whether any of the rule's `keyword_patterns` matched, if it has them.
+    pub keywords: Option<bool>,
+    /// Whether the rule's `expr` held, if it has one.
+    pub expr: Option<bool>,
+}
+
+impl RuleBox {
+    /// Explain every rule that can assign a label (active and not in shadow
+    /// mode), in rule order, whether or not it matched.
+    pub fn explain(&self, text: &str) -> Vec<LabelExplanation> {
+        let text = &*self.preprocessed(text);
+        self.rules
+            .iter()
+            .filter(|rule| rule.assigns_labels())
+            .map(|rule| {
+                let normalized;
+                let text = if rule.rule.digit_normalize {
+                    normalized = NormalizedText::digits(text);
+                    normalized.text.as_str()
+                } else {
+                    text
+                };
+                LabelExplanation {
+                    label: rule.label.clone(),
+                    uuid: rule.uuid.clone(),
+                    outcome: rule.rule.evaluate_patterns(text),
+                    and_patterns: check_each(&rule.rule.and_patterns, text),
+                    or_patterns: check_each(&rule.rule.or_patterns, text),
+                    near_patterns: rule
+                        .rule
+                        .near_patterns
+                        .iter()
+                        .map(|n| n.check(text))
+                        .collect(),
+                    not_patterns: check_each(&rule.rule.not_patterns, text),
+                    keywords: rule.rule.keyword_patterns.as_ref().map(|k| k.check(text)),
+                    expr: rule.rule.expr.as_ref().map(|e| e.eval(&|p| p.check(text))),
+                }
+            })
+            .collect()
+    }
+}
+
+fn check_each(patterns: &[RegexRule], text: &str) -> Vec<PatternCheck> {
+    patterns
+        .iter()
+        .map(|p| PatternCheck {
+            pattern: p.pattern.clone(),
+            matched: p.check(text),
+        })
+        .collect()
+}
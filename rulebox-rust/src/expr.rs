@@ -0,0 +1,78 @@
+// Nested boolean rule expressions, for conditions like (A AND B) OR (C AND NOT D)
+// that the flat and/or/not lists can't express in a single rule.
+
+use crate::{CompileContext, CompileError, RegexRule};
+use serde::{Deserialize, Serialize};
+
+/// A boolean expression over patterns. In JSON each node is a single-key
+/// object: `{"all": [...]}`, `{"any": [...]}`, `{"not": {...}}` or
+/// `{"pattern": {"pattern": "...", "flags": [...]}}`.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Expr {
+    /// True when every child is true (or there are none).
+    All(Vec<Expr>),
+    /// True when at least one child is true.
+    Any(Vec<Expr>),
+    Not(Box<Expr>),
+    Pattern(RegexRule),
+}
+
+impl Expr {
+    pub(crate) fn compile_with(&mut self, ctx: &CompileContext) -> Result<(), CompileError> {
+        match self {
+            Expr::All(children) | Expr::Any(children) => {
+                for child in children {
+                    child.compile_with(ctx)?;
+                }
+                Ok(())
+            }
+            Expr::Not(child) => child.compile_with(ctx),
+            Expr::Pattern(pattern) => pattern.compile_with(ctx),
+        }
+    }
+
+    /// Evaluate with `check` deciding whether each pattern matches.
+    pub(crate) fn eval(&self, check: &dyn Fn(&RegexRule) -> bool) -> bool {
+        match self {
+            Expr::All(children) => children.iter().all(|c| c.eval(check)),
+            Expr::Any(children) => children.iter().any(|c| c.eval(check)),
+            Expr::Not(child) => !child.eval(check),
+            Expr::Pattern(pattern) => check(pattern),
+        }
+    }
+
+    /// Every pattern in the tree.
+    pub(crate) fn patterns<'a>(&'a self, out: &mut Vec<&'a RegexRule>) {
+        match self {
+            Expr::All(children) | Expr::Any(children) => {
+                children.iter().for_each(|c| c.patterns(out));
+            }
+            Expr::Not(child) => child.patterns(out),
+            Expr::Pattern(pattern) => out.push(pattern),
+        }
+    }
+
+    pub(crate) fn patterns_mut<'a>(&'a mut self, out: &mut Vec<&'a mut RegexRule>) {
+        match self {
+            Expr::All(children) | Expr::Any(children) => {
+                children.iter_mut().for_each(|c| c.patterns_mut(out));
+            }
+            Expr::Not(child) => child.patterns_mut(out),
+            Expr::Pattern(pattern) => out.push(pattern),
+        }
+    }
+
+    /// The patterns that aren't under a `not`, i.e. the ones whose matches
+    /// count as evidence for the rule.
+    pub(crate) fn positive_patterns<'a>(&'a self, out: &mut Vec<&'a RegexRule>) {
+        match self {
+            Expr::All(children) | Expr::Any(children) => {
+                children.iter().for_each(|c| c.positive_patterns(out));
+            }
+            Expr::Not(_) => {}
+            Expr::Pattern(pattern) => out.push(pattern),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+// Typed extraction: a rule's `extract_schema` maps named capture groups to
+// field types, turning each match into a JSON record.
+
+use crate::normalize::NormalizedText;
+use crate::{ExtractError, RuleBox};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The type a captured group is parsed as.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    String,
+    /// A signed 64-bit integer.
+    Int,
+    /// An ISO `YYYY-MM-DD` date, kept as a string.
+    Date,
+}
+
+/// What `extract_typed_with` does with a group that doesn't parse as its type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnParseError {
+    /// Drop the whole record and carry on.
+    #[default]
+    Skip,
+    /// Stop and return the error.
+    Fail,
+}
+
+impl FieldType {
+    fn parse(self, raw: &str) -> Option<Value> {
+        match self {
+            FieldType::String => Some(Value::String(raw.to_string())),
+            FieldType::Int => raw.trim().parse::<i64>().ok().map(Value::from),
+            FieldType::Date => {
+                is_iso_date(raw.trim()).then(|| Value::String(raw.trim().to_string()))
+            }
+        }
+    }
+}
+
+fn is_iso_date(raw: &str) -> bool {
+    let parts: Vec<&str> = raw.split('-').collect();
+    let [year, month, day] = parts[..] else {
+        return false;
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return false;
+    }
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
+
+impl RuleBox {
+    /// One record per match of a positive pattern in each matching active
+    /// rule with an `extract_schema`, in rule order then match order. Groups
+    /// that didn't take part in a match are left out of its record, and
+    /// records with a group that fails to parse are skipped.
+    pub fn extract_typed(&self, text: &str) -> Vec<Value> {
+        self.extract_typed_with(text, OnParseError::Skip)
+            .unwrap_or_default()
+    }
+
+    /// `extract_typed` with a choice of what happens on a parse failure.
+    pub fn extract_typed_with(
+        &self,
+        text: &str,
+        on_error: OnParseError,
+    ) -> Result<Vec<Value>, ExtractError> {
+        // Extracted values come from the preprocessed text
+        let text = &*self.preprocessed(text);
+        let mut records = Vec::new();
+        for rule in self
+            .rules
+            .iter()
+            .filter(|r| r.assigns_labels() && !r.extract_schema.is_empty())
+        {
+            if !rule.rule.check(text) {
+                continue;
+            }
+            let normalized = rule
+                .rule
+                .digit_normalize
+                .then(|| NormalizedText::digits(text));
+            let haystack = normalized.as_ref().map_or(text, |n| n.text.as_str());
+
+            let patterns = rule.rule.positive_patterns();
+            for re in patterns.iter().filter_map(|p| p.compiled.as_ref()) {
+                'matches: for captures in re.captures_iter(haystack) {
+                    let mut record = Map::new();
+                    for (field, field_type) in &rule.extract_schema {
+                        let Some(raw) = captures.name(field) else {
+                            continue;
+                        };
+                        match field_type.parse(raw.as_str()) {
+                            Some(value) => {
+                                record.insert(field.clone(), value);
+                            }
+                            None if on_error == OnParseError::Skip => continue 'matches,
+                            None => {
+                                return Err(ExtractError {
+                                    uuid: rule.uuid.clone(),
+                                    field: field.clone(),
+                                    value: raw.as_str().to_string(),
+                                    expected: *field_type,
+                                })
+                            }
+                        }
+                    }
+                    records.push(Value::Object(record));
+                }
+            }
+        }
+        Ok(records)
+    }
+}
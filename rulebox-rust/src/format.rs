@@ -0,0 +1,102 @@
+// Rule files in YAML and TOML as well as JSON. Every format is read through
+// the same serde schema, so a box loads the same whichever one it's kept in.
+
+use crate::{RuleBox, RuleBoxError};
+use std::io;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+// Without `fs` the other formats are only reached through their own feature's
+// constructor
+#[cfg_attr(not(feature = "fs"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    #[cfg(feature = "fs")]
+    /// The format for a file extension, whether or not its feature is on.
+    pub(crate) fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "json" => Some(Format::Json),
+            "yaml" | "yml" => Some(Format::Yaml),
+            "toml" => Some(Format::Toml),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "fs")]
+    /// Files with an unrecognised extension are read as JSON.
+    pub(crate) fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Format::from_extension)
+            .unwrap_or(Format::Json)
+    }
+
+    #[cfg(feature = "fs")]
+    pub(crate) fn is_enabled(self) -> bool {
+        match self {
+            Format::Json => true,
+            Format::Yaml => cfg!(feature = "yaml"),
+            Format::Toml => cfg!(feature = "toml"),
+        }
+    }
+
+    /// Parse a box without compiling it.
+    pub(crate) fn parse(self, text: &str) -> Result<RuleBox, RuleBoxError> {
+        match self {
+            Format::Json => Ok(serde_json::from_str(text)?),
+            #[cfg(feature = "yaml")]
+            Format::Yaml => Ok(serde_yaml::from_str(text)?),
+            #[cfg(feature = "toml")]
+            Format::Toml => Ok(toml::from_str(text)?),
+            #[allow(unreachable_patterns)]
+            format => Err(RuleBoxError::Io(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "{:?} rule files need the `{}` feature",
+                    format,
+                    format.feature()
+                ),
+            ))),
+        }
+    }
+
+    fn feature(self) -> &'static str {
+        match self {
+            Format::Json => "default",
+            Format::Yaml => "yaml",
+            Format::Toml => "toml",
+        }
+    }
+}
+
+/// Read a rule file in the format its extension names, without compiling it.
+#[cfg(feature = "fs")]
+pub(crate) fn read_path(path: &Path) -> Result<RuleBox, RuleBoxError> {
+    let text = std::fs::read_to_string(path)?;
+    Format::from_path(path).parse(&text)
+}
+
+impl RuleBox {
+    /// Load a box from YAML, using the same schema as `from_json`.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_str(yaml: &str) -> Result<Self, RuleBoxError> {
+        let mut rulebox = Format::Yaml.parse(yaml)?;
+        rulebox.compile_and_test()?;
+        Ok(rulebox)
+    }
+
+    /// Load a box from TOML. A TOML document has to be a table, so rules go
+    /// under `[[rules]]` rather than in a bare array.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(toml: &str) -> Result<Self, RuleBoxError> {
+        let mut rulebox = Format::Toml.parse(toml)?;
+        rulebox.compile_and_test()?;
+        Ok(rulebox)
+    }
+}
@@ -0,0 +1,82 @@
+// Approximate literal matching: does the pattern occur somewhere in the text
+// within a bounded number of edits?
+//
+// This is Sellers' variant of the Levenshtein dynamic program, which lets a
+// match start at any position in the text. It runs in O(n * m) time and O(m)
+// space for a text of n characters and a pattern of m characters, so it is far
+// slower than a regex scan: keep fuzzy patterns short and few.
+
+/// A literal compiled for fuzzy matching.
+#[derive(Debug, Clone)]
+pub struct FuzzyMatcher {
+    chars: Vec<char>,
+    fold_case: bool,
+    max_distance: usize,
+}
+
+impl FuzzyMatcher {
+    pub fn new(literal: &str, max_distance: usize, fold_case: bool) -> Self {
+        Self {
+            chars: literal.chars().map(|c| fold(c, fold_case)).collect(),
+            fold_case,
+            max_distance,
+        }
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// Byte span of the leftmost-ending occurrence within `max_distance` edits.
+    pub fn find(&self, text: &str) -> Option<(usize, usize)> {
+        let m = self.chars.len();
+        if m == 0 {
+            return Some((0, 0));
+        }
+
+        // cost[i] is the edit distance of pattern[..i] against the best text
+        // substring ending here; start[i] is where that substring began
+        let mut cost: Vec<usize> = (0..=m).collect();
+        let mut start: Vec<usize> = vec![0; m + 1];
+
+        for (pos, original) in text.char_indices() {
+            let end = pos + original.len_utf8();
+            let c = fold(original, self.fold_case);
+            let mut diag_cost = cost[0];
+            let mut diag_start = start[0];
+            // An empty prefix matches for free, so a match may begin after
+            // any character
+            cost[0] = 0;
+            start[0] = end;
+            for i in 1..=m {
+                let (up_cost, up_start) = (cost[i], start[i]);
+                let substitute = diag_cost + usize::from(self.chars[i - 1] != c);
+                let (mut best, mut best_start) = (substitute, diag_start);
+                if cost[i - 1] + 1 < best {
+                    best = cost[i - 1] + 1;
+                    best_start = start[i - 1];
+                }
+                if up_cost + 1 < best {
+                    best = up_cost + 1;
+                    best_start = up_start;
+                }
+                diag_cost = up_cost;
+                diag_start = up_start;
+                cost[i] = best;
+                start[i] = best_start;
+            }
+            if cost[m] <= self.max_distance {
+                return Some((start[m], end));
+            }
+        }
+        None
+    }
+}
+
+fn fold(c: char, fold_case: bool) -> char {
+    if fold_case {
+        c.to_lowercase().next().unwrap_or(c)
+    } else {
+        c
+    }
+}
@@ -0,0 +1,154 @@
+// Scoring a rulebox against a hand-labelled ("golden") corpus, so a rule
+// change can be gated in CI on precision and recall rather than eyeballed.
+// The corpus is the JSONL `label_stream` writes, reviewed and corrected.
+
+use crate::{RuleBox, RuleBoxError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io::BufRead;
+
+#[derive(Deserialize)]
+struct GoldenLine {
+    text: String,
+    labels: Vec<String>,
+}
+
+/// One label's confusion counts over a golden corpus.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LabelScore {
+    pub label: String,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    pub true_negatives: usize,
+    /// The labels assigned to texts where this one was expected but missing,
+    /// with how often, e.g. `ham` under `spam` when rules confuse the two.
+    pub confused_with: BTreeMap<String, usize>,
+}
+
+impl LabelScore {
+    /// Share of the texts given the label that should have it; 1.0 when it
+    /// was never assigned.
+    pub fn precision(&self) -> f64 {
+        ratio(self.true_positives, self.false_positives)
+    }
+
+    /// Share of the texts that should have the label that got it; 1.0 when
+    /// it was never expected.
+    pub fn recall(&self) -> f64 {
+        ratio(self.true_positives, self.false_negatives)
+    }
+
+    pub fn f1(&self) -> f64 {
+        let (p, r) = (self.precision(), self.recall());
+        if p + r == 0.0 {
+            0.0
+        } else {
+            2.0 * p * r / (p + r)
+        }
+    }
+}
+
+fn ratio(hits: usize, misses: usize) -> f64 {
+    if hits + misses == 0 {
+        1.0
+    } else {
+        hits as f64 / (hits + misses) as f64
+    }
+}
+
+/// A corpus text whose labels weren't the expected ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorpusMismatch {
+    /// Line of the corpus file, numbered from 1.
+    pub line: usize,
+    pub text: String,
+    /// Expected labels that weren't assigned, sorted.
+    pub missing: Vec<String>,
+    /// Assigned labels that weren't expected, sorted.
+    pub unexpected: Vec<String>,
+}
+
+/// Result of `RuleBox::test_corpus`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CorpusReport {
+    pub texts: usize,
+    /// Every label expected or assigned anywhere in the corpus, sorted.
+    pub labels: Vec<LabelScore>,
+    /// In corpus order.
+    pub mismatches: Vec<CorpusMismatch>,
+}
+
+impl CorpusReport {
+    /// The labels whose precision or recall is below the minimum, for
+    /// failing a CI run.
+    pub fn below(&self, min_precision: f64, min_recall: f64) -> Vec<&LabelScore> {
+        self.labels
+            .iter()
+            .filter(|score| score.precision() < min_precision || score.recall() < min_recall)
+            .collect()
+    }
+}
+
+impl RuleBox {
+    /// Label every text of a golden corpus and compare the result with its
+    /// expected labels. Each JSONL line is an object with a string `text`
+    /// and the `labels` it should get (other fields are ignored); blank
+    /// lines are skipped.
+    pub fn test_corpus(&self, input: impl BufRead) -> Result<CorpusReport, RuleBoxError> {
+        let mut report = CorpusReport::default();
+        let mut scores: BTreeMap<String, LabelScore> = BTreeMap::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let golden: GoldenLine =
+                serde_json::from_str(&line).map_err(|source| RuleBoxError::InvalidLine {
+                    line: index + 1,
+                    source,
+                })?;
+            let expected: HashSet<String> = golden.labels.into_iter().collect();
+            let assigned: HashSet<String> = self.assign_labels(&golden.text).into_iter().collect();
+            report.texts += 1;
+
+            let mut missing: Vec<String> = expected.difference(&assigned).cloned().collect();
+            let mut unexpected: Vec<String> = assigned.difference(&expected).cloned().collect();
+            missing.sort();
+            unexpected.sort();
+            for label in expected.union(&assigned) {
+                let score = scores.entry(label.clone()).or_insert_with(|| LabelScore {
+                    label: label.clone(),
+                    // Texts before the label first turned up had neither
+                    true_negatives: report.texts - 1,
+                    ..Default::default()
+                });
+                match (expected.contains(label), assigned.contains(label)) {
+                    (true, true) => score.true_positives += 1,
+                    (false, true) => score.false_positives += 1,
+                    _ => {
+                        score.false_negatives += 1;
+                        for other in &unexpected {
+                            *score.confused_with.entry(other.clone()).or_default() += 1;
+                        }
+                    }
+                }
+            }
+            for (label, score) in scores.iter_mut() {
+                if !expected.contains(label) && !assigned.contains(label) {
+                    score.true_negatives += 1;
+                }
+            }
+            if !missing.is_empty() || !unexpected.is_empty() {
+                report.mismatches.push(CorpusMismatch {
+                    line: index + 1,
+                    text: golden.text,
+                    missing,
+                    unexpected,
+                });
+            }
+        }
+        report.labels = scores.into_values().collect();
+        Ok(report)
+    }
+}
@@ -0,0 +1,42 @@
+// Mutually exclusive label groups: of the matching rules that share a
+// `group`, only the one with the highest priority (then the earliest) assigns
+// its label.
+
+use crate::LabelRule;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub(crate) struct GroupWinners<'a> {
+    // group -> (rule index, rule) of the best match so far
+    winners: HashMap<&'a str, (usize, &'a LabelRule)>,
+}
+
+impl<'a> GroupWinners<'a> {
+    /// Whether the rule at `index` would beat the group's current winner, so
+    /// rules that can't win are never evaluated.
+    pub(crate) fn could_win(&self, group: &str, index: usize, rule: &LabelRule) -> bool {
+        self.winners.get(group).is_none_or(|(best, winner)| {
+            rule.priority > winner.priority || (rule.priority == winner.priority && index < *best)
+        })
+    }
+
+    /// Record a matching rule, which must have passed `could_win`.
+    pub(crate) fn record(&mut self, group: &'a str, index: usize, rule: &'a LabelRule) {
+        self.winners.insert(group, (index, rule));
+    }
+
+    /// Whether the rule at `index` is its group's winner.
+    pub(crate) fn is_winner(&self, group: &str, index: usize) -> bool {
+        self.winners
+            .get(group)
+            .is_some_and(|(best, _)| *best == index)
+    }
+
+    /// The winners' labels, ordered by the winning rule's position in the
+    /// box so the result doesn't depend on hashing.
+    pub(crate) fn labels(self) -> impl Iterator<Item = String> + 'a {
+        let mut winners: Vec<(usize, &LabelRule)> = self.winners.into_values().collect();
+        winners.sort_unstable_by_key(|(index, _)| *index);
+        winners.into_iter().map(|(_, rule)| rule.label.clone())
+    }
+}
@@ -0,0 +1,57 @@
+// A cheaply cloneable, thread-safe reference to a compiled box, for sharing
+// one box across a worker pool (e.g. as axum state) without lifetimes.
+
+use crate::RuleBox;
+use std::ops::Deref;
+use std::sync::Arc;
+
+// Compiled boxes are shared across threads; fail the build if a field ever
+// stops them being Send + Sync
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<RuleBox>();
+};
+
+/// A shared, immutable `RuleBox`. Cloning only bumps a reference count, and
+/// every `&self` method (`check`, `assign_labels`, ...) is available through
+/// `Deref`, so clones can label concurrently from any number of threads.
+#[derive(Debug, Clone)]
+pub struct RuleBoxHandle(Arc<RuleBox>);
+
+impl RuleBoxHandle {
+    pub fn new(rulebox: RuleBox) -> Self {
+        Self(Arc::new(rulebox))
+    }
+
+    /// The underlying `Arc`, e.g. to hand to code that takes `Arc<RuleBox>`.
+    pub fn as_arc(&self) -> &Arc<RuleBox> {
+        &self.0
+    }
+}
+
+impl Deref for RuleBoxHandle {
+    type Target = RuleBox;
+
+    fn deref(&self) -> &RuleBox {
+        &self.0
+    }
+}
+
+impl From<RuleBox> for RuleBoxHandle {
+    fn from(rulebox: RuleBox) -> Self {
+        Self::new(rulebox)
+    }
+}
+
+impl From<Arc<RuleBox>> for RuleBoxHandle {
+    fn from(rulebox: Arc<RuleBox>) -> Self {
+        Self(rulebox)
+    }
+}
+
+impl RuleBox {
+    /// Move the box into a `RuleBoxHandle` for sharing across threads.
+    pub fn into_handle(self) -> RuleBoxHandle {
+        RuleBoxHandle::new(self)
+    }
+}
@@ -0,0 +1,47 @@
+// A small stable hash for fingerprints and cache keys that need to survive
+// across processes and Rust versions (unlike std's DefaultHasher).
+
+use crate::RuleBox;
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// 64-bit FNV-1a.
+pub(crate) fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+impl RuleBox {
+    /// A stable hash of everything that affects matching. Rule UUIDs are left
+    /// out so files that rely on generated UUIDs fingerprint the same on every
+    /// load, and so is rule metadata, which never affects matching. A rule
+    /// with `active_from` / `active_until` counts as active or not by its
+    /// clock, so the fingerprint changes as its window opens and closes.
+    pub fn fingerprint(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        let rules = match &mut value {
+            serde_json::Value::Object(doc) => doc.get_mut("rules"),
+            rules => Some(rules),
+        };
+        if let Some(serde_json::Value::Array(rules)) = rules {
+            for (rule, label_rule) in rules.iter_mut().zip(&self.rules) {
+                if let Some(rule) = rule.as_object_mut() {
+                    rule.remove("uuid");
+                    rule.remove("metadata");
+                    if label_rule.is_scheduled() {
+                        rule.remove("active_from");
+                        rule.remove("active_until");
+                        rule.insert("active".to_string(), label_rule.is_active().into());
+                    }
+                }
+            }
+        }
+        let bytes = serde_json::to_vec(&value).unwrap_or_default();
+        format!("{:016x}", stable_hash(&bytes))
+    }
+}
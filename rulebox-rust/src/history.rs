@@ -0,0 +1,73 @@
+// Rule versions: `update_rule` bumps a rule's `version` and keeps what it
+// replaced in `history`, so the file itself answers "what did this rule look
+// like on date X" without digging through version control.
+
+use crate::{LabelRule, RuleBox};
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A superseded version of a rule.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleRevision {
+    pub version: u32,
+    /// When this version was replaced, to the second; RFC 3339 in the file.
+    pub replaced: DateTime<Utc>,
+    /// The rule as it was, in the rule file format, without its history.
+    pub rule: Value,
+}
+
+/// One change in `RuleBox::changelog`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    pub uuid: String,
+    pub label: String,
+    /// The version the rule went to.
+    pub version: u32,
+    /// When the change was made.
+    pub changed: DateTime<Utc>,
+    /// The rule as it was before the change.
+    pub previous: Value,
+}
+
+impl LabelRule {
+    /// The rule as it was at `at`: the revision in force then, or `None` if
+    /// the current version was already in place.
+    pub fn revision_at(&self, at: DateTime<Utc>) -> Option<&RuleRevision> {
+        self.history.iter().find(|revision| revision.replaced > at)
+    }
+
+    // Move the rule being replaced at `now`, and its history, onto `rule`
+    pub(crate) fn supersede(&mut self, rule: &mut LabelRule, now: DateTime<Utc>) {
+        rule.history = std::mem::take(&mut self.history);
+        rule.history.push(RuleRevision {
+            version: self.version,
+            replaced: now.trunc_subsecs(0),
+            // Only fails for map keys that aren't strings, which rules don't have
+            rule: serde_json::to_value(&*self).unwrap_or(Value::Null),
+        });
+        rule.version = self.version + 1;
+    }
+}
+
+impl RuleBox {
+    /// Every recorded change to every rule, oldest first (ties in box order).
+    pub fn changelog(&self) -> Vec<ChangelogEntry> {
+        let mut entries: Vec<ChangelogEntry> = self
+            .rules
+            .iter()
+            .flat_map(|rule| {
+                rule.history.iter().map(|revision| ChangelogEntry {
+                    uuid: rule.uuid.clone(),
+                    label: rule.label.clone(),
+                    version: revision.version + 1,
+                    changed: revision.replaced,
+                    previous: revision.rule.clone(),
+                })
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.changed);
+        entries
+    }
+}
@@ -0,0 +1,124 @@
+// Labels with the rules behind them, for auditing which rule was responsible
+// for each automated decision.
+
+use crate::groups::GroupWinners;
+use crate::{LabelRule, RuleBox};
+use serde::{Deserialize, Serialize};
+
+/// A label assigned to a text and the rules that produced it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelHit {
+    pub label: String,
+    /// UUIDs of the matching rules that assign the label, in box order: the
+    /// winning rule for a `group`, every matching rule for a label assigned
+    /// by score. A label that's only there because another label implies it
+    /// lists the rules behind those labels; labels from count rules have none.
+    pub rules: Vec<String>,
+}
+
+impl RuleBox {
+    /// The labels for `text`, in `assign_labels` order, each with the UUIDs
+    /// of the rules that produced it.
+    pub fn check_hits(&self, text: &str) -> Vec<LabelHit> {
+        let (labels, assigning) = self.assigning_rules(text);
+        self.hits_for(&labels, &assigning)
+    }
+
+    // The labels for `text` in `assign_labels` order, and the indices (in box
+    // order) of the rules that assigned them: rules that ran in their stage,
+    // matched, won their group and whose label survived score thresholds and
+    // suppressors
+    pub(crate) fn assigning_rules(&self, text: &str) -> (Vec<String>, Vec<usize>) {
+        let prepared = self.preprocessed(text);
+        let rules = self.labeling_rules();
+        let mut labels: Vec<String> = Vec::new();
+        let mut assigning = Vec::new();
+        // Replays `fill_labels` a stage at a time, since a rule's stage
+        // decides which labels it could see
+        for stage in self.stages() {
+            let stage_rules =
+                self.stage_rules(&rules, stage, |label| labels.iter().any(|l| l == label));
+            self.fill_stage(&stage_rules, &prepared, &mut labels, &mut |rule, text| {
+                self.rule_matches(rule, text)
+            });
+            let matched: Vec<(usize, &LabelRule)> = stage_rules
+                .into_iter()
+                .filter(|(_, rule)| rule.assigns_labels() && rule.rule.check(&prepared))
+                .collect();
+            let mut groups = GroupWinners::default();
+            for &(i, rule) in &matched {
+                if let Some(group) = &rule.group {
+                    if groups.could_win(group, i, rule) {
+                        groups.record(group, i, rule);
+                    }
+                }
+            }
+            assigning.extend(
+                matched
+                    .iter()
+                    .filter(|(i, rule)| rule.group.as_ref().is_none_or(|g| groups.is_winner(g, *i)))
+                    .map(|&(i, _)| i),
+            );
+        }
+        // Rules under a score threshold or whose label was suppressed, in
+        // their stage or a later one, didn't assign it
+        assigning.retain(|&i| labels.contains(&self.rules[i].label));
+        assigning.sort_unstable();
+        (labels, assigning)
+    }
+
+    // `check_hits` from the result of `assigning_rules`
+    pub(crate) fn hits_for(&self, labels: &[String], assigning: &[usize]) -> Vec<LabelHit> {
+        let assigning: Vec<&LabelRule> = assigning.iter().map(|&i| &self.rules[i]).collect();
+        let direct = |label: &str| -> Vec<String> {
+            assigning
+                .iter()
+                .filter(|rule| rule.label == label)
+                .map(|rule| rule.uuid.clone())
+                .collect()
+        };
+
+        labels
+            .iter()
+            .map(|label| {
+                let mut rules = direct(label);
+                if rules.is_empty() {
+                    rules = assigning
+                        .iter()
+                        .filter(|rule| self.implied_labels(&rule.label).contains(label))
+                        .map(|rule| rule.uuid.clone())
+                        .collect();
+                }
+                LabelHit {
+                    label: label.clone(),
+                    rules,
+                }
+            })
+            .collect()
+    }
+
+    /// `check_hits` for each text, in input order.
+    pub fn check_many_hits(&self, texts: &[String]) -> Vec<Vec<LabelHit>> {
+        texts.iter().map(|text| self.check_hits(text)).collect()
+    }
+
+    /// UUIDs of the rules behind `text`'s labels, as reported by
+    /// `check_hits`, each once and in box order. Unlike the labels, these
+    /// tell apart several rules sharing a label.
+    pub fn assign_rule_ids(&self, text: &str) -> Vec<String> {
+        let hits = self.check_hits(text);
+        self.rules
+            .iter()
+            .filter(|rule| hits.iter().any(|hit| hit.rules.contains(&rule.uuid)))
+            .map(|rule| rule.uuid.clone())
+            .collect()
+    }
+
+    /// `assign_rule_ids` for each text, in input order.
+    pub fn assign_rule_ids_vector(&self, texts: &[String]) -> Vec<Vec<String>> {
+        texts
+            .iter()
+            .map(|text| self.assign_rule_ids(text))
+            .collect()
+    }
+}
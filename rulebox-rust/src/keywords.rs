@@ -0,0 +1,104 @@
+// Literal keyword lists, matched with Aho-Corasick in a single pass however
+// many keywords there are. Easier to author than an alternation of escaped
+// literals, and much faster for thousands of terms.
+
+use crate::CompileError;
+use aho_corasick::AhoCorasick;
+use serde::{Deserialize, Serialize};
+
+/// A list of literal keywords, any one of which counts as a match.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct KeywordPatterns {
+    pub keywords: Vec<String>,
+    /// Fold ASCII case; non-ASCII letters must match exactly.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub ignore_case: bool,
+    /// Only match keywords that aren't part of a longer word.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub whole_words: bool,
+
+    #[serde(skip)]
+    pub compiled: Option<AhoCorasick>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl KeywordPatterns {
+    pub(crate) fn compile(&mut self) -> Result<(), CompileError> {
+        if self.keywords.iter().any(String::is_empty) {
+            return Err(CompileError::InvalidKeywords {
+                reason: "keywords can't be empty".to_string(),
+            });
+        }
+        let built = AhoCorasick::builder()
+            .ascii_case_insensitive(self.ignore_case)
+            .build(&self.keywords)
+            .map_err(|e| CompileError::InvalidKeywords {
+                reason: e.to_string(),
+            })?;
+        self.compiled = Some(built);
+        Ok(())
+    }
+
+    pub fn is_compiled(&self) -> bool {
+        self.compiled.is_some()
+    }
+
+    pub fn check(&self, text: &str) -> bool {
+        let Some(ac) = &self.compiled else {
+            return false;
+        };
+        if !self.whole_words {
+            return ac.is_match(text);
+        }
+        // A shorter keyword can sit inside a longer one that isn't a whole
+        // word, so every overlapping occurrence has to be considered
+        ac.find_overlapping_iter(text)
+            .any(|m| !word_char_before(text, m.start()) && !word_char_after(text, m.end()))
+    }
+
+    /// Each keyword found in `text`, as written in `keywords`, with its byte
+    /// span. With `whole_words` the spans may overlap.
+    pub(crate) fn find_keywords(&self, text: &str) -> Vec<(&str, usize, usize)> {
+        let Some(ac) = &self.compiled else {
+            return Vec::new();
+        };
+        let found = |m: aho_corasick::Match| {
+            (
+                self.keywords[m.pattern().as_usize()].as_str(),
+                m.start(),
+                m.end(),
+            )
+        };
+        if !self.whole_words {
+            return ac.find_iter(text).map(found).collect();
+        }
+        ac.find_overlapping_iter(text)
+            .filter(|m| !word_char_before(text, m.start()) && !word_char_after(text, m.end()))
+            .map(found)
+            .collect()
+    }
+
+    /// Byte spans of `find_keywords`.
+    pub(crate) fn find_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.find_keywords(text)
+            .into_iter()
+            .map(|(_, start, end)| (start, end))
+            .collect()
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn word_char_before(text: &str, at: usize) -> bool {
+    text[..at].chars().next_back().is_some_and(is_word_char)
+}
+
+fn word_char_after(text: &str, at: usize) -> bool {
+    text[at..].chars().next().is_some_and(is_word_char)
+}
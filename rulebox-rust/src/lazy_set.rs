@@ -0,0 +1,53 @@
+use crate::RegexLimits;
+use regex::{RegexSet, RegexSetBuilder};
+use std::sync::OnceLock;
+
+/// A `RegexSet` over a group of patterns that is only built the first time
+/// it's needed and then shared by every later check, including across threads.
+///
+/// Filled in by `compile`; leave it as `Default::default()` when building
+/// rules by hand.
+#[derive(Debug, Default)]
+pub struct LazyRegexSet {
+    sources: Vec<String>,
+    limits: RegexLimits,
+    set: OnceLock<Option<RegexSet>>,
+}
+
+impl LazyRegexSet {
+    pub(crate) fn new(sources: Vec<String>, limits: RegexLimits) -> Self {
+        Self {
+            sources,
+            limits,
+            set: OnceLock::new(),
+        }
+    }
+
+    /// The built set, or `None` if there's nothing to build or the patterns
+    /// can't be combined (callers then fall back to checking one by one).
+    pub fn get(&self) -> Option<&RegexSet> {
+        if self.sources.is_empty() {
+            return None;
+        }
+        self.set
+            .get_or_init(|| build_set(&self.sources, &self.limits))
+            .as_ref()
+    }
+
+    pub fn is_built(&self) -> bool {
+        self.set.get().is_some()
+    }
+}
+
+// Mirrors `build_regex`: ASCII mode first when there are no Unicode tables
+fn build_set(sources: &[String], limits: &RegexLimits) -> Option<RegexSet> {
+    let mut builder = RegexSetBuilder::new(sources);
+    limits.apply_to_set(&mut builder);
+    if !cfg!(feature = "unicode") {
+        if let Ok(set) = builder.unicode(false).build() {
+            return Some(set);
+        }
+        builder.unicode(true);
+    }
+    builder.build().ok()
+}
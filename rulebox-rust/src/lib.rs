@@ -1,48 +1,448 @@
+use chrono::{DateTime, Utc};
 use regex::{Regex as RustRegex, RegexBuilder};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+#[cfg(feature = "fs")]
 use std::fs;
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
 use uuid::Uuid;
 
+#[cfg(feature = "arrow")]
+mod arrow;
+mod assertions;
+mod budget;
+mod complexity;
+mod corpus;
+mod count_rules;
+mod diff;
+mod document;
+mod error;
+mod explain;
+mod expr;
+mod extract;
+mod format;
+#[cfg(feature = "fuzzy")]
+mod fuzzy;
+mod golden;
+mod groups;
+mod handle;
+mod hash;
+mod history;
+mod hits;
+mod keywords;
+mod lazy_set;
+mod limits;
+mod lint;
+mod match_mode;
+mod merge;
+mod metadata;
+mod mutate;
+mod near;
+pub mod normalize;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod prefilter;
+mod preprocess;
+mod record;
+mod regex_cache;
+mod report;
+#[cfg(feature = "fs")]
+mod result_cache;
+mod schedule;
+mod schema;
+mod scoring;
+mod spans;
+mod stages;
+mod stats;
+mod stream;
+mod suppress;
+mod targets;
+mod taxonomy;
+#[cfg(feature = "metrics")]
+mod telemetry;
+#[cfg(feature = "watch")]
+mod watch;
+
+#[cfg(feature = "arrow")]
+pub use arrow::LABELS_COLUMN;
+pub use assertions::SelfTestReport;
+pub use budget::TimeBudget;
+pub use complexity::{validate_pattern_complexity, ComplexityLimits};
+pub use corpus::{CoverageReport, RuleCoverage};
+pub use count_rules::CountRule;
+pub use diff::{LabelChange, LabelDiffReport, TextDiff};
+pub use error::{
+    AssertionFailure, BudgetExceeded, CompileError, ExtractError, RuleBoxError, SchemaProblem,
+};
+pub use explain::{LabelExplanation, PatternCheck};
+pub use expr::Expr;
+pub use extract::{FieldType, OnParseError};
+use format::Format;
+#[cfg(feature = "fuzzy")]
+pub use fuzzy::FuzzyMatcher;
+pub use golden::{CorpusMismatch, CorpusReport, LabelScore};
+use groups::GroupWinners;
+pub use handle::RuleBoxHandle;
+pub use history::{ChangelogEntry, RuleRevision};
+pub use hits::LabelHit;
+pub use keywords::KeywordPatterns;
+pub use lazy_set::LazyRegexSet;
+pub use limits::RegexLimits;
+pub use lint::{LintKind, LintWarning};
+pub use match_mode::MatchMode;
+pub use metadata::RuleMetadata;
+pub use near::{NearPattern, Proximity};
+use normalize::NormalizedText;
+use prefilter::{Candidates, Prefilter};
+pub use preprocess::Preprocess;
+pub use record::Document;
+pub use regex_cache::RegexCache;
+pub use schedule::Clock;
+pub use spans::{LabelDetail, Match, OverlapPolicy, Segment, SpanDetail};
+pub use stats::RuleStats;
+use stats::StatsCollector;
+pub use suppress::RuleKind;
+#[cfg(feature = "watch")]
+pub use watch::WatchedRuleBox;
+
+// The Arrow and Parquet versions this crate is built against, so callers
+// build batches with matching types
+#[cfg(feature = "parquet")]
+pub use parquet;
+#[cfg(feature = "arrow")]
+pub use {arrow_array, arrow_schema};
+
+// The chrono version `active_from`, `active_until` and `Clock` use
+pub use chrono;
+
 // Represents a regex pattern and flags
-#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct RegexRule {
     pub pattern: String,
     #[serde(default)]
     pub flags: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub match_type: MatchType,
+    /// Treat `pattern` as a plain string matched anywhere (`contains`), as a
+    /// whole `word`, as the `exact` text, or at its start or end
+    /// (`starts_with`, `ends_with`). Regex patterns only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_mode: Option<MatchMode>,
+    /// Edits allowed by a fuzzy pattern.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub max_distance: usize,
+    /// Only count the pattern as matching when it occurs at least this many
+    /// times (non-overlapping). Fuzzy patterns count exact occurrences only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_count: Option<usize>,
+    /// Record field this pattern is matched against by `check_document`
+    /// and `check_record`. Ignored by the plain-text checks, which match the
+    /// whole text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Further fields, any one of which may match, e.g. `["subject",
+    /// "body"]`; combined with `field` if both are given.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
 
     #[serde(skip)]
-    pub compiled: Option<RustRegex>,
+    pub compiled: Option<Arc<RustRegex>>,
+    #[cfg(feature = "fuzzy")]
+    #[serde(skip)]
+    pub fuzzy: Option<FuzzyMatcher>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchType {
+    #[default]
+    Regex,
+    /// Treat the pattern as a literal that may appear with up to
+    /// `max_distance` edits. Requires the `fuzzy` feature.
+    Fuzzy,
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// Box-level settings that patterns are compiled against.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct CompileContext<'a> {
+    pub default_flags: &'a [String],
+    pub flag_presets: Option<&'a BTreeMap<String, Vec<String>>>,
+    /// Named sub-patterns that patterns reference as `{{NAME}}`.
+    pub definitions: Option<&'a BTreeMap<String, String>>,
+    /// Compiled regexes shared with other boxes.
+    pub cache: Option<&'a RegexCache>,
+    pub limits: RegexLimits,
+}
+
+impl CompileContext<'_> {
+    /// Expand `@preset` references into the flags they stand for. An unknown
+    /// preset is returned as the error.
+    fn expand_flags<'f>(&'f self, flags: &'f [String]) -> Result<Vec<&'f str>, &'f str> {
+        let mut expanded = Vec::with_capacity(flags.len());
+        for flag in flags {
+            match flag.strip_prefix('@') {
+                Some(name) => {
+                    let preset = self
+                        .flag_presets
+                        .and_then(|presets| presets.get(name))
+                        .ok_or(flag.as_str())?;
+                    expanded.extend(preset.iter().map(String::as_str));
+                }
+                None => expanded.push(flag.as_str()),
+            }
+        }
+        Ok(expanded)
+    }
+
+    /// Replace `{{NAME}}` references with the definitions they name, each
+    /// wrapped in a non-capturing group so it stays one unit, e.g. under `+`.
+    /// An unknown name is returned as the error.
+    fn expand_definitions<'p>(&self, pattern: &'p str) -> Result<Cow<'p, str>, &'p str> {
+        let mut expanded = String::new();
+        let mut rest = pattern;
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let name = after.split("}}").next().unwrap_or_default();
+            if !after.contains("}}") || !is_definition_name(name) {
+                expanded.push_str(&rest[..start + 2]);
+                rest = after;
+                continue;
+            }
+            let definition = self
+                .definitions
+                .and_then(|definitions| definitions.get(name))
+                .ok_or(name)?;
+            expanded.push_str(&rest[..start]);
+            expanded.push_str("(?:");
+            expanded.push_str(definition);
+            expanded.push(')');
+            rest = &after[name.len() + 2..];
+        }
+        if expanded.is_empty() {
+            return Ok(Cow::Borrowed(pattern));
+        }
+        expanded.push_str(rest);
+        Ok(Cow::Owned(expanded))
+    }
+}
+
+// Definition names are identifiers, so regex repetitions like `{2}` are never
+// mistaken for references
+fn is_definition_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
 impl RegexRule {
-    pub fn compile(&mut self) -> Result<(), String> {
-        let mut builder = RegexBuilder::new(&self.pattern);
-        for flag in &self.flags {
-            match flag.as_str() {
+    pub fn compile(&mut self) -> Result<(), CompileError> {
+        self.compile_with(&CompileContext::default())
+    }
+
+    // Explicit flags replace the box defaults rather than adding to them
+    fn effective_flags<'a>(
+        &'a self,
+        ctx: &'a CompileContext,
+    ) -> Result<Vec<&'a str>, CompileError> {
+        let flags = if self.flags.is_empty() {
+            ctx.default_flags
+        } else {
+            &self.flags
+        };
+        ctx.expand_flags(flags)
+            .map_err(|preset| CompileError::UnknownFlagPreset {
+                pattern: self.pattern.clone(),
+                preset: preset.to_string(),
+            })
+    }
+
+    /// The pattern with its flags inlined, for combining into a `RegexSet`.
+    /// `None` for patterns that aren't plain regexes.
+    pub(crate) fn set_source(&self, ctx: &CompileContext) -> Option<String> {
+        // A set can only say whether a pattern occurs, not how often
+        if self.min_count.is_some_and(|n| n > 1) {
+            return None;
+        }
+        self.presence_source(ctx)
+    }
+
+    /// Like `set_source`, but for patterns that need `min_count` occurrences
+    /// it matches a single one, which is all a pre-filter needs.
+    pub(crate) fn presence_source(&self, ctx: &CompileContext) -> Option<String> {
+        if self.match_type != MatchType::Regex {
+            return None;
+        }
+        let flags = self.effective_flags(ctx).ok()?;
+        let source = self.regex_source(&flags, ctx).ok()?;
+        // In verbose mode a trailing `#` comment would swallow the `)`
+        let end = if flags.contains(&"x") { "\n)" } else { ")" };
+        // `a` has no inline form; it's `-u`, when the pattern allows it
+        let inline: String = flags.iter().filter(|f| **f != "a").copied().collect();
+        if flags.contains(&"a") {
+            let ascii = format!("(?{}-u:{}{}", inline, source, end);
+            if regex_syntax::Parser::new().parse(&ascii).is_ok() {
+                return Some(ascii);
+            }
+        }
+        Some(format!("(?{}:{}{}", inline, source, end))
+    }
+
+    // The regex a regex pattern compiles from, after its `match_mode` or
+    // with its `{{NAME}}` definitions expanded
+    fn regex_source(
+        &self,
+        flags: &[&str],
+        ctx: &CompileContext,
+    ) -> Result<Cow<'_, str>, CompileError> {
+        match self.match_mode {
+            Some(mode) => Ok(Cow::Owned(mode.source(&self.pattern, is_ascii_only(flags)))),
+            None => ctx.expand_definitions(&self.pattern).map_err(|name| {
+                CompileError::UnknownDefinition {
+                    pattern: self.pattern.clone(),
+                    name: name.to_string(),
+                }
+            }),
+        }
+    }
+
+    pub(crate) fn compile_with(&mut self, ctx: &CompileContext) -> Result<(), CompileError> {
+        let flags = self.effective_flags(ctx)?;
+        let source = match self.match_type {
+            MatchType::Regex => self.regex_source(&flags, ctx)?.into_owned(),
+            MatchType::Fuzzy => {
+                self.validate_fuzzy()?;
+                if self.match_mode.is_some() {
+                    return Err(CompileError::InvalidFuzzy {
+                        pattern: self.pattern.clone(),
+                        reason: "can't have a match_mode".to_string(),
+                    });
+                }
+                // Exact occurrences still go through a plain literal search
+                regex::escape(&self.pattern)
+            }
+        };
+        if flags.contains(&"a") && flags.contains(&"u") {
+            return Err(CompileError::ConflictingFlags {
+                pattern: self.pattern.clone(),
+                flags: "a, u".to_string(),
+            });
+        }
+        #[cfg(feature = "fuzzy")]
+        let fold_case = flags.contains(&"i");
+        let mut builder = RegexBuilder::new(&source);
+        ctx.limits.apply(&mut builder);
+        for flag in &flags {
+            match *flag {
                 "i" => builder.case_insensitive(true),
                 "m" => builder.multi_line(true),
-                _ => return Err(format!("Unknown regex flag: {}", flag)),
+                "s" => builder.dot_matches_new_line(true),
+                "x" => builder.ignore_whitespace(true),
+                "U" => builder.swap_greed(true),
+                "R" => builder.crlf(true),
+                "u" => builder.unicode(true),
+                "a" => builder.unicode(false),
+                _ => {
+                    return Err(CompileError::UnknownFlag {
+                        pattern: self.pattern.clone(),
+                        flag: flag.to_string(),
+                    })
+                }
             };
         }
-        match builder.build() {
+        let built = match ctx.cache {
+            Some(cache) => cache.get_or_build(RegexCache::key(&source, &flags, ctx.limits), || {
+                build_regex(&mut builder, &flags)
+            }),
+            None => build_regex(&mut builder, &flags).map(Arc::new),
+        };
+        match built {
             Ok(re) => {
                 self.compiled = Some(re);
+                #[cfg(feature = "fuzzy")]
+                {
+                    self.fuzzy = (self.match_type == MatchType::Fuzzy)
+                        .then(|| FuzzyMatcher::new(&self.pattern, self.max_distance, fold_case));
+                }
                 Ok(())
             }
-            Err(e) => Err(format!("Invalid regex '{}': {}", self.pattern, e)),
+            Err(regex::Error::CompiledTooBig(limit)) => Err(CompileError::SizeLimitExceeded {
+                pattern: self.pattern.clone(),
+                limit,
+            }),
+            Err(source) => Err(CompileError::Regex {
+                pattern: self.pattern.clone(),
+                source,
+            }),
         }
     }
 
+    #[cfg(feature = "fuzzy")]
+    fn validate_fuzzy(&self) -> Result<(), CompileError> {
+        if self.max_distance >= self.pattern.chars().count() {
+            return Err(CompileError::InvalidFuzzy {
+                pattern: self.pattern.clone(),
+                reason: format!(
+                    "would match anything with max_distance {}",
+                    self.max_distance
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "fuzzy"))]
+    fn validate_fuzzy(&self) -> Result<(), CompileError> {
+        Err(CompileError::InvalidFuzzy {
+            pattern: self.pattern.clone(),
+            reason: "requires the `fuzzy` feature".to_string(),
+        })
+    }
+
+    /// Drop the compiled regex so the pattern is picked up by
+    /// `RuleBox::recompile_dirty` after an in-place edit.
+    pub fn invalidate(&mut self) {
+        self.compiled = None;
+    }
+
+    pub fn is_compiled(&self) -> bool {
+        self.compiled.is_some()
+    }
+
     pub fn check(&self, text: &str) -> bool {
-        match &self.compiled {
-            Some(re) => re.is_match(text),
-            None => false,
+        let Some(re) = &self.compiled else {
+            return false;
+        };
+        match self.min_count {
+            Some(n) if n > 1 => re.find_iter(text).take(n).count() == n,
+            _ => re.is_match(text) || self.check_fuzzy(text),
         }
     }
+
+    #[cfg(feature = "fuzzy")]
+    fn check_fuzzy(&self, text: &str) -> bool {
+        self.fuzzy.as_ref().is_some_and(|f| f.is_match(text))
+    }
+
+    #[cfg(not(feature = "fuzzy"))]
+    fn check_fuzzy(&self, _text: &str) -> bool {
+        false
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Rule {
     #[serde(default)]
     pub and_patterns: Vec<RegexRule>,
@@ -50,87 +450,407 @@ pub struct Rule {
     pub or_patterns: Vec<RegexRule>,
     #[serde(default)]
     pub not_patterns: Vec<RegexRule>,
+    /// Only let a `not_pattern` veto when it matches within this many
+    /// characters of a match of the and/or, near or `expr` patterns, rather
+    /// than anywhere in the text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_within: Option<usize>,
+    /// Pairs of patterns that must all match close to each other.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub near_patterns: Vec<NearPattern>,
+    /// Literal keywords that count as extra `or_patterns`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyword_patterns: Option<KeywordPatterns>,
+    /// Match against a copy of the text with separators between digits
+    /// removed, so "555-12 34" can be matched by `5551234`.
+    #[serde(default)]
+    pub digit_normalize: bool,
+    /// Language code the rule applies to; `check_lang` skips it for texts
+    /// detected as another language.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// A nested boolean condition that must also hold for the rule to match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expr: Option<Expr>,
+
+    #[serde(skip)]
+    pub or_set: LazyRegexSet,
 }
 
 impl Rule {
-    pub fn compile(&mut self) -> Result<(), String> {
+    pub fn compile(&mut self) -> Result<(), CompileError> {
+        self.compile_with(&CompileContext::default())
+    }
+
+    pub(crate) fn compile_with(&mut self, ctx: &CompileContext) -> Result<(), CompileError> {
         for p in &mut self.and_patterns {
-            p.compile()?;
+            p.compile_with(ctx)?;
         }
         for p in &mut self.or_patterns {
-            p.compile()?;
+            p.compile_with(ctx)?;
         }
         for p in &mut self.not_patterns {
-            p.compile()?;
+            p.compile_with(ctx)?;
+        }
+        for near in &mut self.near_patterns {
+            near.compile_with(ctx)?;
+        }
+        if let Some(expr) = &mut self.expr {
+            expr.compile_with(ctx)?;
+        }
+        if let Some(keywords) = &mut self.keyword_patterns {
+            keywords.compile()?;
         }
 
-        if !self.and_patterns.is_empty() && !self.or_patterns.is_empty() {
-            return Err("Rule can't have both and_patterns and or_patterns".into());
+        // One pass over the text for "any of" is cheaper than one per pattern
+        let sources: Option<Vec<String>> =
+            self.or_patterns.iter().map(|p| p.set_source(ctx)).collect();
+        self.or_set = match sources {
+            Some(sources) if sources.len() > 1 => LazyRegexSet::new(sources, ctx.limits),
+            _ => LazyRegexSet::default(),
+        };
+
+        if !self.and_patterns.is_empty() && self.has_any_of() {
+            return Err(CompileError::MixedAndOr);
         }
 
         if self.and_patterns.is_empty()
-            && self.or_patterns.is_empty()
+            && !self.has_any_of()
+            && self.near_patterns.is_empty()
+            && self.expr.is_none()
             && !self.not_patterns.is_empty()
         {
-            return Err("Rule cannot consist solely of not_patterns".into());
+            return Err(CompileError::OnlyNotPatterns);
         }
 
         Ok(())
     }
 
+    pub fn is_compiled(&self) -> bool {
+        self.patterns().iter().all(|p| p.is_compiled())
+            && self
+                .keyword_patterns
+                .as_ref()
+                .is_none_or(KeywordPatterns::is_compiled)
+    }
+
+    // Whether the rule has an "any of" group: or patterns or keywords
+    pub(crate) fn has_any_of(&self) -> bool {
+        !self.or_patterns.is_empty() || self.keyword_patterns.is_some()
+    }
+
+    pub(crate) fn keywords_match(&self, text: &str) -> bool {
+        self.keyword_patterns
+            .as_ref()
+            .is_some_and(|k| k.check(text))
+    }
+
+    /// Every pattern in the rule, including those inside `expr`.
+    pub(crate) fn patterns(&self) -> Vec<&RegexRule> {
+        let mut patterns: Vec<&RegexRule> = self
+            .and_patterns
+            .iter()
+            .chain(&self.or_patterns)
+            .chain(&self.not_patterns)
+            .chain(
+                self.near_patterns
+                    .iter()
+                    .flat_map(|n| [&n.first, &n.second]),
+            )
+            .collect();
+        if let Some(expr) = &self.expr {
+            expr.patterns(&mut patterns);
+        }
+        patterns
+    }
+
+    pub(crate) fn patterns_mut(&mut self) -> Vec<&mut RegexRule> {
+        let mut patterns: Vec<&mut RegexRule> = self
+            .and_patterns
+            .iter_mut()
+            .chain(&mut self.or_patterns)
+            .chain(&mut self.not_patterns)
+            .chain(
+                self.near_patterns
+                    .iter_mut()
+                    .flat_map(|n| [&mut n.first, &mut n.second]),
+            )
+            .collect();
+        if let Some(expr) = &mut self.expr {
+            expr.patterns_mut(&mut patterns);
+        }
+        patterns
+    }
+
+    /// The patterns whose matches count as evidence for the rule: the and/or
+    /// and near patterns and any `expr` pattern not under a `not`.
+    pub(crate) fn positive_patterns(&self) -> Vec<&RegexRule> {
+        let mut patterns: Vec<&RegexRule> = self
+            .and_patterns
+            .iter()
+            .chain(&self.or_patterns)
+            .chain(
+                self.near_patterns
+                    .iter()
+                    .flat_map(|n| [&n.first, &n.second]),
+            )
+            .collect();
+        if let Some(expr) = &self.expr {
+            expr.positive_patterns(&mut patterns);
+        }
+        patterns
+    }
+
     pub fn check(&self, text: &str) -> bool {
+        self.evaluate(text) == RuleOutcome::Matched
+    }
+
+    /// Like `check`, but distinguishes a rule whose positive patterns matched
+    /// but was blocked by a `not_pattern`.
+    pub fn evaluate(&self, text: &str) -> RuleOutcome {
+        if self.digit_normalize {
+            return self.evaluate_patterns(&NormalizedText::digits(text).text);
+        }
+        self.evaluate_patterns(text)
+    }
+
+    pub(crate) fn evaluate_patterns(&self, text: &str) -> RuleOutcome {
         if !self.and_patterns.is_empty() && !self.and_patterns.iter().all(|r| r.check(text)) {
-            return false;
+            return RuleOutcome::NoMatch;
         }
 
-        if !self.or_patterns.is_empty() && !self.or_patterns.iter().any(|r| r.check(text)) {
-            return false;
+        if !self.near_patterns.iter().all(|n| n.check(text)) {
+            return RuleOutcome::NoMatch;
         }
 
-        if self.not_patterns.iter().any(|r| r.check(text)) {
-            return false;
+        if self.has_any_of() {
+            let any = self.keywords_match(text)
+                || match self.or_set.get() {
+                    Some(set) => set.is_match(text),
+                    None => self.or_patterns.iter().any(|r| r.check(text)),
+                };
+            if !any {
+                return RuleOutcome::NoMatch;
+            }
         }
 
-        true
+        if let Some(expr) = &self.expr {
+            if !expr.eval(&|p| p.check(text)) {
+                return RuleOutcome::NoMatch;
+            }
+        }
+
+        if self.not_patterns.iter().any(|r| self.vetoes(r, text)) {
+            return RuleOutcome::Vetoed;
+        }
+
+        RuleOutcome::Matched
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleOutcome {
+    NoMatch,
+    Matched,
+    /// The positive patterns matched but a `not_pattern` blocked the rule.
+    Vetoed,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LabelRule {
+    /// Generated (UUIDv7) when the file leaves it out.
     #[serde(default = "generate_uuid")]
+    // Keeps a freshly generated UUID out of the schema as the default
+    #[cfg_attr(feature = "schema", schemars(skip_serializing_if = "is_generated"))]
     pub uuid: String,
     pub rule: Rule,
     pub label: String,
+    /// `suppress` makes a match remove `label` instead of assigning it.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub kind: RuleKind,
     #[serde(default)]
     pub proto_text: String,
     #[serde(default = "default_true")]
     pub active: bool,
+    /// Higher priority rules win when a single label is chosen by `classify`.
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Contribution to the label's `score` when the rule matches; negative
+    /// weights count as evidence against the label.
+    #[serde(default = "default_weight", skip_serializing_if = "is_default_weight")]
+    pub weight: f64,
+    /// Texts this rule must match, checked by `compile_and_test` and
+    /// `run_self_tests`. Also read from `positive_examples`.
+    #[serde(
+        default,
+        alias = "positive_examples",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub should_match: Vec<String>,
+    /// Texts this rule must not match. Also read from `negative_examples`.
+    #[serde(
+        default,
+        alias = "negative_examples",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub should_not_match: Vec<String>,
+    /// Named capture groups to parse into typed fields by `extract_typed`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extract_schema: BTreeMap<String, FieldType>,
+    /// Evaluate the rule and report its matches through `shadow_hits` (and
+    /// metrics), but never assign its label.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub shadow: bool,
+    /// Description, author, timestamps and severity; not used for matching.
+    #[serde(default, skip_serializing_if = "RuleMetadata::is_empty")]
+    pub metadata: RuleMetadata,
+    /// Named capture groups whose values `check_detailed` reports, e.g. the
+    /// matched email address.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub captures: Vec<String>,
+    /// Mutually exclusive group: of the matching rules in a group, only the
+    /// one with the highest `priority` (ties go to the earliest rule) assigns
+    /// its label. Grouped rules don't take part in `score_thresholds`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Compiled size limits for this rule's patterns, overriding the box's
+    /// `regex_limits` where set.
+    #[serde(default, skip_serializing_if = "RegexLimits::is_empty")]
+    pub regex_limits: RegexLimits,
+    /// Labelling pass the rule runs in; stages run in ascending order, and
+    /// rules in later stages can depend on the labels of earlier ones.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub stage: u32,
+    /// Labels an earlier stage must have assigned for the rule to run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_labels: Vec<String>,
+    /// Labels that stop the rule running if an earlier stage assigned them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excludes_labels: Vec<String>,
+    /// Starts at 1 and goes up by one with every `update_rule`.
+    #[serde(
+        default = "default_version",
+        skip_serializing_if = "is_default_version"
+    )]
+    pub version: u32,
+    /// The versions this one replaced, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<RuleRevision>,
+    /// The rule is off before this time, e.g. until a campaign starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_from: Option<DateTime<Utc>>,
+    /// The rule is off from this time on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_until: Option<DateTime<Utc>>,
+    /// What `active_from` and `active_until` are checked against; set for
+    /// the whole box with `RuleBox::with_clock`.
+    #[serde(skip)]
+    pub clock: Clock,
 }
 
 fn generate_uuid() -> String {
     Uuid::now_v7().to_string()
 }
+#[cfg(feature = "schema")]
+fn is_generated(_: &String) -> bool {
+    true
+}
 fn default_true() -> bool {
     true
 }
+fn default_weight() -> f64 {
+    1.0
+}
+fn is_default_weight(weight: &f64) -> bool {
+    *weight == default_weight()
+}
+fn default_version() -> u32 {
+    1
+}
+fn is_default_version(version: &u32) -> bool {
+    *version == default_version()
+}
+
+impl Default for LabelRule {
+    fn default() -> Self {
+        Self {
+            uuid: generate_uuid(),
+            rule: Rule::default(),
+            label: String::new(),
+            kind: RuleKind::Assign,
+            proto_text: String::new(),
+            active: true,
+            priority: 0,
+            tags: Vec::new(),
+            weight: default_weight(),
+            should_match: Vec::new(),
+            should_not_match: Vec::new(),
+            extract_schema: BTreeMap::new(),
+            shadow: false,
+            metadata: RuleMetadata::default(),
+            captures: Vec::new(),
+            group: None,
+            regex_limits: RegexLimits::default(),
+            stage: 0,
+            requires_labels: Vec::new(),
+            excludes_labels: Vec::new(),
+            version: default_version(),
+            history: Vec::new(),
+            active_from: None,
+            active_until: None,
+            clock: Clock::default(),
+        }
+    }
+}
 
 impl LabelRule {
-    pub fn compile(&mut self) -> Result<(), String> {
-        self.rule.compile()
+    pub fn compile(&mut self) -> Result<(), CompileError> {
+        self.compile_with(&CompileContext::default())
+    }
+
+    pub(crate) fn compile_with(&mut self, ctx: &CompileContext) -> Result<(), CompileError> {
+        let ctx = CompileContext {
+            limits: self.regex_limits.or(ctx.limits),
+            ..*ctx
+        };
+        if self.stage == 0 && !(self.requires_labels.is_empty() && self.excludes_labels.is_empty())
+        {
+            return Err(CompileError::LabelConditionInFirstStage.in_rule(&self.uuid));
+        }
+        if let (Some(from), Some(until)) = (self.active_from, self.active_until) {
+            if from >= until {
+                return Err(CompileError::EmptyActiveWindow.in_rule(&self.uuid));
+            }
+        }
+        self.rule
+            .compile_with(&ctx)
+            .map_err(|e| e.in_rule(&self.uuid))
+    }
+
+    /// Whether the rule's matches assign its label: it's active (and inside
+    /// any `active_from` / `active_until` window), not in shadow mode and not
+    /// a suppressor.
+    pub fn assigns_labels(&self) -> bool {
+        self.is_active() && !self.shadow && self.kind == RuleKind::Assign
     }
 
     pub fn check(&self, text: &mut LabeledText) {
-        if self.active && self.rule.check(&text.content) {
+        if self.assigns_labels() && self.rule.check(&text.content) {
             text.labels.insert(self.label.clone());
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LabeledText {
     content: String,
     #[serde(default)]
     labels: HashSet<String>,
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    shadow_labels: HashSet<String>,
 }
 
 impl LabeledText {
@@ -138,64 +858,773 @@ impl LabeledText {
         Self {
             content,
             labels: HashSet::new(),
+            shadow_labels: HashSet::new(),
         }
     }
+
+    /// A text that already has `labels`, e.g. from an earlier box, for
+    /// `RuleBox::check_into` to add to.
+    pub fn with_labels(
+        content: String,
+        labels: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            labels: labels.into_iter().map(Into::into).collect(),
+            ..Self::new(content)
+        }
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    pub fn labels(&self) -> &HashSet<String> {
+        &self.labels
+    }
+
+    pub fn has_label(&self, label: &str) -> bool {
+        self.labels.contains(label)
+    }
+
+    /// The text and its labels, without copying either.
+    pub fn into_parts(self) -> (String, HashSet<String>) {
+        (self.content, self.labels)
+    }
+
+    /// Labels of the matching rules evaluated in shadow: active `shadow`
+    /// rules, and inactive rules when the box has `shadow_inactive` set.
+    /// They may overlap with `labels` but never add to it.
+    pub fn shadow_labels(&self) -> &HashSet<String> {
+        &self.shadow_labels
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RuleBox(pub Vec<LabelRule>);
+/// The winning rule behind a `classify` decision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassifyResult {
+    pub label: String,
+    pub uuid: String,
+    pub priority: i32,
+    pub tags: Vec<String>,
+}
+
+/// Assigned labels alongside the rules that were blocked by `not_patterns`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DebugResult {
+    pub assigned: HashSet<String>,
+    /// `(label, uuid)` for each active rule vetoed by a `not_pattern`, whether
+    /// or not its `requires_labels` and `excludes_labels` held.
+    pub vetoed: Vec<(String, String)>,
+}
+
+/// Load and compile every rule file in a directory independently, reporting
+/// a result per file (sorted by path) rather than stopping at the first
+/// broken one. Picks up `*.json`, plus `*.yaml`/`*.yml` and `*.toml` when
+/// those features are on.
+#[cfg(feature = "fs")]
+pub fn validate_dir(path: &str) -> Vec<(PathBuf, Result<(), RuleBoxError>)> {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => return vec![(PathBuf::from(path), Err(e.into()))],
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .and_then(|ext| ext.to_str())
+                    .and_then(Format::from_extension)
+                    .is_some_and(Format::is_enabled)
+        })
+        .collect();
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|file| {
+            let result =
+                format::read_path(&file).and_then(|mut rulebox| rulebox.compile_and_test());
+            (file, result)
+        })
+        .collect()
+}
+
+// Patterns with the `a` flag are built in ASCII mode, as is every pattern
+// without the `unicode` feature (there are no Unicode tables), falling back to
+// Unicode mode for patterns like `.` that can't be expressed without it. An
+// explicit `u` flag skips the ASCII attempt.
+fn build_regex(builder: &mut RegexBuilder, flags: &[&str]) -> Result<RustRegex, regex::Error> {
+    if is_ascii_only(flags) {
+        if let Ok(re) = builder.unicode(false).build() {
+            return Ok(re);
+        }
+        builder.unicode(true);
+    }
+    builder.build()
+}
+
+// Whether `\w`, `\b` and case folding are ASCII-only for these flags
+fn is_ascii_only(flags: &[&str]) -> bool {
+    flags.contains(&"a") || !cfg!(feature = "unicode") && !flags.contains(&"u")
+}
+
+// Serialized through `document`, which also accepts the bare-array format
+/// A set of label rules. A compiled box is `Send + Sync` and every labelling
+/// method takes `&self`, so one box can serve many threads at once; wrap it
+/// in a `RuleBoxHandle` (or an `Arc`) to share it.
+#[derive(Debug, Default)]
+pub struct RuleBox {
+    pub rules: Vec<LabelRule>,
+    /// Flags applied to any pattern that doesn't list its own.
+    pub default_flags: Vec<String>,
+    /// Named flag sets that patterns reference as `"@name"` in `flags`.
+    pub flag_presets: BTreeMap<String, Vec<String>>,
+    /// Named sub-patterns, e.g. `EMAIL`, that regex patterns reference as
+    /// `{{EMAIL}}`; expanded when the box is compiled.
+    pub definitions: BTreeMap<String, String>,
+    /// Let label scores go below zero instead of clamping them at zero.
+    pub allow_negative_scores: bool,
+    /// Labels assigned by score: their matching rules' weights are summed and
+    /// the label is assigned only when the total exceeds the threshold.
+    pub score_thresholds: BTreeMap<String, f64>,
+    /// Parent labels added whenever a label is assigned, e.g.
+    /// `privacy.email` -> `["privacy"]`; followed transitively.
+    pub implies: BTreeMap<String, Vec<String>>,
+    /// Steps such as lowercasing applied to every text before matching.
+    pub preprocess: Preprocess,
+    /// Compiled size limits for every pattern, unless a rule overrides them.
+    pub regex_limits: RegexLimits,
+    /// Label-count rules applied after the pattern rules.
+    pub count_rules: Vec<CountRule>,
+    /// Evaluate inactive rules as if they were `shadow` rules, so their
+    /// matches show up in `shadow_labels` and `shadow_hits`.
+    pub shadow_inactive: bool,
+    /// Identifies this box in provenance output; not part of the rule file.
+    pub source: String,
+    // Rebuilt by every compile; rules edited in place afterwards need a
+    // `recompile_dirty` so the filter sees their new patterns
+    prefilter: Prefilter,
+    // Set by `with_stats`
+    stats: Option<StatsCollector>,
+    // Every distinct pattern the rules use, compiled once, so rules added or
+    // recompiled later share the regexes already built
+    patterns: RegexCache,
+    // Set by `with_clock`, and given to rules added later
+    clock: Clock,
+}
 
 impl RuleBox {
-    pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let mut rulebox: RuleBox = serde_json::from_str(json)?;
-        rulebox.compile()?;
+    pub fn new(rules: Vec<LabelRule>) -> Self {
+        Self {
+            rules,
+            ..Default::default()
+        }
+    }
+
+    /// Set size limits for every pattern without a rule-level override, and
+    /// recompile. A pattern over `size_limit` fails with
+    /// `CompileError::SizeLimitExceeded` inside the rule's `InRule`.
+    pub fn with_regex_limits(mut self, limits: RegexLimits) -> Result<Self, CompileError> {
+        self.regex_limits = limits;
+        self.compile()?;
+        Ok(self)
+    }
+
+    /// Set flags inherited by every pattern without explicit flags, e.g.
+    /// `&["i"]` to make the whole box case-insensitive, and recompile.
+    pub fn with_default_flags(mut self, flags: &[&str]) -> Result<Self, CompileError> {
+        self.default_flags = flags.iter().map(|f| f.to_string()).collect();
+        self.compile()?;
+        Ok(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, RuleBoxError> {
+        let mut rulebox = Format::Json.parse(json)?;
+        rulebox.compile_and_test()?;
         Ok(rulebox)
     }
 
-    pub fn from_path(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let json = fs::read_to_string(path)?;
-        Self::from_json(&json)
+    /// Load a rule file, picking the format from its extension: `.yaml` or
+    /// `.yml` (with the `yaml` feature), `.toml` (with `toml`), otherwise JSON.
+    #[cfg(feature = "fs")]
+    pub fn from_path(path: &str) -> Result<Self, RuleBoxError> {
+        let mut rulebox = format::read_path(Path::new(path))?;
+        rulebox.compile_and_test()?;
+        Ok(rulebox)
+    }
+
+    /// Load a rule file but only compile and keep the rules for `labels`,
+    /// including rules whose label implies one of them.
+    #[cfg(feature = "fs")]
+    pub fn from_path_filtered(path: &str, labels: &[&str]) -> Result<Self, RuleBoxError> {
+        let mut rulebox = format::read_path(Path::new(path))?;
+        let rules = std::mem::take(&mut rulebox.rules);
+        rulebox.rules = rules
+            .into_iter()
+            .filter(|rule| {
+                labels.contains(&rule.label.as_str())
+                    || rulebox
+                        .implied_labels(&rule.label)
+                        .iter()
+                        .any(|parent| labels.contains(&parent.as_str()))
+            })
+            .collect();
+        rulebox.compile_and_test()?;
+        Ok(rulebox)
+    }
+
+    /// Compile every rule. A pattern repeated across rules (with the same
+    /// flags) is compiled once and shared, including with rules added later
+    /// by `add_rule` or `update_rule`.
+    pub fn compile(&mut self) -> Result<(), CompileError> {
+        self.preprocess.validate()?;
+        let (ctx, rules) = self.compile_parts();
+        for rule in rules {
+            rule.compile_with(&ctx)?;
+        }
+        self.patterns.prune();
+        self.rebuild_prefilter();
+        Ok(())
+    }
+
+    /// Number of distinct compiled regexes the box's rules share.
+    pub fn distinct_patterns(&self) -> usize {
+        self.patterns.len()
     }
 
-    pub fn compile(&mut self) -> Result<(), String> {
-        for rule in &mut self.0 {
-            rule.compile()?;
+    /// Compile the box, reusing regexes from `cache` and adding any new ones,
+    /// so identical patterns across boxes are compiled only once.
+    pub fn compile_with_cache(&mut self, cache: &RegexCache) -> Result<(), CompileError> {
+        self.preprocess.validate()?;
+        let (mut ctx, rules) = self.compile_parts();
+        ctx.cache = Some(cache);
+        for rule in rules {
+            rule.compile_with(&ctx)?;
         }
+        self.patterns.prune();
+        self.rebuild_prefilter();
         Ok(())
     }
 
+    /// Recompile only the rules with a pattern that has been invalidated (or
+    /// never compiled), leaving the rest of the box untouched.
+    pub fn recompile_dirty(&mut self) -> Result<(), CompileError> {
+        let (ctx, rules) = self.compile_parts();
+        for rule in rules {
+            if !rule.rule.is_compiled() {
+                rule.compile_with(&ctx)?;
+            }
+        }
+        self.rebuild_prefilter();
+        Ok(())
+    }
+
+    /// Evaluate inactive rules in shadow: `check` reports their labels in
+    /// `LabeledText::shadow_labels` without assigning them.
+    pub fn with_shadow_inactive(mut self, shadow: bool) -> Self {
+        self.shadow_inactive = shadow;
+        self
+    }
+
+    /// Tag the box with a name reported by `check_with_provenance`.
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = source.to_string();
+        self
+    }
+
+    // Borrow the box settings and the rules separately so rules can be
+    // compiled in place against the settings
+    fn compile_parts(&mut self) -> (CompileContext<'_>, &mut Vec<LabelRule>) {
+        let ctx = CompileContext {
+            default_flags: &self.default_flags,
+            flag_presets: Some(&self.flag_presets),
+            definitions: Some(&self.definitions),
+            cache: Some(&self.patterns),
+            limits: self.regex_limits,
+        };
+        (ctx, &mut self.rules)
+    }
+
+    pub(crate) fn rebuild_prefilter(&mut self) {
+        let ctx = CompileContext {
+            default_flags: &self.default_flags,
+            flag_presets: Some(&self.flag_presets),
+            definitions: Some(&self.definitions),
+            cache: None,
+            limits: self.regex_limits,
+        };
+        self.prefilter = Prefilter::build(&self.rules, &ctx);
+    }
+
     pub fn check(&self, text: &str) -> LabeledText {
+        self.check_rules(text, self.rules.iter().enumerate())
+    }
+
+    /// Add this box's labels for `text`'s content to the labels it already
+    /// has, so several boxes can label the same document in turn. Labels
+    /// already there are kept as they are: this box's `implies` and
+    /// suppressors only apply to the labels it assigns itself.
+    pub fn check_into(&self, text: &mut LabeledText) {
+        let checked = self.check(&text.content);
+        text.labels.extend(checked.labels);
+        text.shadow_labels.extend(checked.shadow_labels);
+    }
+
+    /// Approximate `check` that only evaluates the `k` highest-priority active
+    /// rules (ties go to the earlier rule). Labels from the skipped rules are
+    /// missed, so use this only where latency matters more than completeness.
+    pub fn check_topk(&self, text: &str, k: usize) -> LabeledText {
+        let mut top: Vec<(usize, &LabelRule)> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.assigns_labels())
+            .collect();
+        top.sort_by_key(|(_, r)| std::cmp::Reverse(r.priority));
+        // Suppressors always run; they don't count towards `k`
+        let suppressors = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.suppresses());
+        self.check_rules(text, top.into_iter().take(k).chain(suppressors))
+    }
+
+    /// `check` with an extra runtime switch per rule, e.g. backed by a feature
+    /// flag service. A rule is evaluated only if it's `active` and `gate`
+    /// returns true for it.
+    pub fn check_with_gate(&self, text: &str, gate: impl Fn(&LabelRule) -> bool) -> LabeledText {
+        self.check_rules(
+            text,
+            self.rules.iter().enumerate().filter(|(_, rule)| gate(rule)),
+        )
+    }
+
+    /// `check` for a text the caller has detected as `detected_lang`. Rules
+    /// with a different `lang` are skipped; rules without one always run.
+    /// Codes are compared case-insensitively.
+    pub fn check_lang(&self, text: &str, detected_lang: &str) -> LabeledText {
+        self.check_with_gate(text, |rule| {
+            rule.rule
+                .lang
+                .as_ref()
+                .is_none_or(|lang| lang.eq_ignore_ascii_case(detected_lang))
+        })
+    }
+
+    // Shared by the check variants: run the given (index, rule) pairs that
+    // get past the pre-filter, then the post-pass, stage by stage
+    fn check_rules<'a>(
+        &self,
+        text: &str,
+        rules: impl IntoIterator<Item = (usize, &'a LabelRule)>,
+    ) -> LabeledText {
         let mut labeled = LabeledText::new(text.to_string());
-        for rule in &self.0 {
-            rule.check(&mut labeled);
+        let prepared = self.preprocessed(text);
+        let candidates = self.prefilter.candidates(&prepared);
+        self.check_stages(
+            &prepared,
+            candidates.as_ref(),
+            rules,
+            &mut labeled,
+            |rule, text| self.rule_matches(rule, text),
+        );
+        #[cfg(feature = "metrics")]
+        {
+            telemetry::record_labels(&labeled.labels);
+            telemetry::record_shadow_hits(&self.shadow_hits(text));
+        }
+        labeled
+    }
+
+    // `check_rules` with the rule evaluation supplied by the caller, e.g. to
+    // match a document's fields. Rules outside `candidates` are skipped
+    pub(crate) fn check_stages<'a>(
+        &self,
+        prepared: &str,
+        candidates: Option<&Candidates>,
+        rules: impl IntoIterator<Item = (usize, &'a LabelRule)>,
+        labeled: &mut LabeledText,
+        mut matches: impl FnMut(&LabelRule, &str) -> bool,
+    ) {
+        if self.is_staged() {
+            let rules: Vec<(usize, &LabelRule)> = rules.into_iter().collect();
+            for stage in self.stages() {
+                let stage_rules =
+                    self.stage_rules(&rules, stage, |label| labeled.labels.contains(label));
+                self.check_stage(prepared, candidates, stage_rules, labeled, &mut matches);
+            }
+        } else {
+            self.check_stage(prepared, candidates, rules, labeled, &mut matches);
+        }
+    }
+
+    // One pass of `check_stages`, adding to the labels already on `labeled`
+    fn check_stage<'a>(
+        &self,
+        prepared: &str,
+        candidates: Option<&Candidates>,
+        rules: impl IntoIterator<Item = (usize, &'a LabelRule)>,
+        labeled: &mut LabeledText,
+        matches: &mut impl FnMut(&LabelRule, &str) -> bool,
+    ) {
+        let mut scores = HashMap::new();
+        let mut groups = GroupWinners::default();
+        let mut suppressors = Vec::new();
+        for (i, rule) in rules {
+            if candidates.is_some_and(|c| !c.contains(i)) {
+                continue;
+            }
+            if rule.suppresses() {
+                suppressors.push(rule);
+                continue;
+            }
+            if self.runs_in_shadow(rule) {
+                if matches(rule, prepared) {
+                    labeled.shadow_labels.insert(rule.label.clone());
+                }
+                continue;
+            }
+            if !rule.assigns_labels() {
+                continue;
+            }
+            if let Some(group) = &rule.group {
+                if groups.could_win(group, i, rule) && matches(rule, prepared) {
+                    groups.record(group, i, rule);
+                }
+            } else if matches(rule, prepared) {
+                if self.score_thresholds.contains_key(&rule.label) {
+                    *scores.entry(rule.label.clone()).or_insert(0.0) += rule.weight;
+                } else {
+                    labeled.labels.insert(rule.label.clone());
+                }
+            }
         }
+        labeled.labels.extend(self.labels_over_threshold(scores));
+        labeled.labels.extend(groups.labels());
+        labeled.labels = self.with_derived_labels(std::mem::take(&mut labeled.labels));
+        let suppressed = suppress::suppressed_labels(
+            suppressors,
+            prepared,
+            |label| labeled.labels.contains(label),
+            matches,
+        );
         labeled
+            .labels
+            .retain(|label| !suppressed.contains(&label.as_str()));
+    }
+
+    // Labels from the rules `on` picks, whether or not they're `active`,
+    // through the same stages and post-pass as `check`; used for what-if
+    // comparisons. Shadow rules never assign or suppress
+    fn labels_where(&self, text: &str, on: impl Fn(&LabelRule) -> bool) -> HashSet<String> {
+        let text = &*self.preprocessed(text);
+        let rules: Vec<(usize, &LabelRule)> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| on(rule) && !rule.shadow)
+            .collect();
+        let mut labels = Vec::new();
+        for stage in self.stages() {
+            let stage_rules =
+                self.stage_rules(&rules, stage, |label| labels.iter().any(|l| l == label));
+            self.fill_stage(&stage_rules, text, &mut labels, &mut |rule, text| {
+                rule.rule.check(text)
+            });
+        }
+        labels.into_iter().collect()
+    }
+
+    // Parents of the pattern labels count towards count rules, and labels
+    // from count rules bring their own parents
+    fn with_derived_labels(&self, mut labels: HashSet<String>) -> HashSet<String> {
+        self.add_implied_labels(&mut labels);
+        let mut derived: HashSet<String> = self.count_rule_labels(&labels).into_iter().collect();
+        self.add_implied_labels(&mut derived);
+        labels.extend(derived);
+        labels
+    }
+
+    /// Indices of the texts whose labels would change if the rule with
+    /// `uuid` had its `active` flag flipped. The box itself isn't changed.
+    pub fn preview_toggle(&self, uuid: &str, texts: &[String]) -> Vec<usize> {
+        let current = |rule: &LabelRule| rule.is_active();
+        let toggled = |rule: &LabelRule| {
+            if rule.uuid == uuid {
+                !rule.active && (!rule.is_scheduled() || rule.is_active_at(rule.clock.now()))
+            } else {
+                rule.is_active()
+            }
+        };
+        texts
+            .iter()
+            .enumerate()
+            .filter(|(_, text)| {
+                self.labels_where(text, current) != self.labels_where(text, toggled)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Label a text and also report which rules would have matched but were
+    /// vetoed, to help explain a missing label. `assigned` is what `check`
+    /// assigns.
+    pub fn check_debug(&self, text: &str) -> DebugResult {
+        let assigned = self.check(text).labels;
+        let text = &*self.preprocessed(text);
+        let vetoed = self
+            .rules
+            .iter()
+            .filter(|rule| rule.assigns_labels() && rule.rule.evaluate(text) == RuleOutcome::Vetoed)
+            .map(|rule| (rule.label.clone(), rule.uuid.clone()))
+            .collect();
+        DebugResult { assigned, vetoed }
+    }
+
+    /// `(label, uuid)` for each active shadow rule that matches the text (and
+    /// each inactive one, with `shadow_inactive`); these labels are never
+    /// part of a normal result.
+    pub fn shadow_hits(&self, text: &str) -> Vec<(String, String)> {
+        let text = &*self.preprocessed(text);
+        self.rules
+            .iter()
+            .filter(|rule| self.runs_in_shadow(rule) && rule.rule.check(text))
+            .map(|rule| (rule.label.clone(), rule.uuid.clone()))
+            .collect()
+    }
+
+    // Whether a rule is evaluated only to report its matches
+    fn runs_in_shadow(&self, rule: &LabelRule) -> bool {
+        if rule.is_active() {
+            rule.shadow
+        } else {
+            self.shadow_inactive
+        }
+    }
+
+    /// Every `(label, source, uuid)` assigned to a text, one entry per rule
+    /// `check_hits` credits with the label, so labels from several boxes can
+    /// be traced back. In `assign_labels` order; labels from count rules,
+    /// which no rule assigns, come with an empty `uuid`.
+    pub fn check_with_provenance(&self, text: &str) -> Vec<(String, String, String)> {
+        let mut provenance = Vec::new();
+        for hit in self.check_hits(text) {
+            if hit.rules.is_empty() {
+                provenance.push((hit.label, self.source.clone(), String::new()));
+                continue;
+            }
+            for uuid in hit.rules {
+                provenance.push((hit.label.clone(), self.source.clone(), uuid));
+            }
+        }
+        provenance
     }
 
     pub fn check_many(&self, texts: &[String]) -> Vec<HashSet<String>> {
         texts.iter().map(|t| self.check(t).labels).collect()
     }
 
+    /// The labels for `text`, each once, in a fixed order: pattern rules'
+    /// labels in the order of the first rule assigning each, then labels
+    /// reached by score thresholds (in `score_thresholds` order), group
+    /// winners (in the order of the winning rules), `implies` and count rules.
+    /// The same order as `assign_labels_vector`, and stable across runs.
     pub fn assign_labels(&self, text: &str) -> Vec<String> {
-        let labels = self.check(text).labels;
-        labels.into_iter().collect()
+        let mut labels = Vec::new();
+        self.fill_labels(&self.labeling_rules(), text, &mut labels);
+        #[cfg(feature = "metrics")]
+        telemetry::record_shadow_hits(&self.shadow_hits(text));
+        labels
     }
 
-    pub fn assign_labels_vector(&self, texts: &[String]) -> Vec<Vec<String>> {
-        // Optimized implementation: pre-filter active rules and use explicit loops
-        let active_rules: Vec<&LabelRule> = self.0.iter().filter(|rule| rule.active).collect();
+    /// Pick a single label: of the rules that assign a label in `check` (so
+    /// group winners, rules over their score threshold and rules whose label
+    /// isn't suppressed), the one with the highest priority wins, with ties
+    /// going to the earliest rule.
+    pub fn classify(&self, text: &str) -> Option<String> {
+        self.winning_rule(text).map(|rule| rule.label.clone())
+    }
+
+    /// Like `classify`, but also reports which rule won and its metadata.
+    pub fn classify_detailed(&self, text: &str) -> Option<ClassifyResult> {
+        self.winning_rule(text).map(|rule| ClassifyResult {
+            label: rule.label.clone(),
+            uuid: rule.uuid.clone(),
+            priority: rule.priority,
+            tags: rule.tags.clone(),
+        })
+    }
+
+    fn winning_rule(&self, text: &str) -> Option<&LabelRule> {
+        let (_, assigning) = self.assigning_rules(text);
+        let mut winner: Option<&LabelRule> = None;
+        for rule in assigning.into_iter().map(|i| &self.rules[i]) {
+            if winner.is_none_or(|w| rule.priority > w.priority) {
+                winner = Some(rule);
+            }
+        }
+        winner
+    }
+
+    /// `assign_labels` for each of `texts`, in order. Takes `&[String]`,
+    /// `&[&str]` or any other string slices, so borrowed texts needn't be
+    /// copied first.
+    pub fn assign_labels_vector<S: AsRef<str>>(&self, texts: &[S]) -> Vec<Vec<String>> {
         let mut results = Vec::with_capacity(texts.len());
+        self.assign_labels_vector_into(texts, &mut results);
+        results
+    }
+
+    /// `assign_labels_vector` over texts borrowed from anywhere, such as
+    /// lines of a memory-mapped file, without collecting them first.
+    pub fn assign_labels_iter<'a>(
+        &self,
+        texts: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<Vec<String>> {
+        let active_rules = self.labeling_rules();
+        texts
+            .into_iter()
+            .map(|text| {
+                let mut labels = Vec::new();
+                self.fill_labels(&active_rules, text, &mut labels);
+                labels
+            })
+            .collect()
+    }
+
+    /// `assign_labels_vector` writing into a reused buffer. `out` is resized
+    /// to `texts.len()` and each inner `Vec` is cleared and refilled, keeping
+    /// its capacity, so repeated batches don't reallocate. Takes any string
+    /// slices, e.g. `&str`s borrowed from an Arrow array.
+    pub fn assign_labels_vector_into<S: AsRef<str>>(
+        &self,
+        texts: &[S],
+        out: &mut Vec<Vec<String>>,
+    ) {
+        // Optimized implementation: pre-filter active rules and use explicit loops
+        let active_rules = self.labeling_rules();
+        out.resize_with(texts.len(), Vec::new);
+
+        for (text, labels) in texts.iter().zip(out.iter_mut()) {
+            self.fill_labels(&active_rules, text.as_ref(), labels);
+        }
+    }
+
+    pub(crate) fn labeling_rules(&self) -> Vec<(usize, &LabelRule)> {
+        self.rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule.assigns_labels() || rule.suppresses())
+            .collect()
+    }
+
+    // The per-text body of the batch paths: `labels` is cleared and refilled
+    pub(crate) fn fill_labels(
+        &self,
+        active_rules: &[(usize, &LabelRule)],
+        text: &str,
+        labels: &mut Vec<String>,
+    ) {
+        self.fill_labels_with(active_rules, text, labels, |rule, text| {
+            self.rule_matches(rule, text)
+        });
+    }
+
+    // `fill_labels` with the rule evaluation supplied by the caller, e.g. to
+    // time each rule
+    pub(crate) fn fill_labels_with(
+        &self,
+        active_rules: &[(usize, &LabelRule)],
+        text: &str,
+        labels: &mut Vec<String>,
+        mut matches: impl FnMut(&LabelRule, &str) -> bool,
+    ) {
+        labels.clear();
+        let text = &*self.preprocessed(text);
+        if self.is_staged() {
+            for stage in self.stages() {
+                let stage_rules = self.stage_rules(active_rules, stage, |label| {
+                    labels.iter().any(|l| l == label)
+                });
+                self.fill_stage(&stage_rules, text, labels, &mut matches);
+            }
+        } else {
+            self.fill_stage(active_rules, text, labels, &mut matches);
+        }
+        #[cfg(feature = "metrics")]
+        telemetry::record_labels(labels.iter());
+    }
 
-        for text in texts {
-            let mut labels = Vec::new();
-            for rule in &active_rules {
-                // Skip if we already have this label assigned
-                if !labels.contains(&rule.label) && rule.rule.check(text) {
-                    labels.push(rule.label.clone());
+    // One pass of `fill_labels_with`, adding to `labels`. `active_rules` are
+    // the rules that are on (not in shadow), so a suppressor is told apart by
+    // its kind alone
+    fn fill_stage(
+        &self,
+        active_rules: &[(usize, &LabelRule)],
+        text: &str,
+        labels: &mut Vec<String>,
+        matches: &mut impl FnMut(&LabelRule, &str) -> bool,
+    ) {
+        let mut scores = HashMap::new();
+        let mut groups = GroupWinners::default();
+        let candidates = self.prefilter.candidates(text);
+        let mut suppressors = Vec::new();
+        for &(i, rule) in active_rules {
+            if candidates.as_ref().is_some_and(|c| !c.contains(i)) {
+                continue;
+            }
+            if rule.kind == RuleKind::Suppress {
+                suppressors.push(rule);
+                continue;
+            }
+            if let Some(group) = &rule.group {
+                if groups.could_win(group, i, rule) && matches(rule, text) {
+                    groups.record(group, i, rule);
                 }
+                continue;
+            }
+            if self.score_thresholds.contains_key(&rule.label) {
+                if matches(rule, text) {
+                    *scores.entry(rule.label.clone()).or_insert(0.0) += rule.weight;
+                }
+                continue;
+            }
+            // Skip if we already have this label assigned
+            if !labels.contains(&rule.label) && matches(rule, text) {
+                labels.push(rule.label.clone());
             }
-            results.push(labels);
         }
-        results
+        for label in self.labels_over_threshold(scores).chain(groups.labels()) {
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        self.push_implied_labels(labels);
+        let pattern_labels = labels.len();
+        for label in self.count_rule_labels(labels.iter()) {
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        if labels.len() > pattern_labels {
+            self.push_implied_labels(labels);
+        }
+        let suppressed = suppress::suppressed_labels(
+            suppressors,
+            text,
+            |label| labels.iter().any(|l| l == label),
+            matches,
+        );
+        labels.retain(|label| !suppressed.contains(&label.as_str()));
+    }
+}
+
+/// Parse and compile a rulebox from JSON, like `RuleBox::from_json`.
+impl FromStr for RuleBox {
+    type Err = RuleBoxError;
+
+    fn from_str(json: &str) -> Result<Self, Self::Err> {
+        Self::from_json(json)
     }
 }
@@ -0,0 +1,54 @@
+// Caps on how much memory a single pattern may compile to, so one
+// pathological analyst-supplied pattern fails to load (naming its rule)
+// instead of exhausting a worker's memory.
+
+use regex::{RegexBuilder, RegexSetBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Limits passed to the regex crate's builders. Unset limits keep the regex
+/// crate's defaults. Set box-wide under `regex_limits`, or per rule to
+/// override the box for that rule's patterns.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegexLimits {
+    /// Bytes a compiled pattern may take; larger patterns fail to compile
+    /// with `CompileError::SizeLimitExceeded`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_limit: Option<usize>,
+    /// Bytes of cache the lazy DFA may use per search thread before it
+    /// falls back to slower engines. Never a compile error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dfa_size_limit: Option<usize>,
+}
+
+impl RegexLimits {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// These limits where set, otherwise `fallback`'s.
+    pub(crate) fn or(self, fallback: RegexLimits) -> RegexLimits {
+        RegexLimits {
+            size_limit: self.size_limit.or(fallback.size_limit),
+            dfa_size_limit: self.dfa_size_limit.or(fallback.dfa_size_limit),
+        }
+    }
+
+    pub(crate) fn apply(&self, builder: &mut RegexBuilder) {
+        if let Some(limit) = self.size_limit {
+            builder.size_limit(limit);
+        }
+        if let Some(limit) = self.dfa_size_limit {
+            builder.dfa_size_limit(limit);
+        }
+    }
+
+    pub(crate) fn apply_to_set(&self, builder: &mut RegexSetBuilder) {
+        if let Some(limit) = self.size_limit {
+            builder.size_limit(limit);
+        }
+        if let Some(limit) = self.dfa_size_limit {
+            builder.dfa_size_limit(limit);
+        }
+    }
+}
@@ -0,0 +1,243 @@
+// Warnings about rules that compile but are probably not what their author
+// meant: rules that match every text, repeated or redundant patterns, copies of
+// another rule and flags that change nothing.
+
+use crate::{is_ascii_only, CompileContext, MatchType, RegexRule, Rule, RuleBox};
+use regex_syntax::hir::{Hir, HirKind};
+use regex_syntax::ParserBuilder;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What `RuleBox::lint` found suspicious.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    /// A rule with nothing to match, or an empty pattern, so every text
+    /// matches.
+    MatchesEverything,
+    /// The same pattern twice in one of a rule's pattern lists.
+    DuplicatePattern,
+    /// The same label and patterns as an earlier rule.
+    DuplicateRule,
+    /// A flag that doesn't change what the pattern matches, e.g. `i` on a
+    /// pattern without letters.
+    UnusedFlag,
+    /// A pattern that can't change whether the rule matches, given another
+    /// in the same list, e.g. `cats` alongside `cat` in `or_patterns`.
+    RedundantPattern,
+}
+
+/// One warning from `RuleBox::lint`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintWarning {
+    /// The rule the warning is about.
+    pub uuid: String,
+    pub kind: LintKind,
+    pub message: String,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rule {}: {}", self.uuid, self.message)
+    }
+}
+
+// Flags that only change how the pattern is parsed, so an unchanged parse
+// means the flag does nothing
+const PARSE_FLAGS: [&str; 6] = ["i", "m", "s", "x", "U", "R"];
+
+impl RuleBox {
+    /// Check every rule for likely mistakes that still compile, in box
+    /// order. Only flags a pattern lists itself are checked, not the box's
+    /// `default_flags`. An empty list means nothing was found.
+    pub fn lint(&self) -> Vec<LintWarning> {
+        let ctx = CompileContext {
+            default_flags: &self.default_flags,
+            flag_presets: Some(&self.flag_presets),
+            definitions: Some(&self.definitions),
+            cache: None,
+            limits: self.regex_limits,
+        };
+        let mut warnings = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            let mut warn = |kind, message: String| {
+                warnings.push(LintWarning {
+                    uuid: rule.uuid.clone(),
+                    kind,
+                    message,
+                })
+            };
+
+            if !rule.rule.has_any_of()
+                && rule.rule.and_patterns.is_empty()
+                && rule.rule.near_patterns.is_empty()
+                && rule.rule.expr.is_none()
+            {
+                warn(
+                    LintKind::MatchesEverything,
+                    "has no patterns to match, so it matches every text".to_string(),
+                );
+            }
+            let rule_patterns = rule.rule.patterns();
+            for pattern in &rule_patterns {
+                if pattern.pattern.is_empty() && pattern.match_mode.is_none() {
+                    warn(
+                        LintKind::MatchesEverything,
+                        "has an empty pattern, which matches every text".to_string(),
+                    );
+                }
+            }
+
+            for (list, patterns) in pattern_lists(&rule.rule) {
+                for (j, pattern) in patterns.iter().enumerate() {
+                    if patterns[..j].iter().any(|p| same_pattern(p, pattern)) {
+                        warn(
+                            LintKind::DuplicatePattern,
+                            format!("lists {:?} more than once in {}", pattern.pattern, list),
+                        );
+                    }
+                }
+                for (redundant, given) in redundant_patterns(list, patterns, &ctx) {
+                    warn(
+                        LintKind::RedundantPattern,
+                        format!(
+                            "{:?} in {} is redundant given {:?}",
+                            redundant.pattern, list, given.pattern
+                        ),
+                    );
+                }
+            }
+
+            for pattern in rule_patterns {
+                for flag in unused_flags(pattern, &ctx) {
+                    warn(
+                        LintKind::UnusedFlag,
+                        format!("flag {} has no effect on {:?}", flag, pattern.pattern),
+                    );
+                }
+            }
+
+            if let Some(earlier) = self.rules[..i].iter().find(|earlier| {
+                earlier.label == rule.label
+                    && earlier.kind == rule.kind
+                    && serde_json::to_value(&earlier.rule).ok()
+                        == serde_json::to_value(&rule.rule).ok()
+            }) {
+                warn(
+                    LintKind::DuplicateRule,
+                    format!("has the same label and patterns as rule {}", earlier.uuid),
+                );
+            }
+        }
+        warnings
+    }
+}
+
+fn pattern_lists(rule: &Rule) -> [(&'static str, &[RegexRule]); 3] {
+    [
+        ("and_patterns", &rule.and_patterns),
+        ("or_patterns", &rule.or_patterns),
+        ("not_patterns", &rule.not_patterns),
+    ]
+}
+
+fn same_pattern(a: &RegexRule, b: &RegexRule) -> bool {
+    a.pattern == b.pattern
+        && a.flags == b.flags
+        && a.match_type == b.match_type
+        && a.match_mode == b.match_mode
+        && a.max_distance == b.max_distance
+        && a.min_count == b.min_count
+        && a.field == b.field
+        && a.fields == b.fields
+}
+
+// `(redundant, given)` pairs of plain literal patterns in one list. In
+// `or_patterns` and `not_patterns` a literal containing another can only match
+// where the shorter one does; in `and_patterns` it implies the shorter one
+fn redundant_patterns<'r>(
+    list: &str,
+    patterns: &'r [RegexRule],
+    ctx: &CompileContext,
+) -> Vec<(&'r RegexRule, &'r RegexRule)> {
+    let literals: Vec<(&RegexRule, Vec<u8>)> = patterns
+        .iter()
+        .filter(|p| p.min_count.is_none())
+        .filter_map(|p| literal(p, ctx).map(|lit| (p, lit)))
+        .collect();
+    let mut pairs = Vec::new();
+    for (longer, long) in &literals {
+        for (shorter, short) in &literals {
+            let comparable = long.len() > short.len()
+                && longer.flags == shorter.flags
+                && longer.field == shorter.field
+                && longer.fields == shorter.fields;
+            if !comparable || !long.windows(short.len()).any(|w| w == short.as_slice()) {
+                continue;
+            }
+            if list == "and_patterns" {
+                pairs.push((*shorter, *longer));
+            } else {
+                pairs.push((*longer, *shorter));
+            }
+        }
+    }
+    pairs
+}
+
+// The bytes a pattern matches when it's a plain literal
+fn literal(pattern: &RegexRule, ctx: &CompileContext) -> Option<Vec<u8>> {
+    if pattern.match_type != MatchType::Regex {
+        return None;
+    }
+    let flags = pattern.effective_flags(ctx).ok()?;
+    let hir = parse(pattern, &flags, ctx)?;
+    match hir.kind() {
+        HirKind::Literal(literal) => Some(literal.0.to_vec()),
+        _ => None,
+    }
+}
+
+// The pattern's own flags, with presets expanded; `None` for patterns that
+// rely on the box defaults or aren't regexes
+fn pattern_flags<'c>(pattern: &'c RegexRule, ctx: &'c CompileContext) -> Option<Vec<&'c str>> {
+    if pattern.flags.is_empty() || pattern.match_type != MatchType::Regex {
+        return None;
+    }
+    pattern.effective_flags(ctx).ok()
+}
+
+fn unused_flags<'c>(pattern: &'c RegexRule, ctx: &'c CompileContext) -> Vec<&'c str> {
+    let Some(flags) = pattern_flags(pattern, ctx) else {
+        return Vec::new();
+    };
+    let Some(hir) = parse(pattern, &flags, ctx) else {
+        return Vec::new();
+    };
+    let mut unused: Vec<&str> = Vec::new();
+    for flag in flags.iter().filter(|f| PARSE_FLAGS.contains(f)) {
+        if unused.contains(flag) {
+            continue;
+        }
+        let without: Vec<&str> = flags.iter().filter(|f| *f != flag).copied().collect();
+        if parse(pattern, &without, ctx).is_some_and(|other| other == hir) {
+            unused.push(flag);
+        }
+    }
+    unused
+}
+
+// The pattern parsed as the regex crate would compile it with `flags`
+fn parse(pattern: &RegexRule, flags: &[&str], ctx: &CompileContext) -> Option<Hir> {
+    ParserBuilder::new()
+        .case_insensitive(flags.contains(&"i"))
+        .multi_line(flags.contains(&"m"))
+        .dot_matches_new_line(flags.contains(&"s"))
+        .ignore_whitespace(flags.contains(&"x"))
+        .swap_greed(flags.contains(&"U"))
+        .crlf(flags.contains(&"R"))
+        .unicode(!is_ascii_only(flags))
+        .build()
+        .parse(&pattern.regex_source(flags, ctx).ok()?)
+        .ok()
+}
@@ -0,0 +1,60 @@
+// Plain-string patterns: with a `match_mode` the pattern is a literal, and the
+// escaping and anchors or word boundaries are added here rather than by hand.
+
+use serde::{Deserialize, Serialize};
+
+/// How a literal pattern has to sit in the text. Without one the pattern is a
+/// regex, used as written.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    /// Anywhere in the text.
+    Contains,
+    /// As a whole word: not directly preceded or followed by a word
+    /// character, so `cat` doesn't match `concatenate` and `C++` doesn't
+    /// match `C++x`.
+    Word,
+    /// The whole text.
+    Exact,
+    StartsWith,
+    EndsWith,
+}
+
+impl MatchMode {
+    /// The regex for `literal` in this mode. `ascii` is whether the regex is
+    /// built with ASCII-only word characters.
+    pub(crate) fn source(self, literal: &str, ascii: bool) -> String {
+        let escaped = regex::escape(literal);
+        match self {
+            MatchMode::Contains => escaped,
+            MatchMode::Word => {
+                // `\b` only means "not next to a word character" beside one;
+                // beside punctuation that takes `\B`
+                let boundary = |c: Option<char>| match c {
+                    Some(c) if is_word_char(c, ascii) => r"\b",
+                    Some(_) => r"\B",
+                    None => "",
+                };
+                format!(
+                    "{}{}{}",
+                    boundary(literal.chars().next()),
+                    escaped,
+                    boundary(literal.chars().next_back())
+                )
+            }
+            MatchMode::Exact => format!(r"\A{}\z", escaped),
+            MatchMode::StartsWith => format!(r"\A{}", escaped),
+            MatchMode::EndsWith => format!(r"{}\z", escaped),
+        }
+    }
+}
+
+// What `\w` matches in a regex built in ASCII mode or otherwise
+fn is_word_char(c: char, ascii: bool) -> bool {
+    if ascii {
+        c.is_ascii_alphanumeric() || c == '_'
+    } else {
+        c.is_alphanumeric() || c == '_'
+    }
+}
@@ -0,0 +1,185 @@
+// Combining rule files kept per policy area into one box, optionally with each
+// file's labels under its own prefix so two areas can both have e.g. `other`.
+
+use crate::{RuleBox, RuleBoxError};
+use std::collections::HashSet;
+
+impl RuleBox {
+    /// Put `prefix` in front of every label the box assigns or refers to:
+    /// rule labels, `requires_labels`, `excludes_labels`, `score_thresholds`,
+    /// `implies` and count rules. Group names get it too, so groups from
+    /// different files stay apart. A count rule with no `among` counts only
+    /// this box's labels afterwards. The prefix is used as given, so include
+    /// a separator, e.g. `"health."`.
+    pub fn with_label_prefix(mut self, prefix: &str) -> Self {
+        let prefixed = |label: &str| format!("{}{}", prefix, label);
+        for rule in &mut self.rules {
+            rule.label = prefixed(&rule.label);
+            for label in rule
+                .requires_labels
+                .iter_mut()
+                .chain(&mut rule.excludes_labels)
+            {
+                *label = prefixed(label);
+            }
+            if let Some(group) = &mut rule.group {
+                *group = prefixed(group);
+            }
+        }
+        self.score_thresholds = std::mem::take(&mut self.score_thresholds)
+            .into_iter()
+            .map(|(label, threshold)| (prefixed(&label), threshold))
+            .collect();
+        self.implies = std::mem::take(&mut self.implies)
+            .into_iter()
+            .map(|(label, parents)| {
+                let parents = parents.iter().map(|p| prefixed(p)).collect();
+                (prefixed(&label), parents)
+            })
+            .collect();
+        for rule in &mut self.count_rules {
+            rule.then = prefixed(&rule.then);
+            if rule.among.is_empty() {
+                rule.among = vec![prefixed("*")];
+            } else {
+                rule.among = rule.among.iter().map(|glob| prefixed(glob)).collect();
+            }
+        }
+        self
+    }
+
+    /// Append `other`'s rules and settings to this box. Where the boxes'
+    /// `default_flags` differ, each box's are written onto its own patterns
+    /// (and the merged box has none); `other`'s `regex_limits` likewise go
+    /// onto its rules. Every rule keeps matching as before. Fails, leaving
+    /// this box unchanged, if the boxes disagree on `preprocess`,
+    /// `allow_negative_scores`, a flag preset, a definition or a score
+    /// threshold, share a rule UUID, or `other` has a rule that doesn't
+    /// compile.
+    pub fn merge(&mut self, mut other: RuleBox) -> Result<(), RuleBoxError> {
+        self.check_mergeable(&other)
+            .map_err(RuleBoxError::MergeConflict)?;
+
+        let flags_differ = other.default_flags != self.default_flags;
+        if flags_differ {
+            other.inline_default_flags();
+        }
+        if other.regex_limits != self.regex_limits {
+            for rule in &mut other.rules {
+                rule.regex_limits = rule.regex_limits.or(other.regex_limits);
+            }
+        }
+        // Compiled against its own settings before this box is touched, so a
+        // rule that doesn't compile leaves it as it was
+        other.recompile_dirty()?;
+
+        if flags_differ {
+            self.inline_default_flags();
+        }
+        self.flag_presets.append(&mut other.flag_presets);
+        self.definitions.append(&mut other.definitions);
+        self.score_thresholds.append(&mut other.score_thresholds);
+        for (label, parents) in other.implies {
+            let merged = self.implies.entry(label).or_default();
+            for parent in parents {
+                if !merged.contains(&parent) {
+                    merged.push(parent);
+                }
+            }
+        }
+        self.count_rules.append(&mut other.count_rules);
+        // Shadow evaluation only adds reporting, so either box can turn it on
+        self.shadow_inactive |= other.shadow_inactive;
+        self.rules.append(&mut other.rules);
+        self.rebuild_prefilter();
+        Ok(())
+    }
+
+    // Give patterns without flags the box's default flags explicitly, and
+    // clear the defaults; the patterns match exactly as before
+    fn inline_default_flags(&mut self) {
+        let defaults = std::mem::take(&mut self.default_flags);
+        if defaults.is_empty() {
+            return;
+        }
+        for rule in &mut self.rules {
+            for pattern in rule.rule.patterns_mut() {
+                if pattern.flags.is_empty() {
+                    pattern.flags = defaults.clone();
+                }
+            }
+        }
+    }
+
+    fn check_mergeable(&self, other: &RuleBox) -> Result<(), String> {
+        if other.preprocess != self.preprocess {
+            return Err("the boxes have different preprocess steps".to_string());
+        }
+        if other.allow_negative_scores != self.allow_negative_scores {
+            return Err("the boxes disagree on allow_negative_scores".to_string());
+        }
+        for (name, flags) in &other.flag_presets {
+            if self.flag_presets.get(name).is_some_and(|own| own != flags) {
+                return Err(format!("flag preset @{} is defined differently", name));
+            }
+        }
+        for (name, definition) in &other.definitions {
+            if self
+                .definitions
+                .get(name)
+                .is_some_and(|own| own != definition)
+            {
+                return Err(format!("definition {} is defined differently", name));
+            }
+        }
+        for (label, threshold) in &other.score_thresholds {
+            if self
+                .score_thresholds
+                .get(label)
+                .is_some_and(|own| own != threshold)
+            {
+                return Err(format!("score threshold for {} is set differently", label));
+            }
+        }
+        let uuids: HashSet<&str> = self.rules.iter().map(|rule| rule.uuid.as_str()).collect();
+        if let Some(rule) = other
+            .rules
+            .iter()
+            .find(|rule| uuids.contains(rule.uuid.as_str()))
+        {
+            return Err(format!("rule {} is in both boxes", rule.uuid));
+        }
+        Ok(())
+    }
+
+    /// Load several rule files into one box, in order. See `merge`.
+    #[cfg(feature = "fs")]
+    pub fn from_paths<S: AsRef<str>>(paths: &[S]) -> Result<Self, RuleBoxError> {
+        merge_all(paths.iter().map(|path| RuleBox::from_path(path.as_ref())))
+    }
+
+    /// `from_paths` with a label prefix per file, as `(path, prefix)` pairs.
+    /// See `with_label_prefix`.
+    #[cfg(feature = "fs")]
+    pub fn from_paths_with_prefixes(files: &[(&str, &str)]) -> Result<Self, RuleBoxError> {
+        merge_all(files.iter().map(|(path, prefix)| {
+            RuleBox::from_path(path).map(|rulebox| rulebox.with_label_prefix(prefix))
+        }))
+    }
+}
+
+// The first box takes the rest, so its settings are the merged box's
+#[cfg(feature = "fs")]
+fn merge_all(
+    boxes: impl Iterator<Item = Result<RuleBox, RuleBoxError>>,
+) -> Result<RuleBox, RuleBoxError> {
+    let mut merged: Option<RuleBox> = None;
+    for rulebox in boxes {
+        let rulebox = rulebox?;
+        match &mut merged {
+            Some(merged) => merged.merge(rulebox)?,
+            None => merged = Some(rulebox),
+        }
+    }
+    Ok(merged.unwrap_or_default())
+}
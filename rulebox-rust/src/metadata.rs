@@ -0,0 +1,47 @@
+// Descriptive metadata on rules, for surfacing provenance outside the engine.
+// None of it affects matching.
+
+use crate::{LabelRule, RuleBox};
+use serde::{Deserialize, Serialize};
+
+/// Who wrote a rule, why, and how much a match matters. Timestamps are kept
+/// as written, conventionally RFC 3339 (`2024-05-01T12:00:00Z`).
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    /// Free-form, e.g. `"low"` or `"critical"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+}
+
+impl RuleMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == RuleMetadata::default()
+    }
+}
+
+impl LabelRule {
+    /// The rule's metadata; empty if the rule file gave none. Tags are on the
+    /// rule itself (`tags`).
+    pub fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+}
+
+impl RuleBox {
+    /// Metadata for the rule with `uuid`, if there is one.
+    pub fn rule_metadata(&self, uuid: &str) -> Option<&RuleMetadata> {
+        self.rules
+            .iter()
+            .find(|rule| rule.uuid == uuid)
+            .map(LabelRule::metadata)
+    }
+}
@@ -0,0 +1,70 @@
+// Editing a compiled box in place, for services that hot-update their rules
+// without reloading the whole file. Only the rule being added or replaced is
+// compiled; the box-wide pre-filter is re-collected and rebuilt lazily.
+
+use crate::{CompileContext, CompileError, LabelRule, RuleBox};
+
+impl RuleBox {
+    /// Compile `rule` against the box settings and append it.
+    pub fn add_rule(&mut self, mut rule: LabelRule) -> Result<(), CompileError> {
+        self.compile_rule(&mut rule)?;
+        self.rules.push(rule);
+        self.rebuild_prefilter();
+        Ok(())
+    }
+
+    /// Remove the rule with `uuid`, returning it.
+    pub fn remove_rule(&mut self, uuid: &str) -> Option<LabelRule> {
+        let index = self.rules.iter().position(|rule| rule.uuid == uuid)?;
+        let removed = self.rules.remove(index);
+        self.patterns.prune();
+        self.rebuild_prefilter();
+        Some(removed)
+    }
+
+    /// Replace the rule with `uuid` by `rule`, keeping its place in the box,
+    /// and return the old one. `rule` gets the next `version`, and the old
+    /// rule's `history` (taken off the returned rule) with the old rule
+    /// itself added, timed by the box's clock (see `with_clock`). `Ok(None)`
+    /// means there was no such rule and nothing changed; a rule that doesn't
+    /// compile also leaves the box as it was.
+    pub fn update_rule(
+        &mut self,
+        uuid: &str,
+        mut rule: LabelRule,
+    ) -> Result<Option<LabelRule>, CompileError> {
+        let Some(index) = self.rules.iter().position(|rule| rule.uuid == uuid) else {
+            return Ok(None);
+        };
+        self.compile_rule(&mut rule)?;
+        self.rules[index].supersede(&mut rule, self.clock.now());
+        let old = std::mem::replace(&mut self.rules[index], rule);
+        self.patterns.prune();
+        self.rebuild_prefilter();
+        Ok(Some(old))
+    }
+
+    /// Switch the rule with `uuid` on or off. Returns false if there's no such
+    /// rule.
+    pub fn set_active(&mut self, uuid: &str, active: bool) -> bool {
+        match self.rules.iter_mut().find(|rule| rule.uuid == uuid) {
+            Some(rule) => {
+                rule.active = active;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn compile_rule(&self, rule: &mut LabelRule) -> Result<(), CompileError> {
+        rule.clock = self.clock.clone();
+        let ctx = CompileContext {
+            default_flags: &self.default_flags,
+            flag_presets: Some(&self.flag_presets),
+            definitions: Some(&self.definitions),
+            cache: Some(&self.patterns),
+            limits: self.regex_limits,
+        };
+        rule.compile_with(&ctx)
+    }
+}
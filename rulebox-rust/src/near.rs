@@ -0,0 +1,105 @@
+// Proximity constraints: two patterns that have to match close together, e.g.
+// "refund" a few words from "demand", which `and_patterns` can't tell apart
+// from the two words at opposite ends of a long letter. `not_within` is the
+// same idea for vetoes: a disclaimer in the footer of a long document
+// shouldn't veto a match in its first paragraph.
+
+use crate::{is_default, CompileContext, CompileError, RegexRule, Rule};
+use serde::{Deserialize, Serialize};
+
+/// What `NearPattern::within` counts.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Proximity {
+    /// Characters between the two matches.
+    #[default]
+    Chars,
+    /// Words between the two matches, a word being a run of letters, digits
+    /// or `_`.
+    Words,
+}
+
+/// Two patterns that must both match with at most `within` characters (or
+/// words) between the end of one match and the start of the other, in
+/// either order. Overlapping matches are as near as can be.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NearPattern {
+    pub first: RegexRule,
+    pub second: RegexRule,
+    pub within: usize,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub unit: Proximity,
+}
+
+impl NearPattern {
+    pub(crate) fn compile_with(&mut self, ctx: &CompileContext) -> Result<(), CompileError> {
+        self.first.compile_with(ctx)?;
+        self.second.compile_with(ctx)
+    }
+
+    pub(crate) fn check(&self, text: &str) -> bool {
+        // `check` first, so a `min_count` on either pattern still applies
+        if !self.first.check(text) || !self.second.check(text) {
+            return false;
+        }
+        let seconds = self.second.find_spans(text);
+        self.first.find_spans(text).into_iter().any(|first| {
+            seconds
+                .iter()
+                .any(|&second| self.gap(text, first, second) <= self.within)
+        })
+    }
+
+    fn gap(&self, text: &str, a: (usize, usize), b: (usize, usize)) -> usize {
+        let between = between(text, a, b);
+        match self.unit {
+            Proximity::Chars => between.chars().count(),
+            Proximity::Words => between
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .filter(|word| !word.is_empty())
+                .count(),
+        }
+    }
+}
+
+impl Rule {
+    /// Whether a matching `not_pattern` vetoes the rule: always, unless
+    /// `not_within` limits vetoes to matches that many characters or fewer
+    /// from a match of one of the positive patterns or keywords.
+    pub(crate) fn vetoes(&self, pattern: &RegexRule, text: &str) -> bool {
+        if !pattern.check(text) {
+            return false;
+        }
+        let Some(within) = self.not_within else {
+            return true;
+        };
+        let positives: Vec<(usize, usize)> = self
+            .positive_patterns()
+            .into_iter()
+            .flat_map(|p| p.find_spans(text))
+            .chain(
+                self.keyword_patterns
+                    .iter()
+                    .flat_map(|k| k.find_spans(text)),
+            )
+            .collect();
+        pattern.find_spans(text).into_iter().any(|negative| {
+            positives
+                .iter()
+                .any(|&positive| between(text, negative, positive).chars().count() <= within)
+        })
+    }
+}
+
+// The text between two matches, empty if they overlap
+fn between(text: &str, a: (usize, usize), b: (usize, usize)) -> &str {
+    if a.1 <= b.0 {
+        &text[a.1..b.0]
+    } else if b.1 <= a.0 {
+        &text[b.1..a.0]
+    } else {
+        ""
+    }
+}
@@ -0,0 +1,106 @@
+// Text normalization applied before matching, with enough bookkeeping to map
+// match offsets back onto the original text.
+
+/// A normalized copy of a text plus the original byte offset of every
+/// normalized character.
+#[derive(Debug)]
+pub struct NormalizedText {
+    pub text: String,
+    offsets: Vec<usize>,
+    original_len: usize,
+}
+
+impl NormalizedText {
+    /// Remove separator runs (anything that isn't a letter or digit) that sit
+    /// between two digits, so "555-12 34" becomes "5551234".
+    pub fn digits(original: &str) -> Self {
+        let chars: Vec<(usize, char)> = original.char_indices().collect();
+        let mut text = String::with_capacity(original.len());
+        let mut offsets = Vec::with_capacity(original.len());
+
+        let mut i = 0;
+        while i < chars.len() {
+            let (pos, c) = chars[i];
+            if c.is_ascii_digit() {
+                // Look ahead over a separator run; drop it if a digit follows
+                let mut j = i + 1;
+                while j < chars.len() && !chars[j].1.is_alphanumeric() {
+                    j += 1;
+                }
+                if j > i + 1 && j < chars.len() && chars[j].1.is_ascii_digit() {
+                    push_char(&mut text, &mut offsets, pos, c);
+                    i = j;
+                    continue;
+                }
+            }
+            push_char(&mut text, &mut offsets, pos, c);
+            i += 1;
+        }
+
+        Self {
+            text,
+            offsets,
+            original_len: original.len(),
+        }
+    }
+
+    // Build from `(original byte offset, char)` pairs in output order
+    pub(crate) fn from_chars(original: &str, chars: Vec<(usize, char)>) -> Self {
+        let mut text = String::with_capacity(original.len());
+        let mut offsets = Vec::with_capacity(original.len());
+        for (pos, c) in chars {
+            push_char(&mut text, &mut offsets, pos, c);
+        }
+        Self {
+            text,
+            offsets,
+            original_len: original.len(),
+        }
+    }
+
+    /// Map a byte span in the normalized text back onto the original text.
+    /// Separators removed from inside the span are included in the result.
+    /// Offsets inside a multibyte character are moved back to its start, so
+    /// the result always lies on character boundaries.
+    pub fn original_span(&self, start: usize, end: usize) -> (usize, usize) {
+        let start = floor_char_boundary(&self.text, start);
+        let end = floor_char_boundary(&self.text, end);
+        let orig_start = self
+            .offsets
+            .get(start)
+            .copied()
+            .unwrap_or(self.original_len);
+        if end <= start {
+            return (orig_start, orig_start);
+        }
+        let orig_end = match self
+            .text
+            .get(..end)
+            .and_then(|s| s.char_indices().next_back())
+        {
+            Some((last, c)) => self.offsets[last] + c.len_utf8(),
+            None => orig_start,
+        };
+        (orig_start, orig_end)
+    }
+}
+
+/// The largest char boundary of `text` at or before `index` (clamped to the
+/// text's length).
+pub(crate) fn floor_char_boundary(text: &str, index: usize) -> usize {
+    if index >= text.len() {
+        return text.len();
+    }
+    (0..=index)
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(0)
+}
+
+fn push_char(text: &mut String, offsets: &mut Vec<usize>, pos: usize, c: char) {
+    // One offset entry per byte keeps lookups O(1) for any char boundary
+    for k in 0..c.len_utf8() {
+        offsets.push(pos + k);
+    }
+    text.push(c);
+}
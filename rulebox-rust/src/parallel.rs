@@ -0,0 +1,23 @@
+// Batch labelling spread across threads for large corpora.
+
+use crate::RuleBox;
+use rayon::prelude::*;
+
+impl RuleBox {
+    /// `assign_labels_vector` run on rayon's global thread pool. Every thread
+    /// shares the same compiled rules; results come back in input order.
+    pub fn assign_labels_vector_parallel<S: AsRef<str> + Sync>(
+        &self,
+        texts: &[S],
+    ) -> Vec<Vec<String>> {
+        let active_rules = self.labeling_rules();
+        texts
+            .par_iter()
+            .map(|text| {
+                let mut labels = Vec::new();
+                self.fill_labels(&active_rules, text.as_ref(), &mut labels);
+                labels
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,95 @@
+// A box-wide first pass for large rule sets: one `RegexSet` over a pattern
+// from every rule that can't match without it, so a text only has to be
+// checked against the rules whose patterns actually occur in it.
+
+use crate::lazy_set::LazyRegexSet;
+use crate::{CompileContext, LabelRule, Rule};
+use regex::RegexSet;
+
+// Below this many filterable rules, checking rule by rule is as cheap as a
+// pass over the set
+const MIN_RULES: usize = 32;
+
+#[derive(Debug, Default)]
+pub(crate) struct Prefilter {
+    set: LazyRegexSet,
+    // Rule index for each pattern in the set
+    owners: Vec<usize>,
+    // Rules the set can't rule out, e.g. ones without positive patterns
+    unfiltered: Vec<usize>,
+    rule_count: usize,
+}
+
+impl Prefilter {
+    pub(crate) fn build(rules: &[LabelRule], ctx: &CompileContext) -> Self {
+        let mut sources = Vec::new();
+        let mut owners = Vec::new();
+        let mut unfiltered = Vec::new();
+        for (i, rule) in rules.iter().enumerate() {
+            match required_sources(&rule.rule, ctx) {
+                Some(required) => {
+                    owners.extend(std::iter::repeat_n(i, required.len()));
+                    sources.extend(required);
+                }
+                None => unfiltered.push(i),
+            }
+        }
+        if rules.len() - unfiltered.len() < MIN_RULES {
+            return Self::default();
+        }
+        Self {
+            set: LazyRegexSet::new(sources, ctx.limits),
+            owners,
+            unfiltered,
+            rule_count: rules.len(),
+        }
+    }
+
+    /// Whether each rule could match `text`, indexed like the rules the
+    /// filter was built from. `None` when there's no filter to apply.
+    pub(crate) fn candidates(&self, text: &str) -> Option<Candidates> {
+        let set: &RegexSet = self.set.get()?;
+        let mut possible = vec![false; self.rule_count];
+        for i in &self.unfiltered {
+            possible[*i] = true;
+        }
+        for i in set.matches(text).iter() {
+            possible[self.owners[i]] = true;
+        }
+        Some(Candidates(possible))
+    }
+}
+
+pub(crate) struct Candidates(Vec<bool>);
+
+impl Candidates {
+    /// Rules added after the filter was built are always candidates.
+    pub(crate) fn contains(&self, index: usize) -> bool {
+        self.0.get(index).copied().unwrap_or(true)
+    }
+}
+
+// Patterns at least one of which must occur for the rule to match, or `None`
+// if the rule can match without any of them
+fn required_sources(rule: &Rule, ctx: &CompileContext) -> Option<Vec<String>> {
+    // The set sees the raw text, not the digit-normalized copy
+    if rule.digit_normalize {
+        return None;
+    }
+    if !rule.and_patterns.is_empty() {
+        // Every and pattern has to occur, so any one of them will do
+        let first = rule
+            .and_patterns
+            .iter()
+            .find_map(|p| p.presence_source(ctx))?;
+        return Some(vec![first]);
+    }
+    // Keywords are already a single pass of their own
+    if rule.or_patterns.is_empty() || rule.keyword_patterns.is_some() {
+        return None;
+    }
+    rule.or_patterns
+        .iter()
+        .map(|p| p.presence_source(ctx))
+        .collect()
+}
@@ -0,0 +1,172 @@
+// Box-wide text preprocessing declared in the rule file, applied to every text
+// before any rule sees it.
+
+use crate::normalize::NormalizedText;
+use crate::{CompileError, RuleBox};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Steps applied, in field order, to each text before matching. Offsets
+/// reported by the span methods still refer to the original text.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preprocess {
+    /// Replace each HTML tag or comment (`<...>`) with a space. Entities such
+    /// as `&amp;` are left as they are.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub strip_html: bool,
+    /// Unicode NFKC normalization, e.g. `ﬁ` to `fi` and full-width `Ａ` to
+    /// `A`. Requires the `unicode` feature.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub nfkc: bool,
+    /// Lowercase the text (Unicode-aware, via `char::to_lowercase`).
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub lowercase: bool,
+    /// Replace each run of whitespace, including newlines, with one space.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub collapse_whitespace: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl Preprocess {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), CompileError> {
+        if self.nfkc && !cfg!(feature = "unicode") {
+            return Err(CompileError::InvalidPreprocess {
+                reason: "nfkc requires the `unicode` feature".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl NormalizedText {
+    /// Apply `steps` to `original`, keeping track of where each output
+    /// character came from.
+    pub fn preprocess(original: &str, steps: &Preprocess) -> Self {
+        let mut chars: Vec<(usize, char)> = original.char_indices().collect();
+        if steps.strip_html {
+            chars = strip_html(&chars);
+        }
+        if steps.nfkc {
+            chars = nfkc(&chars);
+        }
+        if steps.lowercase {
+            chars = chars
+                .into_iter()
+                .flat_map(|(pos, c)| c.to_lowercase().map(move |lower| (pos, lower)))
+                .collect();
+        }
+        if steps.collapse_whitespace {
+            chars = collapse_whitespace(&chars);
+        }
+        Self::from_chars(original, chars)
+    }
+}
+
+// A tag starts with `<` followed by a letter, `/`, `!` or `?` and runs to the
+// next `>`; a `<` without a closing `>` is kept as text
+fn strip_html(chars: &[(usize, char)]) -> Vec<(usize, char)> {
+    let mut out = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        let opens_tag = c == '<'
+            && chars
+                .get(i + 1)
+                .is_some_and(|&(_, n)| n.is_ascii_alphabetic() || matches!(n, '/' | '!' | '?'));
+        if opens_tag {
+            if let Some(len) = chars[i..].iter().position(|&(_, c)| c == '>') {
+                out.push((pos, ' '));
+                i += len + 1;
+                continue;
+            }
+        }
+        out.push((pos, c));
+        i += 1;
+    }
+    out
+}
+
+// NFKC over runs that start at a starter, so each output character maps back
+// to the start of the run it came from
+#[cfg(feature = "unicode")]
+fn nfkc(chars: &[(usize, char)]) -> Vec<(usize, char)> {
+    use unicode_normalization::char::canonical_combining_class;
+    use unicode_normalization::UnicodeNormalization;
+
+    // Hangul vowel and trailing jamo compose with the preceding syllable
+    // despite being starters
+    let starts_run =
+        |c: char| canonical_combining_class(c) == 0 && !('\u{1160}'..='\u{11FF}').contains(&c);
+
+    let mut out = Vec::with_capacity(chars.len());
+    let mut run_start = 0;
+    for end in 1..=chars.len() {
+        if end == chars.len() || starts_run(chars[end].1) {
+            let pos = chars[run_start].0;
+            out.extend(
+                chars[run_start..end]
+                    .iter()
+                    .map(|&(_, c)| c)
+                    .nfkc()
+                    .map(|c| (pos, c)),
+            );
+            run_start = end;
+        }
+    }
+    out
+}
+
+// Rejected by `Preprocess::validate` before any text gets here
+#[cfg(not(feature = "unicode"))]
+fn nfkc(chars: &[(usize, char)]) -> Vec<(usize, char)> {
+    chars.to_vec()
+}
+
+fn collapse_whitespace(chars: &[(usize, char)]) -> Vec<(usize, char)> {
+    let mut out: Vec<(usize, char)> = Vec::with_capacity(chars.len());
+    let mut in_run = false;
+    for &(pos, c) in chars {
+        if c.is_whitespace() {
+            if !in_run {
+                out.push((pos, ' '));
+            }
+            in_run = true;
+        } else {
+            out.push((pos, c));
+            in_run = false;
+        }
+    }
+    out
+}
+
+impl RuleBox {
+    /// Set the box's preprocessing steps, checking they can be used.
+    pub fn with_preprocess(mut self, steps: Preprocess) -> Result<Self, CompileError> {
+        steps.validate()?;
+        self.preprocess = steps;
+        Ok(self)
+    }
+
+    /// The text rules are matched against: `text` after the box's
+    /// preprocessing steps.
+    pub fn preprocessed<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        if self.preprocess.is_empty() {
+            Cow::Borrowed(text)
+        } else {
+            Cow::Owned(NormalizedText::preprocess(text, &self.preprocess).text)
+        }
+    }
+
+    // For the span methods, which map offsets back onto the original text
+    pub(crate) fn preprocessed_with_offsets(&self, text: &str) -> Option<NormalizedText> {
+        (!self.preprocess.is_empty()).then(|| NormalizedText::preprocess(text, &self.preprocess))
+    }
+}
@@ -0,0 +1,187 @@
+// Checking structured records, where individual patterns can target a field.
+
+use crate::normalize::NormalizedText;
+use crate::{LabeledText, NearPattern, RegexRule, Rule, RuleBox, RuleOutcome};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// A record of named text fields, such as an email's `subject`, `body` and
+/// `sender`, labelled as a whole by `check_document`. In JSON it's an object
+/// of strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Document {
+    fields: BTreeMap<String, String>,
+}
+
+impl Document {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a field, replacing any earlier value.
+    pub fn with_field(mut self, name: &str, value: &str) -> Self {
+        self.fields.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+
+    /// Fields in name order.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Every field value joined by newlines in field-name order: the text
+    /// patterns without a `field` are matched against.
+    pub fn joined(&self) -> String {
+        self.fields
+            .values()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Document {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self {
+            fields: iter
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<HashMap<String, String>> for Document {
+    fn from(record: HashMap<String, String>) -> Self {
+        record.into_iter().collect()
+    }
+}
+
+impl From<BTreeMap<String, String>> for Document {
+    fn from(fields: BTreeMap<String, String>) -> Self {
+        Self { fields }
+    }
+}
+
+impl RegexRule {
+    /// The fields this pattern is limited to; empty means the joined text.
+    pub(crate) fn target_fields(&self) -> impl Iterator<Item = &String> {
+        self.field.iter().chain(&self.fields)
+    }
+}
+
+impl Rule {
+    /// `evaluate` over a record: patterns with a `field` (or `fields`) see
+    /// only those fields and match if any of them does (never if they're all
+    /// missing); the rest see `whole`.
+    pub(crate) fn evaluate_record(&self, record: &Document, whole: &str) -> RuleOutcome {
+        let matches = |pattern: &RegexRule, text: &str| {
+            if self.digit_normalize {
+                pattern.check(&NormalizedText::digits(text).text)
+            } else {
+                pattern.check(text)
+            }
+        };
+        let check = |pattern: &RegexRule| {
+            let mut fields = pattern.target_fields().peekable();
+            if fields.peek().is_none() {
+                return matches(pattern, whole);
+            }
+            fields
+                .filter_map(|field| record.field(field))
+                .any(|text| matches(pattern, text))
+        };
+
+        if !self.and_patterns.iter().all(check) {
+            return RuleOutcome::NoMatch;
+        }
+        // Both patterns have to see the same text for their distance to mean
+        // anything, so a near pattern uses the fields of its `first`
+        let near = |near: &NearPattern| {
+            let matches = |text: &str| {
+                if self.digit_normalize {
+                    near.check(&NormalizedText::digits(text).text)
+                } else {
+                    near.check(text)
+                }
+            };
+            let mut fields = near.first.target_fields().peekable();
+            if fields.peek().is_none() {
+                return matches(whole);
+            }
+            fields.filter_map(|field| record.field(field)).any(matches)
+        };
+        if !self.near_patterns.iter().all(near) {
+            return RuleOutcome::NoMatch;
+        }
+        if self.has_any_of() {
+            let keywords = if self.digit_normalize {
+                self.keywords_match(&NormalizedText::digits(whole).text)
+            } else {
+                self.keywords_match(whole)
+            };
+            if !keywords && !self.or_patterns.iter().any(check) {
+                return RuleOutcome::NoMatch;
+            }
+        }
+        if self.expr.as_ref().is_some_and(|expr| !expr.eval(&check)) {
+            return RuleOutcome::NoMatch;
+        }
+        // A windowed veto only looks at the positive matches in the same text
+        let vetoes = |pattern: &RegexRule| {
+            let vetoes = |text: &str| {
+                if self.digit_normalize {
+                    self.vetoes(pattern, &NormalizedText::digits(text).text)
+                } else {
+                    self.vetoes(pattern, text)
+                }
+            };
+            let mut fields = pattern.target_fields().peekable();
+            if fields.peek().is_none() {
+                return vetoes(whole);
+            }
+            fields.filter_map(|field| record.field(field)).any(vetoes)
+        };
+        if self.not_patterns.iter().any(vetoes) {
+            return RuleOutcome::Vetoed;
+        }
+        RuleOutcome::Matched
+    }
+}
+
+impl RuleBox {
+    /// Label a document of named fields. Patterns with a `field` (or
+    /// `fields`) are matched against those fields; patterns without one are
+    /// matched against `Document::joined`, which is also the returned text's
+    /// content. Each field is preprocessed on its own, as is the joined text.
+    pub fn check_document(&self, document: &Document) -> LabeledText {
+        let whole = document.joined();
+        let prepared: Document = document
+            .fields()
+            .map(|(field, value)| (field, self.preprocessed(value).into_owned()))
+            .collect();
+        let prepared_whole = self.preprocessed(&whole).into_owned();
+        let mut labeled = LabeledText::new(whole);
+        // The pre-filter only knows the joined text, so every rule runs
+        self.check_stages(
+            &prepared_whole,
+            None,
+            self.rules.iter().enumerate(),
+            &mut labeled,
+            |rule, _| rule.rule.evaluate_record(&prepared, &prepared_whole) == RuleOutcome::Matched,
+        );
+        #[cfg(feature = "metrics")]
+        crate::telemetry::record_labels(&labeled.labels);
+        labeled
+    }
+
+    /// `check_document` for a record held as a map.
+    pub fn check_record(&self, record: &HashMap<String, String>) -> LabeledText {
+        self.check_document(&record.iter().collect())
+    }
+}
@@ -0,0 +1,71 @@
+// A compiled-regex cache that can be shared by every rulebox in a process.
+
+use crate::RegexLimits;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Compiled regexes keyed by pattern and flags, so boxes compiled with
+/// `RuleBox::compile_with_cache` share one `Regex` per distinct pattern, and
+/// a reloaded box only compiles the patterns that changed.
+#[derive(Debug, Default)]
+pub struct RegexCache {
+    regexes: Mutex<HashMap<String, Arc<Regex>>>,
+}
+
+impl RegexCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of distinct compiled regexes held.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop the regexes no compiled rule uses any more, e.g. once a reloaded
+    /// box has replaced the old one, and return how many were dropped.
+    pub fn prune(&self) -> usize {
+        let mut regexes = self.lock();
+        let before = regexes.len();
+        regexes.retain(|_, re| Arc::strong_count(re) > 1);
+        before - regexes.len()
+    }
+
+    // Flag order doesn't change the regex, so it doesn't change the key;
+    // limits do, since a pattern can compile under one and not another
+    pub(crate) fn key(source: &str, flags: &[&str], limits: RegexLimits) -> String {
+        let mut flags = flags.to_vec();
+        flags.sort_unstable();
+        flags.dedup();
+        format!(
+            "{}\0{:?}\0{:?}\0{}",
+            flags.concat(),
+            limits.size_limit,
+            limits.dfa_size_limit,
+            source
+        )
+    }
+
+    pub(crate) fn get_or_build(
+        &self,
+        key: String,
+        build: impl FnOnce() -> Result<Regex, regex::Error>,
+    ) -> Result<Arc<Regex>, regex::Error> {
+        if let Some(re) = self.lock().get(&key) {
+            return Ok(Arc::clone(re));
+        }
+        // Build outside the lock; if another thread got there first, keep theirs
+        let re = Arc::new(build()?);
+        Ok(Arc::clone(self.lock().entry(key).or_insert(re)))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<Regex>>> {
+        // The map is always left consistent, so a poisoned lock is still usable
+        self.regexes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,194 @@
+// Human-readable documentation of a rulebox, generated from the rules themselves.
+
+use crate::{AssertionFailure, Expr, LabelRule, Proximity, RegexRule, RuleBox, SelfTestReport};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+impl RuleBox {
+    /// Render the rules as a Markdown document grouped by label, noting
+    /// inactive rules (including those outside their `active_from` /
+    /// `active_until` window) and rules failing `run_self_tests`, whether an
+    /// inline example or their own `proto_text`.
+    pub fn report_markdown(&self) -> String {
+        let self_tests = self.run_self_tests();
+        let mut by_label: BTreeMap<&str, Vec<&LabelRule>> = BTreeMap::new();
+        for rule in &self.rules {
+            by_label.entry(&rule.label).or_default().push(rule);
+        }
+
+        let mut out = String::from("# RuleBox report\n");
+        let active = self.rules.iter().filter(|r| r.is_active()).count();
+        let _ = writeln!(
+            out,
+            "\n{} rules ({} active) across {} labels.",
+            self.rules.len(),
+            active,
+            by_label.len()
+        );
+
+        for (label, rules) in by_label {
+            let _ = writeln!(out, "\n## {}", label);
+            for rule in rules {
+                write_rule(&mut out, rule, &self_tests);
+            }
+        }
+        out
+    }
+}
+
+fn write_rule(out: &mut String, rule: &LabelRule, self_tests: &SelfTestReport) {
+    let _ = writeln!(out, "\n### Rule {}\n", code(&rule.uuid));
+    let metadata = rule.metadata();
+    if let Some(description) = &metadata.description {
+        let _ = writeln!(out, "{}\n", description);
+    }
+    if rule.is_active() && rule.shadow {
+        let _ = writeln!(out, "- Status: **shadow**");
+    } else if rule.is_active() {
+        let _ = writeln!(out, "- Status: active");
+    } else {
+        let _ = writeln!(out, "- Status: **inactive**");
+    }
+    if rule.is_scheduled() {
+        let time = |at: Option<DateTime<Utc>>| {
+            at.map_or("-".to_string(), |at| {
+                at.to_rfc3339_opts(SecondsFormat::Secs, true)
+            })
+        };
+        let _ = writeln!(
+            out,
+            "- Active from {} until {}",
+            time(rule.active_from),
+            time(rule.active_until)
+        );
+    }
+    if rule.priority != 0 {
+        let _ = writeln!(out, "- Priority: {}", rule.priority);
+    }
+    if let Some(severity) = &metadata.severity {
+        let _ = writeln!(out, "- Severity: {}", severity);
+    }
+    if let Some(author) = &metadata.author {
+        let _ = writeln!(out, "- Author: {}", author);
+    }
+    if !rule.tags.is_empty() {
+        let tags: Vec<String> = rule.tags.iter().map(|t| code(t)).collect();
+        let _ = writeln!(out, "- Tags: {}", tags.join(", "));
+    }
+    if rule.rule.digit_normalize {
+        let _ = writeln!(out, "- Matches against digit-normalized text");
+    }
+    let of_rule = |failure: &&AssertionFailure| failure.uuid == rule.uuid;
+    if !rule.proto_text.is_empty() {
+        let status = if self_tests.proto_mismatches.iter().any(|f| of_rule(&f)) {
+            "**fails self-test**"
+        } else {
+            "passes self-test"
+        };
+        let _ = writeln!(out, "- Example: {} ({})", code(&rule.proto_text), status);
+    }
+    for failure in self_tests.failures.iter().filter(of_rule) {
+        let expected = if failure.should_match {
+            "should match"
+        } else {
+            "should not match"
+        };
+        let _ = writeln!(
+            out,
+            "- **Fails self-test**: {} {}",
+            expected,
+            code(&failure.text)
+        );
+    }
+    write_patterns(out, "All of", &rule.rule.and_patterns);
+    write_patterns(out, "Any of", &rule.rule.or_patterns);
+    for near in &rule.rule.near_patterns {
+        let unit = match near.unit {
+            Proximity::Chars => "characters",
+            Proximity::Words => "words",
+        };
+        let _ = writeln!(
+            out,
+            "- {} within {} {} of {}",
+            code(&near.first.pattern),
+            near.within,
+            unit,
+            code(&near.second.pattern)
+        );
+    }
+    if let Some(keywords) = &rule.rule.keyword_patterns {
+        let listed: Vec<String> = keywords.keywords.iter().map(|k| code(k)).collect();
+        let _ = writeln!(out, "- Any of the keywords: {}", listed.join(", "));
+    }
+    match rule.rule.not_within {
+        Some(within) => {
+            let heading = format!("None of, within {} characters of a match", within);
+            write_patterns(out, &heading, &rule.rule.not_patterns);
+        }
+        None => write_patterns(out, "None of", &rule.rule.not_patterns),
+    }
+    if let Some(expr) = &rule.rule.expr {
+        let _ = writeln!(out, "- Expression:");
+        write_expr(out, expr, 1);
+    }
+}
+
+fn write_expr(out: &mut String, expr: &Expr, depth: usize) {
+    let indent = "  ".repeat(depth);
+    match expr {
+        Expr::All(children) | Expr::Any(children) => {
+            let heading = if matches!(expr, Expr::All(_)) {
+                "All of"
+            } else {
+                "Any of"
+            };
+            let _ = writeln!(out, "{indent}- {heading}:");
+            for child in children {
+                write_expr(out, child, depth + 1);
+            }
+        }
+        Expr::Not(child) => {
+            let _ = writeln!(out, "{indent}- Not:");
+            write_expr(out, child, depth + 1);
+        }
+        Expr::Pattern(p) => write_pattern(out, &indent, p),
+    }
+}
+
+fn write_patterns(out: &mut String, heading: &str, patterns: &[RegexRule]) {
+    if patterns.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "- {}:", heading);
+    for p in patterns {
+        write_pattern(out, "  ", p);
+    }
+}
+
+fn write_pattern(out: &mut String, indent: &str, p: &RegexRule) {
+    if p.flags.is_empty() {
+        let _ = writeln!(out, "{indent}- {}", code(&p.pattern));
+    } else {
+        let _ = writeln!(
+            out,
+            "{indent}- {} (flags: {})",
+            code(&p.pattern),
+            p.flags.join(", ")
+        );
+    }
+}
+
+// Wrap text in a code span, widening the fence if the text contains backticks
+fn code(text: &str) -> String {
+    let mut fence = String::from("`");
+    while text.contains(fence.as_str()) {
+        fence.push('`');
+    }
+    let pad = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{fence}{pad}{text}{pad}{fence}")
+}
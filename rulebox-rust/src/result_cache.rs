@@ -0,0 +1,67 @@
+// Persisting check_many results between runs, keyed on the rulebox fingerprint.
+
+use crate::hash::stable_hash;
+use crate::RuleBox;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::io;
+
+#[derive(Default, Serialize, Deserialize)]
+struct ResultCache {
+    fingerprint: String,
+    // Text hash -> the texts with that hash, so a collision can't hand one
+    // text another's labels
+    entries: BTreeMap<String, Vec<CachedLabels>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedLabels {
+    text: String,
+    labels: BTreeSet<String>,
+}
+
+impl RuleBox {
+    /// `check_many`, reusing results stored in `cache_path` by a previous run
+    /// with the same fingerprint. The cache is discarded when the fingerprint
+    /// changes (or the file can't be read) and rewritten with any new texts.
+    pub fn check_many_cached(
+        &self,
+        texts: &[String],
+        cache_path: &str,
+    ) -> io::Result<Vec<HashSet<String>>> {
+        let fingerprint = self.fingerprint();
+        let mut cache = fs::read(cache_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<ResultCache>(&bytes).ok())
+            .filter(|cache| cache.fingerprint == fingerprint)
+            .unwrap_or_else(|| ResultCache {
+                fingerprint,
+                entries: BTreeMap::new(),
+            });
+
+        let mut changed = false;
+        let mut results = Vec::with_capacity(texts.len());
+        for text in texts {
+            let key = format!("{:016x}", stable_hash(text.as_bytes()));
+            let bucket = cache.entries.entry(key).or_default();
+            let cached = match bucket.iter().position(|entry| entry.text == *text) {
+                Some(i) => &bucket[i],
+                None => {
+                    changed = true;
+                    bucket.push(CachedLabels {
+                        text: text.clone(),
+                        labels: self.check(text).labels.into_iter().collect(),
+                    });
+                    &bucket[bucket.len() - 1]
+                }
+            };
+            results.push(cached.labels.iter().cloned().collect());
+        }
+
+        if changed {
+            fs::write(cache_path, serde_json::to_vec(&cache)?)?;
+        }
+        Ok(results)
+    }
+}
@@ -0,0 +1,72 @@
+// Time-boxed rules: `active_from` / `active_until` switch a rule on and off
+// by the clock, so a campaign's rules retire themselves rather than waiting
+// for someone to remember to set `active: false`.
+
+use crate::{LabelRule, RuleBox};
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::sync::Arc;
+
+/// Where rules get the current time from: the system clock by default, or
+/// any function, e.g. a fixed time in tests.
+#[derive(Clone, Default)]
+pub struct Clock(Option<Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>>);
+
+impl Clock {
+    pub fn new(now: impl Fn() -> DateTime<Utc> + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(now)))
+    }
+
+    /// A clock stopped at `at`.
+    pub fn fixed(at: DateTime<Utc>) -> Self {
+        Self::new(move || at)
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        match &self.0 {
+            Some(now) => now(),
+            None => Utc::now(),
+        }
+    }
+}
+
+impl fmt::Debug for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("Clock(custom)"),
+            None => f.write_str("Clock(system)"),
+        }
+    }
+}
+
+impl LabelRule {
+    /// Whether the rule is on: `active`, and inside its `active_from` /
+    /// `active_until` window by its clock. Only rules with a window read the
+    /// clock.
+    pub fn is_active(&self) -> bool {
+        self.active && (!self.is_scheduled() || self.is_active_at(self.clock.now()))
+    }
+
+    /// Whether `at` falls in the rule's window, `active_from` inclusive and
+    /// `active_until` exclusive. Ignores `active`.
+    pub fn is_active_at(&self, at: DateTime<Utc>) -> bool {
+        self.active_from.is_none_or(|from| from <= at)
+            && self.active_until.is_none_or(|until| at < until)
+    }
+
+    pub(crate) fn is_scheduled(&self) -> bool {
+        self.active_from.is_some() || self.active_until.is_some()
+    }
+}
+
+impl RuleBox {
+    /// Check `active_from` / `active_until` against `clock` instead of the
+    /// system clock, for this box's rules and any added later.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        for rule in &mut self.rules {
+            rule.clock = clock.clone();
+        }
+        self.clock = clock;
+        self
+    }
+}
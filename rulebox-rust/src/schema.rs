@@ -0,0 +1,125 @@
+// The rule file schema: a JSON Schema for editors and CI, and strict loading
+// that rejects fields serde would otherwise ignore. A misspelt `or_pattern`
+// is silently dropped by a normal load, leaving a rule that matches anything.
+
+use crate::format::Format;
+use crate::{RuleBox, RuleBoxError, SchemaProblem};
+use serde::Deserializer;
+use std::fmt::Display;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+impl Format {
+    /// Parse a box without compiling it, failing on any field the schema
+    /// doesn't know.
+    pub(crate) fn parse_strict(self, text: &str) -> Result<RuleBox, RuleBoxError> {
+        match self {
+            Format::Json => strict(&mut serde_json::Deserializer::from_str(text)),
+            #[cfg(feature = "yaml")]
+            Format::Yaml => strict(serde_yaml::Deserializer::from_str(text)),
+            #[cfg(feature = "toml")]
+            Format::Toml => strict(toml::Deserializer::new(text)),
+            // Reports the missing feature
+            #[allow(unreachable_patterns)]
+            format => format.parse(text),
+        }
+    }
+}
+
+// Collects every unknown field, plus the error that stopped parsing if there
+// was one; serde can't carry on past a malformed value
+fn strict<'de, D>(deserializer: D) -> Result<RuleBox, RuleBoxError>
+where
+    D: Deserializer<'de>,
+    D::Error: Display,
+{
+    let mut problems = Vec::new();
+    let mut unknown = |path: serde_ignored::Path| {
+        problems.push(SchemaProblem {
+            path: ignored_path(&path),
+            message: "unknown field".to_string(),
+        })
+    };
+    let result = serde_path_to_error::deserialize(serde_ignored::Deserializer::new(
+        deserializer,
+        &mut unknown,
+    ));
+    if let Err(e) = &result {
+        problems.push(SchemaProblem {
+            path: error_path(e.path()),
+            message: e.inner().to_string(),
+        });
+    }
+    match result {
+        Ok(rulebox) if problems.is_empty() => Ok(rulebox),
+        _ => Err(RuleBoxError::Schema(problems)),
+    }
+}
+
+fn ignored_path(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+    match path {
+        Path::Root => "$".to_string(),
+        Path::Seq { parent, index } => format!("{}[{}]", ignored_path(parent), index),
+        Path::Map { parent, key } => format!("{}.{}", ignored_path(parent), key),
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => ignored_path(parent),
+    }
+}
+
+fn error_path(path: &serde_path_to_error::Path) -> String {
+    use serde_path_to_error::Segment;
+    let mut out = "$".to_string();
+    for segment in path.iter() {
+        match segment {
+            Segment::Seq { index } => out.push_str(&format!("[{}]", index)),
+            Segment::Map { key } => out.push_str(&format!(".{}", key)),
+            Segment::Enum { variant } => out.push_str(&format!(".{}", variant)),
+            Segment::Unknown => out.push_str(".?"),
+        }
+    }
+    out
+}
+
+/// The two layouts `RuleBox` accepts, for the schema.
+#[cfg(feature = "schema")]
+#[derive(schemars::JsonSchema)]
+#[serde(untagged)]
+#[schemars(rename = "RuleBox")]
+#[allow(dead_code)]
+enum RuleBoxSchema {
+    Rules(Vec<crate::LabelRule>),
+    Document(crate::document::RuleBoxDocument),
+}
+
+impl RuleBox {
+    /// A JSON Schema (draft 7) describing rule files, for editor completion
+    /// and validating rules in CI. It describes the fields but, like a normal
+    /// load, allows unknown ones; use `from_json_strict` to reject those.
+    #[cfg(feature = "schema")]
+    pub fn schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(RuleBoxSchema))
+            .expect("a schema serializes to JSON")
+    }
+
+    /// Like `from_json`, but a field the schema doesn't know (such as a
+    /// misspelt `or_pattern`) is an error rather than being ignored. Every
+    /// unknown field is reported with its JSON path, e.g.
+    /// `$.rules[0].rule.or_pattern`.
+    pub fn from_json_strict(json: &str) -> Result<Self, RuleBoxError> {
+        let mut rulebox = Format::Json.parse_strict(json)?;
+        rulebox.compile_and_test()?;
+        Ok(rulebox)
+    }
+
+    /// `from_path` with the checks of `from_json_strict`.
+    #[cfg(feature = "fs")]
+    pub fn from_path_strict(path: &str) -> Result<Self, RuleBoxError> {
+        let path = Path::new(path);
+        let text = std::fs::read_to_string(path)?;
+        let mut rulebox = Format::from_path(path).parse_strict(&text)?;
+        rulebox.compile_and_test()?;
+        Ok(rulebox)
+    }
+}
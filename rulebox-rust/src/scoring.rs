@@ -0,0 +1,56 @@
+// Weighted scoring: every matching rule adds its weight to its label's score.
+
+use crate::RuleBox;
+use std::collections::HashMap;
+
+impl RuleBox {
+    /// Sum the weights of the matching active rules per label. Labels with no
+    /// matching rule are absent. Totals are clamped at zero unless
+    /// `allow_negative_scores` is set.
+    pub fn score(&self, text: &str) -> HashMap<String, f64> {
+        let text = &*self.preprocessed(text);
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        for rule in self.rules.iter().filter(|r| r.assigns_labels()) {
+            if rule.rule.check(text) {
+                *scores.entry(rule.label.clone()).or_insert(0.0) += rule.weight;
+            }
+        }
+        if !self.allow_negative_scores {
+            for score in scores.values_mut() {
+                *score = score.max(0.0);
+            }
+        }
+        scores
+    }
+
+    pub fn with_negative_scores(mut self, allow: bool) -> Self {
+        self.allow_negative_scores = allow;
+        self
+    }
+
+    /// Assign `label` by score: `check` and `assign_labels_vector` add it only
+    /// when the weights of its matching rules sum to more than `threshold`.
+    pub fn with_score_threshold(mut self, label: &str, threshold: f64) -> Self {
+        self.score_thresholds.insert(label.to_string(), threshold);
+        self
+    }
+
+    // The thresholded labels whose summed weights (clamped like `score`)
+    // exceed their threshold, in `score_thresholds` order
+    pub(crate) fn labels_over_threshold(
+        &self,
+        scores: HashMap<String, f64>,
+    ) -> impl Iterator<Item = String> + '_ {
+        self.score_thresholds
+            .iter()
+            .filter_map(move |(label, threshold)| {
+                let score = *scores.get(label)?;
+                let score = if self.allow_negative_scores {
+                    score
+                } else {
+                    score.max(0.0)
+                };
+                (score > *threshold).then(|| label.clone())
+            })
+    }
+}
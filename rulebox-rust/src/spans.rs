@@ -0,0 +1,426 @@
+// Locating where in a text each rule matched.
+
+use crate::normalize::NormalizedText;
+use crate::{RegexRule, Rule, RuleBox, RuleOutcome};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+impl RegexRule {
+    /// Byte spans of every non-overlapping match in `text`.
+    pub(crate) fn find_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        let Some(re) = &self.compiled else {
+            return Vec::new();
+        };
+        let spans: Vec<(usize, usize)> = re.find_iter(text).map(|m| (m.start(), m.end())).collect();
+        if spans.is_empty() {
+            return self.find_fuzzy(text).into_iter().collect();
+        }
+        spans
+    }
+
+    /// `find_spans` plus the spans of the `names` groups that took part in
+    /// each match. Fuzzy matches have no groups.
+    pub(crate) fn find_captures(&self, text: &str, names: &[String]) -> Vec<Hit<'_>> {
+        let spans = |captures: &regex::Captures| {
+            names
+                .iter()
+                .filter_map(|name| {
+                    let group = captures.name(name)?;
+                    Some((name.clone(), group.start(), group.end()))
+                })
+                .collect()
+        };
+        let Some(re) = &self.compiled else {
+            return Vec::new();
+        };
+        if names.is_empty() {
+            return self
+                .find_spans(text)
+                .into_iter()
+                .map(|(start, end)| Hit::new(&self.pattern, start, end))
+                .collect();
+        }
+        let hits: Vec<Hit> = re
+            .captures_iter(text)
+            .filter_map(|captures| {
+                let whole = captures.get(0)?;
+                Some(Hit {
+                    pattern: &self.pattern,
+                    start: whole.start(),
+                    end: whole.end(),
+                    captures: spans(&captures),
+                })
+            })
+            .collect();
+        if hits.is_empty() {
+            return self
+                .find_fuzzy(text)
+                .into_iter()
+                .map(|(start, end)| Hit::new(&self.pattern, start, end))
+                .collect();
+        }
+        hits
+    }
+
+    #[cfg(feature = "fuzzy")]
+    fn find_fuzzy(&self, text: &str) -> Option<(usize, usize)> {
+        self.fuzzy.as_ref().and_then(|f| f.find(text))
+    }
+
+    #[cfg(not(feature = "fuzzy"))]
+    fn find_fuzzy(&self, _text: &str) -> Option<(usize, usize)> {
+        None
+    }
+}
+
+/// One positive pattern or keyword match, with the spans of any requested
+/// capture groups as `(name, start, end)`.
+pub(crate) struct Hit<'r> {
+    /// The pattern's source, or the keyword.
+    pub(crate) pattern: &'r str,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) captures: Vec<(String, usize, usize)>,
+}
+
+impl<'r> Hit<'r> {
+    fn new(pattern: &'r str, start: usize, end: usize) -> Self {
+        Self {
+            pattern,
+            start,
+            end,
+            captures: Vec::new(),
+        }
+    }
+
+    // Move every span from the normalized text onto the original
+    fn map_to_original(&mut self, normalized: &NormalizedText) {
+        (self.start, self.end) = normalized.original_span(self.start, self.end);
+        for (_, start, end) in &mut self.captures {
+            (*start, *end) = normalized.original_span(*start, *end);
+        }
+    }
+
+    // Capture values sliced from the text the spans refer to
+    fn capture_values(&self, text: &str) -> BTreeMap<String, String> {
+        self.captures
+            .iter()
+            .filter_map(|(name, start, end)| {
+                Some((name.clone(), text.get(*start..*end)?.to_string()))
+            })
+            .collect()
+    }
+}
+
+impl Rule {
+    /// Each positive pattern's and keyword's matches, with the spans of the
+    /// `captures` groups, or `None` if the rule as a whole doesn't match. Spans always
+    /// refer to the original text, even for `digit_normalize` rules.
+    pub(crate) fn positive_hits(&self, text: &str, captures: &[String]) -> Option<Vec<Hit<'_>>> {
+        if self.evaluate(text) != RuleOutcome::Matched {
+            return None;
+        }
+        let normalized = self.digit_normalize.then(|| NormalizedText::digits(text));
+        let haystack = normalized.as_ref().map_or(text, |n| n.text.as_str());
+
+        let mut hits = Vec::new();
+        for pattern in self.positive_patterns() {
+            hits.extend(pattern.find_captures(haystack, captures));
+        }
+        if let Some(keywords) = &self.keyword_patterns {
+            hits.extend(
+                keywords
+                    .find_keywords(haystack)
+                    .into_iter()
+                    .map(|(keyword, start, end)| Hit::new(keyword, start, end)),
+            );
+        }
+        if let Some(n) = &normalized {
+            for hit in &mut hits {
+                hit.map_to_original(n);
+            }
+        }
+        Some(hits)
+    }
+}
+
+/// A contiguous region of a text and the labels that apply to all of it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Segment {
+    pub start: usize,
+    pub end: usize,
+    pub labels: BTreeSet<String>,
+}
+
+/// One positive pattern or keyword match, tagged with the rule it came
+/// from. `pattern` holds the keyword for keyword matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Match {
+    pub uuid: String,
+    pub label: String,
+    pub pattern: String,
+    pub start: usize,
+    pub end: usize,
+    /// Values of the rule's `captures` groups that took part in the match.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub captures: BTreeMap<String, String>,
+}
+
+/// One pattern match behind a label in `check_detailed`, with byte and
+/// character offsets into the checked text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpanDetail {
+    pub uuid: String,
+    pub pattern: String,
+    pub start: usize,
+    pub end: usize,
+    pub char_start: usize,
+    pub char_end: usize,
+    pub text: String,
+    /// Values of the rule's `captures` groups that took part in the match.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub captures: BTreeMap<String, String>,
+}
+
+/// Everything `check_detailed` found for one label.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelDetail {
+    /// Matches from every rule that assigned the label, in document order.
+    /// A label that's only there because another label implies it has the
+    /// matches behind that label; labels from count rules have none.
+    pub spans: Vec<SpanDetail>,
+    /// Every value captured for each of the rules' `captures` groups, in
+    /// document order.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub captures: BTreeMap<String, Vec<String>>,
+}
+
+impl Match {
+    /// The matched slice of `text`, or `None` if this match came from a
+    /// different text and its span doesn't fit.
+    pub fn text<'t>(&self, text: &'t str) -> Option<&'t str> {
+        text.get(self.start..self.end)
+    }
+}
+
+impl Segment {
+    /// This segment's slice of `text`, or `None` if the segment came from a
+    /// different text and its span doesn't fit.
+    pub fn text<'t>(&self, text: &'t str) -> Option<&'t str> {
+        text.get(self.start..self.end)
+    }
+}
+
+impl RuleBox {
+    // `positive_hits` against the preprocessed text, with spans mapped back
+    // onto the original
+    fn hits<'r>(
+        rule: &'r Rule,
+        captures: &[String],
+        text: &str,
+        prepared: Option<&NormalizedText>,
+    ) -> Option<Vec<Hit<'r>>> {
+        let Some(prepared) = prepared else {
+            return rule.positive_hits(text, captures);
+        };
+        let mut hits = rule.positive_hits(&prepared.text, captures)?;
+        for hit in &mut hits {
+            hit.map_to_original(prepared);
+        }
+        Some(hits)
+    }
+
+    /// Every match of every positive pattern and keyword in each rule that
+    /// assigned its label, sorted by `(start, end)`. Ties keep rule order.
+    /// Rules that matched but lost their group, fell under a score threshold,
+    /// had their label suppressed or didn't run in their stage are left out.
+    /// Spans refer to the original text even when the box has `preprocess`
+    /// steps.
+    pub fn all_matches(&self, text: &str) -> Vec<Match> {
+        let (_, assigning) = self.assigning_rules(text);
+        self.matches_of(text, &assigning)
+    }
+
+    // The matches of the rules at `indices`, sorted like `all_matches`
+    fn matches_of(&self, text: &str, indices: &[usize]) -> Vec<Match> {
+        let prepared = self.preprocessed_with_offsets(text);
+        let mut matches = Vec::new();
+        for rule in indices.iter().map(|&i| &self.rules[i]) {
+            let Some(hits) = Self::hits(&rule.rule, &rule.captures, text, prepared.as_ref()) else {
+                continue;
+            };
+            matches.extend(hits.into_iter().map(|hit| Match {
+                uuid: rule.uuid.clone(),
+                label: rule.label.clone(),
+                pattern: hit.pattern.to_string(),
+                start: hit.start,
+                end: hit.end,
+                captures: hit.capture_values(text),
+            }));
+        }
+        matches.sort_by_key(|m| (m.start, m.end));
+        matches
+    }
+
+    /// The same labels as `check`, each with the pattern matches of the rules
+    /// `check_hits` credits with it, for highlighting matched text.
+    pub fn check_detailed(&self, text: &str) -> BTreeMap<String, LabelDetail> {
+        let (labels, assigning) = self.assigning_rules(text);
+        let matches = self.matches_of(text, &assigning);
+        let mut details: BTreeMap<String, LabelDetail> = BTreeMap::new();
+        for hit in self.hits_for(&labels, &assigning) {
+            let detail = details.entry(hit.label).or_default();
+            for m in matches.iter().filter(|m| hit.rules.contains(&m.uuid)) {
+                let matched = m.text(text).unwrap_or_default().to_string();
+                let char_start = text
+                    .get(..m.start)
+                    .map_or(0, |prefix| prefix.chars().count());
+                for (name, value) in &m.captures {
+                    detail
+                        .captures
+                        .entry(name.clone())
+                        .or_default()
+                        .push(value.clone());
+                }
+                detail.spans.push(SpanDetail {
+                    char_start,
+                    char_end: char_start + matched.chars().count(),
+                    text: matched,
+                    uuid: m.uuid.clone(),
+                    pattern: m.pattern.clone(),
+                    start: m.start,
+                    end: m.end,
+                    captures: m.captures.clone(),
+                });
+            }
+        }
+        details
+    }
+
+    /// The label of the active rule whose match starts earliest in `text`,
+    /// with that byte offset. Ties go to the higher-priority rule, then the
+    /// earlier rule.
+    pub fn first_label_by_position(&self, text: &str) -> Option<(String, usize)> {
+        let prepared = self.preprocessed_with_offsets(text);
+        let mut best: Option<(usize, i32, &str)> = None;
+        for rule in self.rules.iter().filter(|r| r.assigns_labels()) {
+            let Some(start) = Self::hits(&rule.rule, &[], text, prepared.as_ref())
+                .and_then(|hits| hits.iter().map(|hit| hit.start).min())
+            else {
+                continue;
+            };
+            if best.is_none_or(|(s, p, _)| start < s || (start == s && rule.priority > p)) {
+                best = Some((start, rule.priority, &rule.label));
+            }
+        }
+        best.map(|(start, _, label)| (label.to_string(), start))
+    }
+
+    /// Split a text into segments at every match boundary, so each segment
+    /// carries the set of labels whose matches cover it, counting only the
+    /// rules `all_matches` does. Segments cover the whole text; stretches with
+    /// no matches have an empty label set.
+    pub fn segment(&self, text: &str) -> Vec<Segment> {
+        self.segment_with(text, OverlapPolicy::KeepAll)
+    }
+
+    /// Like `segment`, but resolving overlapping matches with `policy`.
+    pub fn segment_with(&self, text: &str, policy: OverlapPolicy) -> Vec<Segment> {
+        let (_, assigning) = self.assigning_rules(text);
+        let prepared = self.preprocessed_with_offsets(text);
+        let mut spans: Vec<RuleSpan> = Vec::new();
+        for index in assigning {
+            let rule = &self.rules[index];
+            if let Some(hits) = Self::hits(&rule.rule, &[], text, prepared.as_ref()) {
+                spans.extend(
+                    hits.into_iter()
+                        .filter(|hit| hit.start < hit.end)
+                        .map(|hit| RuleSpan {
+                            start: hit.start,
+                            end: hit.end,
+                            label: &rule.label,
+                            priority: rule.priority,
+                            index,
+                        }),
+                );
+            }
+        }
+        let spans: Vec<(usize, usize, &str)> = resolve_overlaps(spans, policy)
+            .into_iter()
+            .map(|s| (s.start, s.end, s.label))
+            .collect();
+        segments_from_spans(text, &spans)
+    }
+}
+
+/// How `segment_with` handles matches that overlap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Keep every match, so overlapping regions carry several labels.
+    #[default]
+    KeepAll,
+    /// Keep the match from the highest-priority rule and drop any match that
+    /// overlaps one already kept.
+    HighestPriority,
+    /// Keep the longest match and drop any match that overlaps one already
+    /// kept.
+    Longest,
+}
+
+struct RuleSpan<'a> {
+    start: usize,
+    end: usize,
+    label: &'a str,
+    priority: i32,
+    // Position of the rule in the box, for deterministic tie-breaking
+    index: usize,
+}
+
+// Greedily pick non-overlapping spans in policy order; ties go to the earlier
+// rule, then the earlier span
+fn resolve_overlaps(mut spans: Vec<RuleSpan>, policy: OverlapPolicy) -> Vec<RuleSpan> {
+    match policy {
+        OverlapPolicy::KeepAll => return spans,
+        OverlapPolicy::HighestPriority => {
+            spans.sort_by_key(|s| (std::cmp::Reverse(s.priority), s.index, s.start))
+        }
+        OverlapPolicy::Longest => {
+            spans.sort_by_key(|s| (std::cmp::Reverse(s.end - s.start), s.index, s.start))
+        }
+    }
+    let mut kept: Vec<RuleSpan> = Vec::new();
+    for span in spans {
+        if kept
+            .iter()
+            .all(|k| span.end <= k.start || span.start >= k.end)
+        {
+            kept.push(span);
+        }
+    }
+    kept
+}
+
+pub(crate) fn segments_from_spans(text: &str, spans: &[(usize, usize, &str)]) -> Vec<Segment> {
+    let mut boundaries: Vec<usize> = vec![0, text.len()];
+    for (start, end, _) in spans {
+        boundaries.push(*start);
+        boundaries.push(*end);
+    }
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut segments: Vec<Segment> = Vec::new();
+    for pair in boundaries.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        let labels: BTreeSet<String> = spans
+            .iter()
+            .filter(|(s, e, _)| *s <= start && *e >= end)
+            .map(|(_, _, label)| label.to_string())
+            .collect();
+        match segments.last_mut() {
+            Some(last) if last.labels == labels => last.end = end,
+            _ => segments.push(Segment { start, end, labels }),
+        }
+    }
+    segments
+}
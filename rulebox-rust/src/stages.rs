@@ -0,0 +1,48 @@
+// Multi-pass labelling: rules run in ascending `stage` order, and a rule in a
+// later stage can require (or exclude) labels assigned by earlier stages, e.g.
+// `privacy_risk` = `contains_email` and not `newsletter`, without repeating
+// either rule's patterns. Each stage is a full pass over its rules, groups,
+// thresholds, `implies`, count rules and suppressors included.
+
+use crate::{LabelRule, RuleBox};
+
+impl LabelRule {
+    /// Whether the rule's `requires_labels` and `excludes_labels` hold,
+    /// given which labels earlier stages assigned.
+    pub(crate) fn label_conditions_hold(&self, assigned: impl Fn(&str) -> bool) -> bool {
+        self.requires_labels.iter().all(|label| assigned(label))
+            && !self.excludes_labels.iter().any(|label| assigned(label))
+    }
+}
+
+impl RuleBox {
+    /// Whether any rule has a `stage` or label conditions, so labelling
+    /// takes more than one pass.
+    pub fn is_staged(&self) -> bool {
+        self.rules.iter().any(|rule| {
+            rule.stage != 0 || !rule.requires_labels.is_empty() || !rule.excludes_labels.is_empty()
+        })
+    }
+
+    // The distinct stages, in the order they run
+    pub(crate) fn stages(&self) -> Vec<u32> {
+        let mut stages: Vec<u32> = self.rules.iter().map(|rule| rule.stage).collect();
+        stages.sort_unstable();
+        stages.dedup();
+        stages
+    }
+
+    // The rules of `stage` whose label conditions hold
+    pub(crate) fn stage_rules<'r>(
+        &self,
+        rules: &[(usize, &'r LabelRule)],
+        stage: u32,
+        assigned: impl Fn(&str) -> bool,
+    ) -> Vec<(usize, &'r LabelRule)> {
+        rules
+            .iter()
+            .filter(|(_, rule)| rule.stage == stage && rule.label_conditions_hold(&assigned))
+            .copied()
+            .collect()
+    }
+}
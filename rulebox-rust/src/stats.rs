@@ -0,0 +1,144 @@
+// Opt-in per-rule counters for finding rules that never fire. Kept behind a
+// mutex keyed by uuid so `check` can stay `&self` and rules can be added or
+// removed without re-indexing.
+
+use crate::{LabelRule, NormalizedText, RuleBox, RuleOutcome};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often a rule was evaluated, fired and was vetoed, and the time spent
+/// evaluating it, since stats were enabled or last reset.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleStats {
+    pub uuid: String,
+    pub label: String,
+    pub evaluations: u64,
+    /// Evaluations where the rule matched.
+    pub matches: u64,
+    /// Evaluations where the positive patterns matched but a `not_pattern`
+    /// blocked the rule.
+    pub vetoes: u64,
+    /// Vetoes per `not_pattern`, in rule order. A veto counts against every
+    /// `not_pattern` that matched.
+    pub not_pattern_vetoes: Vec<(String, u64)>,
+    pub total_time: Duration,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct StatsCollector {
+    rules: Mutex<HashMap<String, RuleStats>>,
+}
+
+impl StatsCollector {
+    // Evaluate a rule against the text, recording the outcome and timing
+    pub(crate) fn evaluate(&self, rule: &LabelRule, text: &str) -> RuleOutcome {
+        let start = Instant::now();
+        let outcome = rule.rule.evaluate(text);
+        let elapsed = start.elapsed();
+
+        // Which not_patterns vetoed is only worked out when one did
+        let vetoed_by: Vec<bool> = if outcome == RuleOutcome::Vetoed {
+            let normalized;
+            let text = if rule.rule.digit_normalize {
+                normalized = NormalizedText::digits(text);
+                normalized.text.as_str()
+            } else {
+                text
+            };
+            rule.rule
+                .not_patterns
+                .iter()
+                .map(|p| rule.rule.vetoes(p, text))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut rules = self.rules.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = rules
+            .entry(rule.uuid.clone())
+            .or_insert_with(|| RuleStats::new(rule));
+        stats.evaluations += 1;
+        stats.total_time += elapsed;
+        match outcome {
+            RuleOutcome::Matched => stats.matches += 1,
+            RuleOutcome::Vetoed => {
+                stats.vetoes += 1;
+                for ((_, count), vetoed) in stats.not_pattern_vetoes.iter_mut().zip(vetoed_by) {
+                    if vetoed {
+                        *count += 1;
+                    }
+                }
+            }
+            RuleOutcome::NoMatch => {}
+        }
+        outcome
+    }
+}
+
+impl RuleStats {
+    fn new(rule: &LabelRule) -> Self {
+        Self {
+            uuid: rule.uuid.clone(),
+            label: rule.label.clone(),
+            not_pattern_vetoes: rule
+                .rule
+                .not_patterns
+                .iter()
+                .map(|p| (p.pattern.clone(), 0))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl RuleBox {
+    /// Start counting, per rule, evaluations, matches, `not_pattern` vetoes
+    /// and evaluation time in `check` (and the methods built on it) and
+    /// `assign_labels_vector`. Off by default since every evaluation then
+    /// takes a lock; `Instant` also isn't available on wasm32.
+    pub fn with_stats(mut self) -> Self {
+        self.stats = Some(StatsCollector::default());
+        self
+    }
+
+    /// Counters for every rule in box order, or `None` if stats aren't
+    /// enabled. Rules that were never evaluated (including ones skipped by the
+    /// pre-filter) report zero evaluations.
+    pub fn stats(&self) -> Option<Vec<RuleStats>> {
+        let collector = self.stats.as_ref()?;
+        let recorded = collector.rules.lock().unwrap_or_else(|e| e.into_inner());
+        Some(
+            self.rules
+                .iter()
+                .map(|rule| {
+                    recorded
+                        .get(&rule.uuid)
+                        .cloned()
+                        .unwrap_or_else(|| RuleStats::new(rule))
+                })
+                .collect(),
+        )
+    }
+
+    /// Zero every counter, keeping stats enabled.
+    pub fn reset_stats(&self) {
+        if let Some(collector) = &self.stats {
+            collector
+                .rules
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clear();
+        }
+    }
+
+    // The single place the counted paths evaluate a rule
+    pub(crate) fn rule_matches(&self, rule: &LabelRule, text: &str) -> bool {
+        match &self.stats {
+            Some(collector) => collector.evaluate(rule, text) == RuleOutcome::Matched,
+            None => rule.rule.check(text),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// Labelling newline-delimited JSON a line at a time, so corpora far bigger
+// than memory can be processed without collecting the texts first.
+
+use crate::{RuleBox, RuleBoxError};
+use serde::de::Error as _;
+use serde_json::{Map, Value};
+use std::io::{BufRead, Write};
+
+impl RuleBox {
+    /// Label every line of JSONL from `input`, writing one JSON object per
+    /// line to `output` as it goes. A line is either a JSON string, written
+    /// out as `{"text": ..., "labels": [...]}`, or an object with a string
+    /// `text` field, written back with `labels` added. Labels are sorted and
+    /// blank lines are skipped. Returns the number of texts labelled.
+    pub fn label_stream(
+        &self,
+        input: impl BufRead,
+        mut output: impl Write,
+    ) -> Result<usize, RuleBoxError> {
+        let mut count = 0;
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = |source| RuleBoxError::InvalidLine {
+                line: index + 1,
+                source,
+            };
+            let mut record = match serde_json::from_str(&line).map_err(invalid)? {
+                Value::String(text) => {
+                    let mut record = Map::new();
+                    record.insert("text".to_string(), Value::String(text));
+                    record
+                }
+                Value::Object(record) => record,
+                _ => {
+                    let e = serde_json::Error::custom("expected a string or an object");
+                    return Err(invalid(e));
+                }
+            };
+            let Some(Value::String(text)) = record.get("text") else {
+                let e = serde_json::Error::custom("missing string field `text`");
+                return Err(invalid(e));
+            };
+            let mut labels: Vec<String> = self.check(text).labels().iter().cloned().collect();
+            labels.sort();
+            record.insert("labels".to_string(), labels.into());
+            serde_json::to_writer(&mut output, &record)?;
+            output.write_all(b"\n")?;
+            count += 1;
+        }
+        output.flush()?;
+        Ok(count)
+    }
+}
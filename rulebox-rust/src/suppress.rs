@@ -0,0 +1,51 @@
+// Suppressor rules: a rule with `"kind": "suppress"` removes its label from a
+// text's result when it matches, whichever rules assigned it, e.g. a
+// newsletter footer pattern suppressing `personal_data`. Suppression is the
+// last step of labelling, after groups, thresholds, implied labels and count
+// rules, so a suppressed label's parents are kept.
+
+use crate::LabelRule;
+use serde::{Deserialize, Serialize};
+
+/// What a rule does when it matches.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleKind {
+    /// Assign the rule's label.
+    #[default]
+    Assign,
+    /// Remove the rule's label if anything else assigned it.
+    Suppress,
+}
+
+impl LabelRule {
+    /// Whether the rule's matches remove its label: it's an active
+    /// suppressor and not in shadow mode.
+    pub fn suppresses(&self) -> bool {
+        self.is_active() && !self.shadow && self.kind == RuleKind::Suppress
+    }
+}
+
+// The labels (among those `present`) that a matching suppressor in `rules`
+// removes. Suppressors whose label isn't present aren't evaluated. `rules`
+// must only hold rules that are on, which for a what-if can include inactive
+// ones, so only their kind is checked here.
+pub(crate) fn suppressed_labels<'r>(
+    rules: impl IntoIterator<Item = &'r LabelRule>,
+    text: &str,
+    present: impl Fn(&str) -> bool,
+    mut matches: impl FnMut(&LabelRule, &str) -> bool,
+) -> Vec<&'r str> {
+    let mut suppressed: Vec<&str> = Vec::new();
+    for rule in rules {
+        if rule.kind == RuleKind::Suppress
+            && present(&rule.label)
+            && !suppressed.contains(&rule.label.as_str())
+            && matches(rule, text)
+        {
+            suppressed.push(&rule.label);
+        }
+    }
+    suppressed
+}
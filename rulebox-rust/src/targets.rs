@@ -0,0 +1,84 @@
+// Asking about particular labels: only the rules that can produce one of them
+// are evaluated, stopping at the first that does, e.g. for a spam gate that
+// only cares about `spam` in a box of hundreds of rules.
+
+use crate::{LabelRule, RuleBox};
+
+impl RuleBox {
+    /// Whether `text` would be given any of `labels`, as `assign_labels`
+    /// decides. Only rules whose label is (or implies) one of `labels` are
+    /// evaluated, stopping at the first one that assigns it. Labels reached
+    /// through score thresholds, groups or count rules depend on other
+    /// rules, so asking for one of those falls back to labelling the text.
+    pub fn check_for(&self, text: &str, labels: &[&str]) -> bool {
+        let yields = |rule: &LabelRule| self.target_labels(&rule.label, labels);
+        let producers: Vec<(usize, &LabelRule)> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule.assigns_labels() && !yields(rule).is_empty())
+            .collect();
+        if self.needs_full_pass(&producers, labels) {
+            return self
+                .assign_labels(text)
+                .iter()
+                .any(|label| labels.contains(&label.as_str()));
+        }
+
+        let text = &*self.preprocessed(text);
+        let candidates = self.prefilter.candidates(text);
+        // A suppressor can still take the label away, so it's checked once
+        // the label is found
+        let mut suppressed: Vec<&str> = Vec::new();
+        for (i, rule) in producers {
+            if candidates.as_ref().is_some_and(|c| !c.contains(i)) || !self.rule_matches(rule, text)
+            {
+                continue;
+            }
+            for label in yields(rule) {
+                if suppressed.contains(&label) {
+                    continue;
+                }
+                if !self.is_suppressed(label, text) {
+                    return true;
+                }
+                suppressed.push(label);
+            }
+        }
+        false
+    }
+
+    /// `check_for` with a single label.
+    pub fn has_label(&self, text: &str, label: &str) -> bool {
+        self.check_for(text, &[label])
+    }
+
+    // Which of `targets` a rule assigning `label` produces: the label itself
+    // and the labels it implies
+    fn target_labels<'t>(&self, label: &str, targets: &[&'t str]) -> Vec<&'t str> {
+        let implied = self.implied_labels(label);
+        targets
+            .iter()
+            .copied()
+            .filter(|target| *target == label || implied.iter().any(|l| l == target))
+            .collect()
+    }
+
+    // Whether a target can come from anything but a single matching rule
+    fn needs_full_pass(&self, producers: &[(usize, &LabelRule)], targets: &[&str]) -> bool {
+        self.is_staged()
+            || producers.iter().any(|(_, rule)| {
+                rule.group.is_some() || self.score_thresholds.contains_key(&rule.label)
+            })
+            || self
+                .count_rules
+                .iter()
+                .any(|rule| !self.target_labels(&rule.then, targets).is_empty())
+    }
+
+    fn is_suppressed(&self, label: &str, text: &str) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.suppresses() && rule.label == label && self.rule_matches(rule, text))
+    }
+}
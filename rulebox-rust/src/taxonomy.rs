@@ -0,0 +1,56 @@
+// Label hierarchy: a label can imply parent labels (e.g. `privacy.email`
+// implies `privacy`) so parents don't need duplicate rules.
+
+use crate::RuleBox;
+use std::collections::HashSet;
+
+impl RuleBox {
+    /// Declare that assigning `label` also assigns each of `parents`.
+    pub fn with_implied_labels(mut self, label: &str, parents: &[&str]) -> Self {
+        self.implies
+            .entry(label.to_string())
+            .or_default()
+            .extend(parents.iter().map(|p| p.to_string()));
+        self
+    }
+
+    /// Every label implied by `label`, following `implies` transitively, in
+    /// breadth-first order. Cycles are tolerated and `label` itself is left out.
+    pub fn implied_labels(&self, label: &str) -> Vec<String> {
+        let mut implied: Vec<String> = Vec::new();
+        let mut seen: HashSet<&str> = HashSet::from([label]);
+        let mut queue = vec![label];
+        while let Some(current) = queue.pop() {
+            for parent in self.implies.get(current).into_iter().flatten() {
+                if seen.insert(parent) {
+                    implied.push(parent.clone());
+                    queue.insert(0, parent);
+                }
+            }
+        }
+        implied
+    }
+
+    // Add the parents of every label in `labels`
+    pub(crate) fn add_implied_labels(&self, labels: &mut HashSet<String>) {
+        if self.implies.is_empty() {
+            return;
+        }
+        let parents: Vec<String> = labels.iter().flat_map(|l| self.implied_labels(l)).collect();
+        labels.extend(parents);
+    }
+
+    // `add_implied_labels` for the batch paths' label vectors
+    pub(crate) fn push_implied_labels(&self, labels: &mut Vec<String>) {
+        if self.implies.is_empty() {
+            return;
+        }
+        for i in 0..labels.len() {
+            for parent in self.implied_labels(&labels[i]) {
+                if !labels.contains(&parent) {
+                    labels.push(parent);
+                }
+            }
+        }
+    }
+}
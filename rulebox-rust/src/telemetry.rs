@@ -0,0 +1,20 @@
+// Label counters reported through the `metrics` facade, so whichever exporter
+// the host process installs (e.g. Prometheus) picks them up.
+
+pub(crate) const LABEL_ASSIGNED_TOTAL: &str = "rulebox_label_assigned_total";
+pub(crate) const SHADOW_HIT_TOTAL: &str = "rulebox_shadow_hit_total";
+
+/// Count one assignment of each label.
+pub(crate) fn record_labels<'a>(labels: impl IntoIterator<Item = &'a String>) {
+    for label in labels {
+        metrics::counter!(LABEL_ASSIGNED_TOTAL, "label" => label.clone()).increment(1);
+    }
+}
+
+/// Count matches of shadow rules, given as `(label, uuid)`.
+pub(crate) fn record_shadow_hits(hits: &[(String, String)]) {
+    for (label, uuid) in hits {
+        metrics::counter!(SHADOW_HIT_TOTAL, "label" => label.clone(), "uuid" => uuid.clone())
+            .increment(1);
+    }
+}
@@ -0,0 +1,155 @@
+// Hot-reloading a rule file for long-running services. Readers take an `Arc`
+// to the current box, so a reload never blocks a labelling call for longer
+// than the pointer swap, and a broken edit leaves the last good box in place.
+
+use crate::format::Format;
+use crate::hash::stable_hash;
+use crate::{RegexCache, RuleBox, RuleBoxError};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// A `RuleBox` loaded from a file and swapped for a freshly compiled one
+/// whenever the file changes. If the new rules fail to load, compile or pass
+/// their examples, the previous box keeps serving and the error is kept in
+/// `last_error`.
+pub struct WatchedRuleBox {
+    shared: Arc<Shared>,
+    // Dropping the watcher stops the reloads
+    _watcher: RecommendedWatcher,
+}
+
+struct Shared {
+    path: PathBuf,
+    current: RwLock<Arc<RuleBox>>,
+    // Hash of the file contents behind `current`, so the several events an
+    // editor's save produces only cost one compile
+    content_hash: Mutex<u64>,
+    last_error: Mutex<Option<String>>,
+    generation: AtomicU64,
+    // Regexes of the serving box, so a reload only compiles changed patterns
+    cache: RegexCache,
+}
+
+impl WatchedRuleBox {
+    /// Load `path` and start watching it. The first load has to succeed.
+    pub fn new(path: &str) -> Result<Self, RuleBoxError> {
+        let path = PathBuf::from(path);
+        let text = std::fs::read_to_string(&path)?;
+        let cache = RegexCache::new();
+        let mut rulebox = Format::from_path(&path).parse(&text)?;
+        rulebox.compile_and_test_with_cache(&cache)?;
+
+        let shared = Arc::new(Shared {
+            path,
+            current: RwLock::new(Arc::new(rulebox)),
+            content_hash: Mutex::new(stable_hash(text.as_bytes())),
+            last_error: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            cache,
+        });
+
+        let handler = Arc::clone(&shared);
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else { return };
+                let relevant = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                    && event.paths.iter().any(|p| handler.is_rule_file(p));
+                if relevant {
+                    // A failure is recorded in `last_error`
+                    let _ = handler.reload();
+                }
+            })
+            .map_err(watch_error)?;
+        // Watching the directory rather than the file keeps working when an
+        // editor saves by writing a new file and renaming it over the old one
+        let dir = match shared.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+
+        Ok(Self {
+            shared,
+            _watcher: watcher,
+        })
+    }
+
+    /// The box currently serving. Holding on to it keeps that version alive
+    /// across reloads, so a batch is labelled by a single set of rules.
+    pub fn current(&self) -> Arc<RuleBox> {
+        Arc::clone(
+            &self
+                .shared
+                .current
+                .read()
+                .unwrap_or_else(|e| e.into_inner()),
+        )
+    }
+
+    /// Re-read the file now rather than waiting for a change event. Returns
+    /// whether a new box was swapped in; `false` means the contents were
+    /// unchanged.
+    pub fn reload(&self) -> Result<bool, RuleBoxError> {
+        self.shared.reload()
+    }
+
+    /// Why the most recent reload failed, or `None` if it succeeded.
+    pub fn last_error(&self) -> Option<String> {
+        self.shared
+            .last_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// How many times a new box has been swapped in since loading.
+    pub fn generation(&self) -> u64 {
+        self.shared.generation.load(Ordering::SeqCst)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.shared.path
+    }
+}
+
+impl Shared {
+    fn is_rule_file(&self, path: &Path) -> bool {
+        path.file_name() == self.path.file_name()
+    }
+
+    fn reload(&self) -> Result<bool, RuleBoxError> {
+        let result = self.load_if_changed();
+        if !matches!(result, Ok(false)) {
+            // Patterns only the replaced (or rejected) box used
+            self.cache.prune();
+        }
+        *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) =
+            result.as_ref().err().map(|e| e.to_string());
+        result
+    }
+
+    fn load_if_changed(&self) -> Result<bool, RuleBoxError> {
+        // Held throughout so concurrent reloads can't swap in an older file
+        let mut content_hash = self.content_hash.lock().unwrap_or_else(|e| e.into_inner());
+        let text = std::fs::read_to_string(&self.path)?;
+        let hash = stable_hash(text.as_bytes());
+        if hash == *content_hash {
+            return Ok(false);
+        }
+        let mut rulebox = Format::from_path(&self.path).parse(&text)?;
+        rulebox.compile_and_test_with_cache(&self.cache)?;
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(rulebox);
+        *content_hash = hash;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        Ok(true)
+    }
+}
+
+fn watch_error(e: notify::Error) -> RuleBoxError {
+    RuleBoxError::Io(io::Error::other(e))
+}
@@ -0,0 +1,113 @@
+#[cfg(all(test, feature = "arrow"))]
+mod tests {
+    use rulebox_rust::arrow_array::cast::AsArray;
+    use rulebox_rust::arrow_array::{Array, ArrayRef, Int32Array, RecordBatch, StringArray};
+    use rulebox_rust::*;
+    use std::sync::Arc;
+
+    fn rulebox() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"label": "greeting", "rule": {"or_patterns": [{"pattern": "(?i)hello"}]}},
+                {"label": "question", "rule": {"or_patterns": [{"pattern": "\\?"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    fn batch() -> RecordBatch {
+        let ids: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3]));
+        let texts: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("Hello, how are you?"),
+            None,
+            Some("goodbye"),
+        ]));
+        RecordBatch::try_from_iter([("id", ids), ("text", texts)]).unwrap()
+    }
+
+    fn labels_of(batch: &RecordBatch) -> Vec<Option<Vec<String>>> {
+        let labels = batch
+            .column_by_name(LABELS_COLUMN)
+            .unwrap()
+            .as_list::<i32>();
+        (0..labels.len())
+            .map(|i| {
+                labels.is_valid(i).then(|| {
+                    let row = labels.value(i);
+                    row.as_string::<i32>()
+                        .iter()
+                        .map(|l| l.unwrap().to_string())
+                        .collect()
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_label_record_batch_appends_labels_column() {
+        let labelled = rulebox().label_record_batch(&batch(), "text").unwrap();
+        assert_eq!(labelled.num_columns(), 3);
+        assert_eq!(labelled.schema().field(2).name(), "labels");
+        assert_eq!(
+            labels_of(&labelled),
+            vec![
+                Some(vec!["greeting".to_string(), "question".to_string()]),
+                None,
+                Some(vec![])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_label_record_batch_rejects_bad_columns() {
+        let rulebox = rulebox();
+        let missing = rulebox.label_record_batch(&batch(), "body").unwrap_err();
+        assert!(matches!(missing, RuleBoxError::Arrow(_)));
+        assert!(missing.to_string().contains("no column named \"body\""));
+
+        let not_strings = rulebox.label_record_batch(&batch(), "id").unwrap_err();
+        assert!(not_strings
+            .to_string()
+            .contains("expected a column of strings, got Arrow type Int32"));
+
+        let labelled = rulebox.label_record_batch(&batch(), "text").unwrap();
+        assert!(rulebox.label_record_batch(&labelled, "text").is_err());
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_label_parquet_streams_a_file() {
+        use rulebox_rust::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+        use rulebox_rust::parquet::arrow::ArrowWriter;
+        use std::fs::File;
+
+        let dir = std::env::temp_dir().join(format!("rulebox-parquet-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("in.parquet");
+        let output = dir.join("out.parquet");
+        let batch = batch();
+        let mut writer =
+            ArrowWriter::try_new(File::create(&input).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let rows = rulebox()
+            .label_parquet(input.to_str().unwrap(), output.to_str().unwrap(), "text")
+            .unwrap();
+        assert_eq!(rows, 6);
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&output).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let labels: Vec<_> = reader.flat_map(|b| labels_of(&b.unwrap())).collect();
+        assert_eq!(labels.len(), 6);
+        assert_eq!(labels[3], labels[0]);
+        assert_eq!(labels[4], None);
+
+        assert!(rulebox()
+            .label_parquet(input.to_str().unwrap(), output.to_str().unwrap(), "body")
+            .is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
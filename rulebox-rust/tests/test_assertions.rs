@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    #[test]
+    fn test_passing_examples_load() {
+        let rulebox = RuleBox::from_json(
+            r#"[{
+                "label": "greeting",
+                "rule": {"or_patterns": [{"pattern": "\\bhello\\b", "flags": ["i"]}]},
+                "should_match": ["Hello there", "well hello"],
+                "should_not_match": ["othello"]
+            }]"#,
+        )
+        .expect("Examples should pass");
+        assert!(rulebox.failed_assertions().is_empty());
+    }
+
+    #[test]
+    fn test_every_failed_example_is_reported() {
+        let err = RuleBox::from_json(
+            r#"[
+                {
+                    "uuid": "r1",
+                    "label": "greeting",
+                    "rule": {"or_patterns": [{"pattern": "hello"}]},
+                    "should_match": ["hi there", "hello"],
+                    "should_not_match": ["othello"]
+                },
+                {
+                    "uuid": "r2",
+                    "label": "off",
+                    "active": false,
+                    "rule": {"or_patterns": [{"pattern": "cat"}]},
+                    "should_match": ["dog"]
+                }
+            ]"#,
+        )
+        .expect_err("Failing examples should stop the load");
+
+        let RuleBoxError::Assertions(failures) = &err else {
+            panic!("Expected assertion failures, got {:?}", err);
+        };
+        let summary: Vec<(&str, &str, bool)> = failures
+            .iter()
+            .map(|f| (f.uuid.as_str(), f.text.as_str(), f.should_match))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("r1", "hi there", true),
+                ("r1", "othello", false),
+                ("r2", "dog", true)
+            ]
+        );
+        assert!(err
+            .to_string()
+            .contains("rule r1 (greeting) should not match \"othello\""));
+    }
+
+    #[test]
+    fn test_run_self_tests_report() {
+        let mut rulebox: RuleBox = serde_json::from_str(
+            r#"[
+                {
+                    "uuid": "r1",
+                    "label": "greeting",
+                    "rule": {"or_patterns": [{"pattern": "hello"}]},
+                    "positive_examples": ["hello", "hi"],
+                    "negative_examples": ["bye"]
+                },
+                {"uuid": "r2", "label": "farewell", "rule": {"or_patterns": [{"pattern": "bye"}]}}
+            ]"#,
+        )
+        .unwrap();
+        rulebox.compile().unwrap();
+        assert_eq!(rulebox.rules[0].should_match, vec!["hello", "hi"]);
+
+        let report = rulebox.run_self_tests();
+        assert!(!report.passed());
+        assert_eq!(report.rules_tested, 1);
+        assert_eq!(report.examples_run, 3);
+        assert_eq!(report.untested, vec!["r2"]);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].text, "hi");
+
+        rulebox.rules[0].should_match.pop();
+        assert!(rulebox.run_self_tests().passed());
+    }
+
+    #[test]
+    fn test_proto_text_mismatches_are_warnings() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "preprocess": {"lowercase": true},
+                "rules": [
+                    {"uuid": "r1", "label": "greeting", "proto_text": "Hello there",
+                     "rule": {"or_patterns": [{"pattern": "hello"}]}},
+                    {"uuid": "r2", "label": "farewell", "proto_text": "see you", "active": false,
+                     "rule": {"or_patterns": [{"pattern": "bye"}]}},
+                    {"uuid": "r3", "label": "other", "rule": {"or_patterns": [{"pattern": "x"}]}}
+                ]
+            }"#,
+        )
+        .expect("a proto_text mismatch doesn't stop loading");
+
+        // The box lowercases texts first; the rule on its own doesn't
+        assert!(rulebox.rules[0].validate_proto().is_err());
+        assert!(rulebox.rules[2].validate_proto().is_ok());
+        let failure = rulebox.rules[1].validate_proto().unwrap_err();
+        assert_eq!(
+            failure.to_string(),
+            "rule r2 (farewell) should match \"see you\""
+        );
+
+        let mismatches = rulebox.proto_mismatches();
+        assert_eq!(mismatches, vec![failure]);
+        let report = rulebox.run_self_tests();
+        assert!(report.passed());
+        assert_eq!(report.proto_mismatches, mismatches);
+    }
+}
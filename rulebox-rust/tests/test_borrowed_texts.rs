@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    fn greeting_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"label": "greeting", "rule": {"or_patterns": [{"pattern": "(?i)\\bhello\\b"}]}},
+                {"label": "farewell", "rule": {"or_patterns": [{"pattern": "(?i)\\bbye\\b"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_str_slices_and_iterators_match_owned_strings() {
+        let rulebox = greeting_box();
+        // Stands in for a memory-mapped file: one buffer, texts borrowed from it
+        let corpus = "Hello there\nnothing here\nbye, hello\n";
+        let borrowed: Vec<&str> = corpus.lines().collect();
+        let owned: Vec<String> = borrowed.iter().map(|t| t.to_string()).collect();
+
+        let expected = rulebox.assign_labels_vector(&owned);
+        assert_eq!(
+            expected,
+            vec![
+                vec!["greeting".to_string()],
+                vec![],
+                vec!["greeting".to_string(), "farewell".to_string()]
+            ]
+        );
+        assert_eq!(rulebox.assign_labels_vector(&borrowed), expected);
+        assert_eq!(rulebox.assign_labels_iter(corpus.lines()), expected);
+        assert!(rulebox
+            .assign_labels_iter(std::iter::empty::<&str>())
+            .is_empty());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_takes_str_slices() {
+        let rulebox = greeting_box();
+        let texts = ["hello", "bye"];
+        assert_eq!(
+            rulebox.assign_labels_vector_parallel(&texts),
+            rulebox.assign_labels_vector(&texts)
+        );
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::time::Duration;
+
+    fn rulebox() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"uuid": "r1", "label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}},
+                {"uuid": "r2", "label": "farewell", "rule": {"or_patterns": [{"pattern": "bye"}]}}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_generous_budget_labels_like_assign_labels() {
+        let rulebox = rulebox();
+        let budget = TimeBudget::default()
+            .with_max_text_bytes(1024)
+            .with_per_rule(Duration::from_secs(60))
+            .with_per_text(Duration::from_secs(60));
+        assert_eq!(
+            rulebox.assign_labels_with_budget("hello and bye", &budget),
+            Ok(rulebox.assign_labels("hello and bye"))
+        );
+        assert_eq!(
+            rulebox.assign_labels_with_budget("hello", &TimeBudget::default()),
+            Ok(vec!["greeting".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_long_text_is_rejected() {
+        let budget = TimeBudget::default().with_max_text_bytes(5);
+        let err = rulebox()
+            .assign_labels_with_budget("hello there", &budget)
+            .unwrap_err();
+        assert_eq!(err, BudgetExceeded::TextTooLong { len: 11, limit: 5 });
+        assert_eq!(err.uuid(), None);
+        assert_eq!(err.to_string(), "Text is 11 bytes, over the limit of 5");
+    }
+
+    #[test]
+    fn test_slow_rule_is_named() {
+        let budget = TimeBudget::default().with_per_rule(Duration::ZERO);
+        let err = rulebox()
+            .assign_labels_with_budget("hello and bye", &budget)
+            .unwrap_err();
+        assert!(matches!(err, BudgetExceeded::RuleTooSlow { .. }));
+        assert_eq!(err.uuid(), Some("r1"));
+        assert!(err.to_string().starts_with("rule r1 (greeting) took"));
+
+        let budget = TimeBudget::default().with_per_text(Duration::ZERO);
+        let err = rulebox()
+            .assign_labels_with_budget("hello and bye", &budget)
+            .unwrap_err();
+        assert!(matches!(err, BudgetExceeded::TextTooSlow { .. }));
+        assert_eq!(err.uuid(), Some("r1"));
+    }
+
+    #[test]
+    fn test_batch_keeps_going_past_a_text_over_budget() {
+        let budget = TimeBudget::default().with_max_text_bytes(8);
+        let results =
+            rulebox().assign_labels_vector_with_budget(&["hello", "hello and bye", "bye"], &budget);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], Ok(vec!["greeting".to_string()]));
+        assert!(matches!(
+            results[1],
+            Err(BudgetExceeded::TextTooLong { len: 13, limit: 8 })
+        ));
+        assert_eq!(results[2], Ok(vec!["farewell".to_string()]));
+    }
+}
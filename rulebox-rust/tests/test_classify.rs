@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    fn classify_rulebox() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {
+                    "uuid": "ham-1",
+                    "label": "ham",
+                    "rule": {"or_patterns": [{"pattern": "meeting"}]}
+                },
+                {
+                    "uuid": "spam-1",
+                    "label": "spam",
+                    "priority": 10,
+                    "tags": ["moderation", "v2"],
+                    "rule": {"or_patterns": [{"pattern": "free money"}]}
+                },
+                {
+                    "uuid": "ham-2",
+                    "label": "newsletter",
+                    "rule": {"or_patterns": [{"pattern": "unsubscribe"}]}
+                }
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_classify_prefers_priority_then_rule_order() {
+        let rulebox = classify_rulebox();
+
+        assert_eq!(
+            rulebox.classify("free money at the meeting"),
+            Some("spam".to_string())
+        );
+        assert_eq!(
+            rulebox.classify("meeting notes, unsubscribe here"),
+            Some("ham".to_string())
+        );
+        assert_eq!(rulebox.classify("nothing to see"), None);
+    }
+
+    #[test]
+    fn test_classify_detailed_reports_winning_rule() {
+        let rulebox = classify_rulebox();
+
+        let result = rulebox
+            .classify_detailed("free money at the meeting")
+            .expect("Expected a winning rule");
+        assert_eq!(
+            result,
+            ClassifyResult {
+                label: "spam".to_string(),
+                uuid: "spam-1".to_string(),
+                priority: 10,
+                tags: vec!["moderation".to_string(), "v2".to_string()],
+            }
+        );
+        assert!(rulebox.classify_detailed("nothing to see").is_none());
+    }
+
+    #[test]
+    fn test_classify_only_picks_rules_that_assign_their_label() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "score_thresholds": {"weak": 1.5},
+                "rules": [
+                    {"uuid": "hi", "label": "spam", "group": "verdict", "priority": 5, "rule": {"or_patterns": [{"pattern": "free"}]}},
+                    {"uuid": "lo", "label": "promo", "group": "verdict", "priority": 3, "rule": {"or_patterns": [{"pattern": "offer"}]}},
+                    {"uuid": "w", "label": "weak", "priority": 9, "rule": {"or_patterns": [{"pattern": "offer"}]}},
+                    {"uuid": "pd", "label": "pd", "priority": 8, "rule": {"or_patterns": [{"pattern": "offer"}]}},
+                    {"uuid": "no-pd", "label": "pd", "kind": "suppress", "rule": {"or_patterns": [{"pattern": "footer"}]}},
+                    {"uuid": "later", "label": "later", "priority": 7, "stage": 1, "requires_labels": ["promo"], "rule": {"or_patterns": [{"pattern": "free"}]}}
+                ]
+            }"#,
+        )
+        .expect("Failed to load rules");
+
+        // `weak` is under its threshold, `pd` is suppressed, `later` needs
+        // `promo`, which lost its group
+        let text = "free offer in the footer";
+        assert_eq!(rulebox.classify(text), Some("spam".to_string()));
+        // Without `free`, `promo` wins the group and `pd` isn't suppressed
+        assert_eq!(rulebox.classify("an offer"), Some("pd".to_string()));
+        assert_eq!(
+            rulebox
+                .classify_detailed("an offer in the footer")
+                .map(|c| c.uuid),
+            Some("lo".to_string())
+        );
+    }
+
+    #[test]
+    fn test_check_topk_evaluates_highest_priority_rules() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "low", "rule": {"or_patterns": [{"pattern": "a"}]}},
+                {"label": "high", "priority": 10, "rule": {"or_patterns": [{"pattern": "a"}]}},
+                {"label": "mid", "priority": 5, "rule": {"or_patterns": [{"pattern": "a"}]}},
+                {"label": "mid_late", "priority": 5, "rule": {"or_patterns": [{"pattern": "a"}]}},
+                {"label": "off", "active": false, "priority": 99, "rule": {"or_patterns": [{"pattern": "a"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let labeled = rulebox.check_topk("a", 2);
+        let mut labels: Vec<&String> = labeled.labels().iter().collect();
+        labels.sort();
+        assert_eq!(labels, vec!["high", "mid"]);
+        assert_eq!(rulebox.check_topk("a", 10).labels().len(), 4);
+        assert!(rulebox.check_topk("a", 0).labels().is_empty());
+    }
+
+    #[test]
+    fn test_check_with_gate_switches_rules_at_check_time() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "stable", "rule": {"or_patterns": [{"pattern": "a"}]}},
+                {"label": "beta", "tags": ["flag:beta"], "rule": {"or_patterns": [{"pattern": "a"}]}},
+                {"label": "off", "active": false, "rule": {"or_patterns": [{"pattern": "a"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        let sorted = |labeled: LabeledText| {
+            let mut labels: Vec<String> = labeled.labels().iter().cloned().collect();
+            labels.sort();
+            labels
+        };
+
+        let beta_enabled = std::cell::Cell::new(false);
+        let gate =
+            |rule: &LabelRule| !rule.tags.contains(&"flag:beta".to_string()) || beta_enabled.get();
+        assert_eq!(sorted(rulebox.check_with_gate("a", gate)), vec!["stable"]);
+        beta_enabled.set(true);
+        assert_eq!(
+            sorted(rulebox.check_with_gate("a", gate)),
+            vec!["beta", "stable"]
+        );
+        // Statically inactive rules stay off whatever the gate says
+        assert_eq!(
+            sorted(rulebox.check_with_gate("a", |_| true)),
+            vec!["beta", "stable"]
+        );
+    }
+
+    #[test]
+    fn test_check_lang_skips_other_languages() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "thanks_en", "rule": {"or_patterns": [{"pattern": "merci|thanks"}], "lang": "en"}},
+                {"label": "thanks_fr", "rule": {"or_patterns": [{"pattern": "merci|thanks"}], "lang": "fr"}},
+                {"label": "any", "rule": {"or_patterns": [{"pattern": "merci|thanks"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        let sorted = |labeled: LabeledText| {
+            let mut labels: Vec<String> = labeled.labels().iter().cloned().collect();
+            labels.sort();
+            labels
+        };
+
+        assert_eq!(
+            sorted(rulebox.check_lang("merci", "FR")),
+            vec!["any", "thanks_fr"]
+        );
+        assert_eq!(sorted(rulebox.check_lang("thanks", "de")), vec!["any"]);
+        assert_eq!(rulebox.check("thanks").labels().len(), 3);
+    }
+}
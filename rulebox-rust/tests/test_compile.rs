@@ -0,0 +1,319 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    #[test]
+    fn test_recompile_dirty_picks_up_edited_patterns() {
+        let mut rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "animal", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"label": "colour", "rule": {"or_patterns": [{"pattern": "red"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let pattern = &mut rulebox.rules[0].rule.or_patterns[0];
+        pattern.pattern = "dog".to_string();
+        pattern.invalidate();
+        assert!(!rulebox.rules[0].rule.is_compiled());
+        assert!(rulebox.rules[1].rule.is_compiled());
+
+        rulebox.recompile_dirty().expect("Failed to recompile");
+        assert!(rulebox.rules[0].rule.is_compiled());
+
+        let texts = vec!["a red dog".to_string(), "a cat".to_string()];
+        let results = rulebox.assign_labels_vector(&texts);
+        assert_eq!(results[0], vec!["animal".to_string(), "colour".to_string()]);
+        assert!(results[1].is_empty());
+    }
+
+    #[test]
+    fn test_default_flags_apply_to_flagless_patterns() {
+        let json = r#"[
+            {"label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}},
+            {"label": "shout", "rule": {"or_patterns": [{"pattern": "HEY", "flags": ["m"]}]}}
+        ]"#;
+        let texts = vec!["HELLO there".to_string(), "hey you".to_string()];
+
+        let rulebox = RuleBox::from_json(json).expect("Failed to load rules");
+        let results = rulebox.assign_labels_vector(&texts);
+        assert!(results[0].is_empty());
+
+        let rulebox = rulebox
+            .with_default_flags(&["i"])
+            .expect("Failed to apply default flags");
+        let results = rulebox.assign_labels_vector(&texts);
+        assert_eq!(results[0], vec!["greeting".to_string()]);
+        // Explicit flags are kept as-is, so this pattern stays case-sensitive
+        assert!(results[1].is_empty());
+    }
+
+    #[test]
+    fn test_default_flags_load_from_object_format() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "default_flags": ["i"],
+                "rules": [{"label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}}]
+            }"#,
+        )
+        .expect("Failed to load rules");
+        assert_eq!(rulebox.default_flags, vec!["i".to_string()]);
+        assert_eq!(rulebox.assign_labels("HELLO"), vec!["greeting".to_string()]);
+    }
+
+    #[test]
+    fn test_flag_presets_expand_at_compile() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "flag_presets": {"ci_multiline": ["i", "m"]},
+                "rules": [
+                    {
+                        "label": "heading",
+                        "rule": {"or_patterns": [{"pattern": "^title$", "flags": ["@ci_multiline"]}]}
+                    }
+                ]
+            }"#,
+        )
+        .expect("Failed to load rules");
+        assert_eq!(
+            rulebox.assign_labels("intro\nTITLE\nbody"),
+            vec!["heading".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_unknown_flag_preset_is_compile_error() {
+        let err = RuleBox::from_json(
+            r#"[{"label": "x", "rule": {"or_patterns": [{"pattern": "x", "flags": ["@missing"]}]}}]"#,
+        )
+        .expect_err("Unknown preset should fail to compile");
+        assert!(err.to_string().contains("@missing"));
+    }
+
+    #[test]
+    #[cfg(feature = "fs")]
+    fn test_from_path_filtered_keeps_only_wanted_labels() {
+        let path =
+            std::env::temp_dir().join(format!("rulebox-filtered-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"[
+                {"label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"label": "dog", "rule": {"or_patterns": [{"pattern": "dog"}]}},
+                {"label": "broken", "rule": {"or_patterns": [{"pattern": "("}]}}
+            ]"#,
+        )
+        .unwrap();
+
+        let rulebox = RuleBox::from_path_filtered(path.to_str().unwrap(), &["cat"])
+            .expect("Unwanted rules shouldn't be compiled");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(rulebox.rules.len(), 1);
+        assert_eq!(
+            rulebox.assign_labels("cat and dog"),
+            vec!["cat".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_from_str() {
+        let rulebox: RuleBox =
+            r#"[{"label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}]}}]"#
+                .parse()
+                .expect("Failed to parse rules");
+        assert_eq!(rulebox.assign_labels("a cat"), vec!["cat".to_string()]);
+    }
+
+    #[test]
+    fn test_perl_classes_follow_unicode_feature() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "word", "rule": {"or_patterns": [{"pattern": "^\\w+$"}]}},
+                {"label": "any", "rule": {"or_patterns": [{"pattern": "^.$"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        let labels = rulebox.assign_labels_vector(&["é".to_string()]);
+        // Without the `unicode` feature `\w` is ASCII-only, but `.` still works
+        if cfg!(feature = "unicode") {
+            assert_eq!(labels, vec![vec!["word", "any"]]);
+        } else {
+            assert_eq!(labels, vec![vec!["any"]]);
+        }
+    }
+
+    fn compile_error(json: &str) -> CompileError {
+        match RuleBox::from_json(json) {
+            Err(RuleBoxError::Compile(e)) => e,
+            other => panic!("expected a compile error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_compile_errors_are_structured() {
+        let e = compile_error(
+            r#"[{"uuid": "r1", "label": "x", "rule": {"or_patterns": [{"pattern": "(open"}]}}]"#,
+        );
+        assert_eq!(e.uuid(), Some("r1"));
+        assert_eq!(e.pattern(), Some("(open"));
+        assert!(matches!(e.root(), CompileError::Regex { .. }));
+        assert!(std::error::Error::source(&e).is_some());
+
+        let e = compile_error(
+            r#"[{"uuid": "r2", "label": "x", "rule": {"or_patterns": [{"pattern": "a", "flags": ["z"]}]}}]"#,
+        );
+        assert_eq!(
+            e.root(),
+            &CompileError::UnknownFlag {
+                pattern: "a".to_string(),
+                flag: "z".to_string()
+            }
+        );
+
+        let e = compile_error(
+            r#"[{"uuid": "r3", "label": "x", "rule": {"or_patterns": [{"pattern": "a", "flags": ["@nope"]}]}}]"#,
+        );
+        assert!(
+            matches!(e.root(), CompileError::UnknownFlagPreset { preset, .. } if preset == "@nope")
+        );
+
+        let e = compile_error(
+            r#"[{"uuid": "r4", "label": "x", "rule": {
+                "and_patterns": [{"pattern": "a"}], "or_patterns": [{"pattern": "b"}]}}]"#,
+        );
+        assert_eq!(e.root(), &CompileError::MixedAndOr);
+        assert_eq!(e.pattern(), None);
+        assert_eq!(
+            e.to_string(),
+            "rule r4: Rule can't have both and_patterns and or_patterns"
+        );
+
+        let e = compile_error(
+            r#"[{"uuid": "r5", "label": "x", "rule": {"not_patterns": [{"pattern": "a"}]}}]"#,
+        );
+        assert_eq!(e.root(), &CompileError::OnlyNotPatterns);
+    }
+
+    #[test]
+    fn test_full_flag_set() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "dotall", "rule": {"or_patterns": [{"pattern": "start.+end", "flags": ["s"]}]}},
+                {"label": "verbose", "rule": {"or_patterns": [
+                    {"pattern": "\\d{3} - \\d{4}  # phone number", "flags": ["x"]},
+                    {"pattern": "never", "flags": ["x"]}
+                ]}},
+                {"label": "crlf", "rule": {"or_patterns": [{"pattern": "^the$", "flags": ["m", "R"]}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        assert_eq!(rulebox.assign_labels("start\nend"), vec!["dotall"]);
+        assert_eq!(rulebox.assign_labels("call 555-1234"), vec!["verbose"]);
+        assert_eq!(rulebox.assign_labels("the\r\nend"), vec!["crlf"]);
+
+        let mut lazy = RegexRule {
+            pattern: "a+".to_string(),
+            flags: vec!["U".to_string()],
+            ..Default::default()
+        };
+        lazy.compile().unwrap();
+        assert_eq!(lazy.compiled.unwrap().find("aaa").unwrap().as_str(), "a");
+    }
+
+    #[test]
+    fn test_regex_size_limit_names_the_rule() {
+        let json = r#"{
+            "regex_limits": {"size_limit": 2000},
+            "rules": [
+                {"uuid": "small", "label": "a", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"uuid": "big", "label": "b", "rule": {"or_patterns": [{"pattern": "(abc|def){500}"}]}}
+            ]
+        }"#;
+        let Err(RuleBoxError::Compile(err)) = RuleBox::from_json(json) else {
+            panic!("expected a compile error");
+        };
+        assert_eq!(err.uuid(), Some("big"));
+        assert_eq!(err.pattern(), Some("(abc|def){500}"));
+        assert!(matches!(
+            err.root(),
+            CompileError::SizeLimitExceeded { limit: 2000, .. }
+        ));
+        assert!(err
+            .to_string()
+            .starts_with("rule big: Pattern '(abc|def){500}'"));
+
+        // A rule can raise the box's limit for its own patterns
+        let raised = json.replace(
+            r#""uuid": "big","#,
+            r#""uuid": "big", "regex_limits": {"size_limit": 10000000},"#,
+        );
+        let rulebox = RuleBox::from_json(&raised).unwrap();
+        assert_eq!(rulebox.rules[1].regex_limits.size_limit, Some(10_000_000));
+        let saved = rulebox.to_json_string().unwrap();
+        assert!(saved.contains(r#""size_limit": 2000"#));
+        assert!(RuleBox::from_json(&saved).is_ok());
+
+        // The regex crate's own limit is far higher
+        let unlimited = json.replace(r#""regex_limits": {"size_limit": 2000},"#, "");
+        assert!(RuleBox::from_json(&unlimited).is_ok());
+    }
+
+    #[test]
+    fn test_ascii_flag_limits_word_chars_and_case_folding() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "unicode_word", "rule": {"or_patterns": [{"pattern": "\\bber"}]}},
+                {"label": "ascii_word", "rule": {"or_patterns": [
+                    {"pattern": "\\bber", "flags": ["a"]},
+                    {"pattern": "never", "flags": ["a"]}
+                ]}},
+                {"label": "unicode_fold", "rule": {"or_patterns": [{"pattern": "ÉTÉ", "flags": ["i"]}]}},
+                {"label": "ascii_fold", "rule": {"or_patterns": [{"pattern": "ÉTÉ", "flags": ["i", "a"]}]}},
+                {"label": "ascii_any", "rule": {"or_patterns": [{"pattern": "^.$", "flags": ["a"]}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        // In ASCII mode `ü` isn't a word character, so `\b` falls before `b`
+        assert_eq!(rulebox.assign_labels("überall"), vec!["ascii_word"]);
+        assert_eq!(
+            rulebox.assign_labels("ber"),
+            vec!["unicode_word", "ascii_word"]
+        );
+        assert_eq!(rulebox.assign_labels("été"), vec!["unicode_fold"]);
+        assert_eq!(
+            rulebox.assign_labels("ÉtÉ"),
+            vec!["unicode_fold", "ascii_fold"]
+        );
+        // `.` can't be ASCII-only on text, so it still matches any character
+        assert_eq!(rulebox.assign_labels("é"), vec!["ascii_any"]);
+    }
+
+    #[test]
+    fn test_ascii_flag_with_word_match_mode() {
+        let rulebox = RuleBox::from_json(
+            r#"[{"label": "coffee", "rule": {"or_patterns": [
+                {"pattern": "café", "match_mode": "word", "flags": ["a"]}
+            ]}}]"#,
+        )
+        .expect("Failed to load rules");
+        assert_eq!(rulebox.assign_labels("un café noir"), vec!["coffee"]);
+        assert!(rulebox.assign_labels("cafés").is_empty());
+    }
+
+    #[test]
+    fn test_ascii_and_unicode_flags_conflict() {
+        let mut rule = RegexRule {
+            pattern: "\\w".to_string(),
+            flags: vec!["a".to_string(), "u".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            rule.compile(),
+            Err(CompileError::ConflictingFlags {
+                pattern: "\\w".to_string(),
+                flags: "a, u".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    #[test]
+    fn test_simple_patterns_pass_default_limits() {
+        let limits = ComplexityLimits::default();
+        assert!(validate_pattern_complexity(r"\bhello\b|\bhi\b", &limits).is_ok());
+        assert!(
+            validate_pattern_complexity(r"[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}", &limits).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_complex_patterns_are_rejected() {
+        let limits = ComplexityLimits {
+            max_alternations: 2,
+            max_repetition_bound: 50,
+            max_nesting_depth: 2,
+            ..Default::default()
+        };
+
+        let err = validate_pattern_complexity("a|b|c|d", &limits).unwrap_err();
+        assert!(matches!(err, CompileError::TooComplex { .. }));
+        assert!(err.to_string().contains("alternations"));
+
+        let err = validate_pattern_complexity("a{1,500}", &limits).unwrap_err();
+        assert!(err.to_string().contains("repetition bound 500"));
+
+        let err = validate_pattern_complexity("((a+)+)+", &limits).unwrap_err();
+        assert!(err.to_string().contains("nesting"));
+    }
+
+    #[test]
+    fn test_invalid_syntax_is_reported() {
+        let err =
+            validate_pattern_complexity("(unclosed", &ComplexityLimits::default()).unwrap_err();
+        assert!(matches!(
+            err,
+            CompileError::Regex {
+                source: regex::Error::Syntax(_),
+                ..
+            }
+        ));
+        assert_eq!(err.pattern(), Some("(unclosed"));
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_lazy_regex_set_is_shared_across_threads() {
+        assert_send_sync::<RuleBox>();
+
+        let rulebox = Arc::new(
+            RuleBox::from_json(
+                r#"[
+                    {
+                        "label": "greeting",
+                        "rule": {
+                            "or_patterns": [
+                                {"pattern": "\\bhello\\b", "flags": ["i"]},
+                                {"pattern": "\\bhi\\b"},
+                                {"pattern": "^hey", "flags": ["m"]}
+                            ]
+                        }
+                    }
+                ]"#,
+            )
+            .expect("Failed to load rules"),
+        );
+        assert!(!rulebox.rules[0].rule.or_set.is_built());
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let rulebox = Arc::clone(&rulebox);
+                thread::spawn(move || {
+                    let texts = vec![
+                        format!("HELLO {}", i),
+                        "say hi".to_string(),
+                        "line one\nhey there".to_string(),
+                        "Hi there".to_string(),
+                    ];
+                    for _ in 0..100 {
+                        let results = rulebox.assign_labels_vector(&texts);
+                        assert_eq!(results[0], vec!["greeting".to_string()]);
+                        assert_eq!(results[1], vec!["greeting".to_string()]);
+                        assert_eq!(results[2], vec!["greeting".to_string()]);
+                        assert!(results[3].is_empty());
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("Labeling thread panicked");
+        }
+
+        assert!(rulebox.rules[0].rule.or_set.is_built());
+    }
+
+    #[test]
+    fn test_handle_clones_check_concurrently() {
+        assert_send_sync::<RuleBoxHandle>();
+
+        let handle = RuleBox::from_json(
+            r#"[
+                {"uuid": "g", "label": "greeting", "rule": {"or_patterns": [{"pattern": "(?i)hello"}]}},
+                {"uuid": "f", "label": "farewell", "rule": {"or_patterns": [{"pattern": "(?i)bye"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+        .with_stats()
+        .into_handle();
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let rulebox = handle.clone();
+                thread::spawn(move || {
+                    let text = if i % 2 == 0 { "Hello" } else { "bye now" };
+                    let expected = if i % 2 == 0 { "greeting" } else { "farewell" };
+                    for _ in 0..200 {
+                        let labeled = rulebox.check(text);
+                        assert_eq!(labeled.labels().len(), 1);
+                        assert!(labeled.labels().contains(expected));
+                    }
+                })
+            })
+            .collect();
+        for thread in handles {
+            thread.join().expect("Labeling thread panicked");
+        }
+
+        // Every evaluation from every thread was counted
+        let stats = handle.stats().unwrap();
+        assert_eq!(stats[0].evaluations + stats[1].evaluations, 8 * 200 * 2);
+        assert_eq!(stats[0].matches, 4 * 200);
+        assert_eq!(stats[1].matches, 4 * 200);
+        assert_eq!(Arc::strong_count(handle.as_arc()), 1);
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_cooccurrence_counts_unordered_pairs() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"label": "dog", "rule": {"or_patterns": [{"pattern": "dog"}]}},
+                {"label": "animal", "rule": {"or_patterns": [{"pattern": "cat|dog"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let texts: Vec<String> = ["cat", "cat and dog", "dog", "fish"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(
+            rulebox.cooccurrence(&texts),
+            HashMap::from([
+                (pair("animal", "cat"), 2),
+                (pair("animal", "dog"), 2),
+                (pair("cat", "dog"), 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_confusion_matrix_uses_classify() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"label": "dog", "priority": 1, "rule": {"or_patterns": [{"pattern": "dog"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let gold: Vec<(String, String)> = [
+            ("a cat", "cat"),
+            ("cat and dog", "cat"),
+            ("dog", "dog"),
+            ("fish", "fish"),
+            ("big cat", "cat"),
+        ]
+        .iter()
+        .map(|(text, label)| (text.to_string(), label.to_string()))
+        .collect();
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+
+        assert_eq!(
+            rulebox.confusion_matrix(&gold),
+            HashMap::from([
+                (pair("cat", "cat"), 2),
+                (pair("cat", "dog"), 1),
+                (pair("dog", "dog"), 1),
+                (pair("fish", "none"), 1),
+            ])
+        );
+        assert_eq!(
+            rulebox.confusion_matrix_with_none(&gold, "<unlabeled>")[&pair("fish", "<unlabeled>")],
+            1
+        );
+    }
+
+    #[test]
+    fn test_coverage_finds_dead_and_redundant_rules() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "cat", "label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"uuid": "feline", "label": "cat", "rule": {"or_patterns": [{"pattern": "cats?"}]}},
+                {"uuid": "dog", "label": "dog", "rule": {"or_patterns": [{"pattern": "dog"}]}},
+                {"uuid": "bird", "label": "bird", "rule": {"or_patterns": [{"pattern": "bird"}]}},
+                {"uuid": "off", "label": "cat", "active": false, "rule": {"or_patterns": [{"pattern": "cat"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let texts: Vec<String> = ["cat", "cats and dog", "dog", "fish"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let report = rulebox.coverage(&texts);
+        assert_eq!(report.texts, 4);
+        let matches: Vec<(&str, usize)> = report
+            .rules
+            .iter()
+            .map(|r| (r.uuid.as_str(), r.matches))
+            .collect();
+        assert_eq!(
+            matches,
+            vec![("cat", 2), ("feline", 2), ("dog", 2), ("bird", 0)]
+        );
+        assert_eq!(report.never_fired, vec!["bird".to_string()]);
+        assert_eq!(
+            report.always_together,
+            vec![("cat".to_string(), "feline".to_string())]
+        );
+    }
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    fn pii_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"{
+                "rules": [
+                    {"label": "pii_email", "rule": {"or_patterns": [{"pattern": "@example\\.com"}]}},
+                    {"label": "pii_phone", "rule": {"or_patterns": [{"pattern": "\\d{5} \\d{6}"}]}},
+                    {"label": "pii_name", "rule": {"or_patterns": [{"pattern": "Jane Doe"}]}},
+                    {"label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}}
+                ],
+                "count_rules": [
+                    {"if_labels_count_at_least": 3, "among": ["pii_*"], "then": "high_risk"},
+                    {"if_labels_count_at_least": 2, "then": "busy"}
+                ]
+            }"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_count_rule_needs_enough_matching_labels() {
+        let rulebox = pii_box();
+        let text = "hello Jane Doe, jane@example.com";
+        let mut labels = rulebox.assign_labels(text);
+        labels.sort();
+        assert_eq!(labels, vec!["busy", "greeting", "pii_email", "pii_name"]);
+
+        let text = "Jane Doe, jane@example.com, 01234 567890";
+        assert_eq!(
+            rulebox.assign_labels_vector(&[text.to_string()]),
+            vec![vec![
+                "pii_email",
+                "pii_phone",
+                "pii_name",
+                "high_risk",
+                "busy"
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_count_rules_round_trip() {
+        let rulebox = pii_box();
+        let json = serde_json::to_string(&rulebox).unwrap();
+        let reloaded = RuleBox::from_json(&json).unwrap();
+        assert_eq!(reloaded.count_rules.len(), 2);
+        assert_eq!(reloaded.count_rules[0].among, vec!["pii_*"]);
+    }
+}
@@ -0,0 +1,248 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    #[test]
+    fn test_check_debug_reports_vetoed_rules() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {
+                    "uuid": "personal-1",
+                    "label": "personal_data",
+                    "rule": {
+                        "or_patterns": [{"pattern": "@"}],
+                        "not_patterns": [{"pattern": "unsubscribe", "flags": ["i"]}]
+                    }
+                },
+                {
+                    "uuid": "contact-1",
+                    "label": "contact",
+                    "rule": {"or_patterns": [{"pattern": "email"}]}
+                },
+                {
+                    "uuid": "inactive-1",
+                    "label": "inactive",
+                    "active": false,
+                    "rule": {
+                        "or_patterns": [{"pattern": "email"}],
+                        "not_patterns": [{"pattern": "Unsubscribe"}]
+                    }
+                }
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let result = rulebox.check_debug("email me@example.com to Unsubscribe");
+        assert_eq!(result.assigned.len(), 1);
+        assert!(result.assigned.contains("contact"));
+        assert_eq!(
+            result.vetoed,
+            vec![("personal_data".to_string(), "personal-1".to_string())]
+        );
+
+        let result = rulebox.check_debug("email me@example.com");
+        assert!(result.assigned.contains("personal_data"));
+        assert!(result.vetoed.is_empty());
+    }
+
+    #[test]
+    fn test_check_debug_assigns_what_check_does() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "implies": {"email": ["contact"]},
+                "score_thresholds": {"weak": 1.5},
+                "rules": [
+                    {"label": "email", "rule": {"or_patterns": [{"pattern": "@"}]}},
+                    {"label": "ham", "group": "verdict", "rule": {"or_patterns": [{"pattern": "footer"}]}},
+                    {"label": "spam", "group": "verdict", "priority": 5, "rule": {"or_patterns": [{"pattern": "@"}]}},
+                    {"label": "weak", "rule": {"or_patterns": [{"pattern": "footer"}]}},
+                    {"label": "pd", "rule": {"or_patterns": [{"pattern": "@"}]}},
+                    {"label": "pd", "kind": "suppress", "rule": {"or_patterns": [{"pattern": "footer"}]}}
+                ]
+            }"#,
+        )
+        .expect("Failed to load rules");
+
+        let text = "me@example.com in the footer";
+        let result = rulebox.check_debug(text);
+        assert_eq!(&result.assigned, rulebox.check(text).labels());
+        let mut assigned: Vec<&str> = result.assigned.iter().map(String::as_str).collect();
+        assigned.sort();
+        assert_eq!(assigned, vec!["contact", "email", "spam"]);
+    }
+
+    #[test]
+    fn test_check_with_provenance_across_boxes() {
+        let pii = RuleBox::from_json(
+            r#"[
+                {"uuid": "email-1", "label": "email", "rule": {"or_patterns": [{"pattern": "@"}]}},
+                {"uuid": "email-2", "label": "email", "rule": {"or_patterns": [{"pattern": "mailto:"}]}}
+            ]"#,
+        )
+        .unwrap()
+        .with_source("pii");
+        let tone = RuleBox::from_json(
+            r#"[{"uuid": "polite-1", "label": "polite", "rule": {"or_patterns": [{"pattern": "please"}]}}]"#,
+        )
+        .unwrap()
+        .with_source("tone");
+
+        let text = "please write to mailto:me@example.com";
+        let mut provenance = pii.check_with_provenance(text);
+        provenance.extend(tone.check_with_provenance(text));
+
+        let expected = [
+            ("email", "pii", "email-1"),
+            ("email", "pii", "email-2"),
+            ("polite", "tone", "polite-1"),
+        ];
+        assert_eq!(provenance.len(), expected.len());
+        for ((label, source, uuid), (e_label, e_source, e_uuid)) in provenance.iter().zip(expected)
+        {
+            assert_eq!(
+                (label.as_str(), source.as_str(), uuid.as_str()),
+                (e_label, e_source, e_uuid)
+            );
+        }
+    }
+
+    #[test]
+    fn test_provenance_follows_the_labelling_pass() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "implies": {"email": ["contact"]},
+                "score_thresholds": {"weak": 1.5},
+                "count_rules": [{"if_labels_count_at_least": 3, "then": "busy"}],
+                "rules": [
+                    {"uuid": "e1", "label": "email", "rule": {"or_patterns": [{"pattern": "@"}]}},
+                    {"uuid": "ham", "label": "ham", "group": "verdict", "rule": {"or_patterns": [{"pattern": "footer"}]}},
+                    {"uuid": "spam", "label": "spam", "group": "verdict", "priority": 5, "rule": {"or_patterns": [{"pattern": "@"}]}},
+                    {"uuid": "w", "label": "weak", "rule": {"or_patterns": [{"pattern": "footer"}]}},
+                    {"uuid": "pd", "label": "pd", "rule": {"or_patterns": [{"pattern": "@"}]}},
+                    {"uuid": "no-pd", "label": "pd", "kind": "suppress", "rule": {"or_patterns": [{"pattern": "footer"}]}},
+                    {"uuid": "later", "label": "later", "stage": 1, "requires_labels": ["ham"], "rule": {"or_patterns": [{"pattern": "@"}]}}
+                ]
+            }"#,
+        )
+        .expect("Failed to load rules")
+        .with_source("pii");
+
+        let provenance = rulebox.check_with_provenance("me@example.com in the footer");
+        let provenance: Vec<(&str, &str, &str)> = provenance
+            .iter()
+            .map(|(l, s, u)| (l.as_str(), s.as_str(), u.as_str()))
+            .collect();
+        assert_eq!(
+            provenance,
+            vec![
+                ("email", "pii", "e1"),
+                ("spam", "pii", "spam"),
+                ("contact", "pii", "e1"),
+                ("busy", "pii", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preview_toggle_lists_changed_texts() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "cat1", "label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"uuid": "cat2", "label": "cat", "rule": {"or_patterns": [{"pattern": "kitten"}]}},
+                {"uuid": "dog", "label": "dog", "active": false, "rule": {"or_patterns": [{"pattern": "dog"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        let texts: Vec<String> = ["cat", "cat kitten", "dog", "kitten", "fish"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        // "cat kitten" keeps its label through the other rule
+        assert_eq!(rulebox.preview_toggle("cat1", &texts), vec![0]);
+        assert_eq!(rulebox.preview_toggle("dog", &texts), vec![2]);
+        assert!(rulebox.preview_toggle("missing", &texts).is_empty());
+        assert!(!rulebox.rules[2].active);
+    }
+
+    #[test]
+    fn test_preview_toggle_follows_stages_and_groups() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "a", "label": "a", "rule": {"or_patterns": [{"pattern": "a"}]}},
+                {"uuid": "b", "label": "b", "active": false, "stage": 1, "requires_labels": ["a"], "rule": {"or_patterns": [{"pattern": "b"}]}},
+                {"uuid": "hi", "label": "hi", "group": "g", "priority": 5, "rule": {"or_patterns": [{"pattern": "x"}]}},
+                {"uuid": "lo", "label": "lo", "group": "g", "active": false, "rule": {"or_patterns": [{"pattern": "x"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        let texts: Vec<String> = ["b", "a b", "x"].iter().map(|s| s.to_string()).collect();
+
+        // `b` needs `a` from the first stage
+        assert_eq!(rulebox.preview_toggle("b", &texts), vec![1]);
+        // `lo` would lose its group to `hi`
+        assert!(rulebox.preview_toggle("lo", &texts).is_empty());
+    }
+
+    #[test]
+    fn test_preview_toggle_runs_suppressors_on_both_sides() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "pd", "label": "pd", "rule": {"or_patterns": [{"pattern": "email"}]}},
+                {"uuid": "s", "label": "pd", "kind": "suppress", "rule": {"or_patterns": [{"pattern": "newsletter"}]}},
+                {"uuid": "s2", "label": "pd", "kind": "suppress", "active": false, "rule": {"or_patterns": [{"pattern": "footer"}]}},
+                {"uuid": "x", "label": "x", "rule": {"or_patterns": [{"pattern": "unrelated"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        let texts: Vec<String> = ["email newsletter", "email footer"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert!(rulebox.preview_toggle("x", &texts).is_empty());
+        // Switching `s` off lets `pd` through; switching `s2` on removes it
+        assert_eq!(rulebox.preview_toggle("s", &texts), vec![0]);
+        assert_eq!(rulebox.preview_toggle("s2", &texts), vec![1]);
+    }
+
+    #[test]
+    fn test_preview_toggle_ignores_shadow_rules() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "live", "label": "pet", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"uuid": "canary", "label": "dog", "shadow": true, "rule": {"or_patterns": [{"pattern": "dog"}]}},
+                {"uuid": "retired", "label": "dog", "shadow": true, "active": false, "rule": {"or_patterns": [{"pattern": "dog"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        let texts: Vec<String> = ["cat", "dog"].iter().map(|s| s.to_string()).collect();
+
+        assert!(rulebox.preview_toggle("canary", &texts).is_empty());
+        assert!(rulebox.preview_toggle("retired", &texts).is_empty());
+        assert_eq!(rulebox.preview_toggle("live", &texts), vec![0]);
+    }
+
+    #[test]
+    fn test_preview_toggle_respects_schedules() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "expired", "label": "old", "active_until": "2024-01-01T00:00:00Z",
+                 "rule": {"or_patterns": [{"pattern": "sale"}]}},
+                {"uuid": "expired_off", "label": "old", "active": false, "active_until": "2024-01-01T00:00:00Z",
+                 "rule": {"or_patterns": [{"pattern": "sale"}]}},
+                {"uuid": "current_off", "label": "now", "active": false,
+                 "active_from": "2024-03-01T00:00:00Z", "active_until": "2024-04-01T00:00:00Z",
+                 "rule": {"or_patterns": [{"pattern": "sale"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+        .with_clock(Clock::fixed("2024-03-15T12:00:00Z".parse().unwrap()));
+        let texts: Vec<String> = ["sale", "other"].iter().map(|s| s.to_string()).collect();
+
+        // Outside its window a rule assigns nothing either way
+        assert!(rulebox.preview_toggle("expired", &texts).is_empty());
+        assert!(rulebox.preview_toggle("expired_off", &texts).is_empty());
+        assert_eq!(rulebox.preview_toggle("current_off", &texts), vec![0]);
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    const RULES: &str = r#"{
+        "definitions": {"EMAIL": "[\\w.+-]+@[\\w-]+\\.[\\w.]+", "PET": "cat|dog"},
+        "rules": [
+            {"uuid": "e", "label": "email", "rule": {"or_patterns": [{"pattern": "mail(to)?: {{EMAIL}}"}]}},
+            {"uuid": "p", "label": "pets", "rule": {"or_patterns": [{"pattern": "^{{PET}}$"}]}},
+            {"uuid": "l", "label": "literal", "rule": {"or_patterns": [{"pattern": "{{PET}}", "match_mode": "contains"}]}}
+        ]
+    }"#;
+
+    #[test]
+    fn test_references_are_expanded() {
+        let rulebox = RuleBox::from_json(RULES).unwrap();
+        assert_eq!(
+            rulebox.assign_labels("mailto: someone@example.com"),
+            vec!["email".to_string()]
+        );
+        assert!(rulebox.assign_labels("mailto: nobody").is_empty());
+    }
+
+    #[test]
+    fn test_definitions_stay_one_unit() {
+        // Pasted in as is, `^cat|dog$` would match any text ending in "dog"
+        let rulebox = RuleBox::from_json(RULES).unwrap();
+        assert_eq!(rulebox.assign_labels("dog"), vec!["pets".to_string()]);
+        assert!(rulebox.assign_labels("hotdog").is_empty());
+    }
+
+    #[test]
+    fn test_literal_patterns_are_not_expanded() {
+        let rulebox = RuleBox::from_json(RULES).unwrap();
+        assert_eq!(
+            rulebox.assign_labels("see {{PET}}"),
+            vec!["literal".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_unknown_definition_fails_to_load() {
+        let err = RuleBox::from_json(
+            r#"{"rules": [{"uuid": "x", "label": "x", "rule": {"or_patterns": [{"pattern": "{{PHONE}}"}]}}]}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Unknown definition: {{PHONE}}"));
+        let RuleBoxError::Compile(CompileError::InRule { source, .. }) = err else {
+            panic!("expected a compile error, got {:?}", err);
+        };
+        assert!(matches!(
+            *source,
+            CompileError::UnknownDefinition { ref name, .. } if name == "PHONE"
+        ));
+    }
+
+    #[test]
+    fn test_round_trip_keeps_definitions() {
+        let rulebox = RuleBox::from_json(RULES).unwrap();
+        let reloaded = RuleBox::from_json(&rulebox.to_json_string().unwrap()).unwrap();
+        assert_eq!(reloaded.definitions, rulebox.definitions);
+        assert_eq!(
+            reloaded.rules[0].rule.or_patterns[0].pattern,
+            "mail(to)?: {{EMAIL}}"
+        );
+    }
+
+    #[test]
+    fn test_merge_rejects_conflicting_definitions() {
+        let mut rulebox = RuleBox::from_json(RULES).unwrap();
+        let other = RuleBox::from_json(
+            r#"{"definitions": {"PET": "hamster"}, "rules": [{"uuid": "h", "label": "h", "rule": {"or_patterns": [{"pattern": "{{PET}}"}]}}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            rulebox.merge(other),
+            Err(RuleBoxError::MergeConflict(_))
+        ));
+    }
+}
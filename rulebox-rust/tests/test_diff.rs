@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    #[test]
+    fn test_diff_labels_reports_gains_and_losses() {
+        let old = RuleBox::from_json(
+            r#"[
+                {"uuid": "r1", "label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}},
+                {"uuid": "r2", "label": "farewell", "rule": {"or_patterns": [{"pattern": "bye"}]}}
+            ]"#,
+        )
+        .unwrap();
+        // r1 widened to "hi", r2 narrowed to "goodbye", a new rule added
+        let new = RuleBox::from_json(
+            r#"[
+                {"uuid": "r1", "label": "greeting", "rule": {"or_patterns": [{"pattern": "hello|hi"}]}},
+                {"uuid": "r2", "label": "farewell", "rule": {"or_patterns": [{"pattern": "goodbye"}]}},
+                {"uuid": "r3", "label": "thanks", "rule": {"or_patterns": [{"pattern": "thanks"}]}}
+            ]"#,
+        )
+        .unwrap();
+        let texts: Vec<String> = ["hello", "hi, thanks", "bye", "goodbye", "silence"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+
+        let report = old.diff_labels(&new, &texts);
+        assert_eq!(report.texts_compared, 5);
+        assert_eq!(
+            report.changes,
+            vec![
+                TextDiff {
+                    index: 1,
+                    text: "hi, thanks".to_string(),
+                    gained: vec!["greeting".to_string(), "thanks".to_string()],
+                    lost: vec![],
+                },
+                TextDiff {
+                    index: 2,
+                    text: "bye".to_string(),
+                    gained: vec![],
+                    lost: vec!["farewell".to_string()],
+                },
+            ]
+        );
+        let by_label = report.by_label();
+        assert_eq!(by_label["greeting"], LabelChange { gained: 1, lost: 0 });
+        assert_eq!(by_label["farewell"], LabelChange { gained: 0, lost: 1 });
+        assert_eq!(by_label.len(), 3);
+
+        assert!(old.diff_labels(&old, &texts).is_empty());
+    }
+}
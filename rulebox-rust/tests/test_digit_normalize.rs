@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::normalize::NormalizedText;
+    use rulebox_rust::*;
+
+    #[test]
+    fn test_digit_normalize_matches_varied_separators() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {
+                    "label": "known_number",
+                    "rule": {
+                        "or_patterns": [{"pattern": "5551234"}],
+                        "digit_normalize": true
+                    }
+                },
+                {
+                    "label": "raw_number",
+                    "rule": {"or_patterns": [{"pattern": "5551234"}]}
+                }
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let texts = vec![
+            "Call 555-12 34 today".to_string(),
+            "Call 555.1234 today".to_string(),
+            "Call 5551234 today".to_string(),
+            "Call 555 then 1234".to_string(),
+        ];
+        let results = rulebox.assign_labels_vector(&texts);
+
+        assert_eq!(results[0], vec!["known_number".to_string()]);
+        assert_eq!(results[1], vec!["known_number".to_string()]);
+        assert!(results[2].contains(&"known_number".to_string()));
+        assert!(results[2].contains(&"raw_number".to_string()));
+        // Letters between the digit runs keep them apart
+        assert!(results[3].is_empty());
+    }
+
+    #[test]
+    fn test_normalized_span_maps_back_to_original() {
+        let original = "ref: 555-12 34!";
+        let normalized = NormalizedText::digits(original);
+        assert_eq!(normalized.text, "ref: 5551234!");
+
+        let start = normalized.text.find("5551234").unwrap();
+        let (s, e) = normalized.original_span(start, start + 7);
+        assert_eq!(&original[s..e], "555-12 34");
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    fn checks(patterns: &[PatternCheck]) -> Vec<(&str, bool)> {
+        patterns
+            .iter()
+            .map(|p| (p.pattern.as_str(), p.matched))
+            .collect()
+    }
+
+    #[test]
+    fn test_explain_reports_each_pattern() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "pets", "label": "pets", "rule": {
+                    "or_patterns": [{"pattern": "cat"}, {"pattern": "dog"}],
+                    "not_patterns": [{"pattern": "hot dog"}]
+                }},
+                {"uuid": "both", "label": "both", "rule": {
+                    "and_patterns": [{"pattern": "cat"}, {"pattern": "mouse"}]
+                }},
+                {"uuid": "off", "label": "off", "active": false, "rule": {
+                    "or_patterns": [{"pattern": "cat"}]
+                }}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let explanations = rulebox.explain("a cat and a hot dog");
+        let uuids: Vec<&str> = explanations.iter().map(|e| e.uuid.as_str()).collect();
+        assert_eq!(uuids, vec!["pets", "both"]);
+
+        let pets = &explanations[0];
+        assert_eq!(pets.outcome, RuleOutcome::Vetoed);
+        assert_eq!(
+            checks(&pets.or_patterns),
+            vec![("cat", true), ("dog", true)]
+        );
+        assert_eq!(checks(&pets.not_patterns), vec![("hot dog", true)]);
+        assert_eq!(pets.expr, None);
+
+        let both = &explanations[1];
+        assert_eq!(both.label, "both");
+        assert_eq!(both.outcome, RuleOutcome::NoMatch);
+        assert_eq!(
+            checks(&both.and_patterns),
+            vec![("cat", true), ("mouse", false)]
+        );
+    }
+
+    #[test]
+    fn test_explain_uses_normalized_text_and_expr() {
+        let rulebox = RuleBox::from_json(
+            r#"[{"uuid": "phone", "label": "phone", "rule": {
+                "digit_normalize": true,
+                "or_patterns": [{"pattern": "5551234"}],
+                "expr": {"not": {"pattern": {"pattern": "fax"}}}
+            }}]"#,
+        )
+        .unwrap();
+        let explanation = &rulebox.explain("call 555-12 34")[0];
+        assert_eq!(explanation.outcome, RuleOutcome::Matched);
+        assert!(explanation.or_patterns[0].matched);
+        assert_eq!(explanation.expr, Some(true));
+    }
+}
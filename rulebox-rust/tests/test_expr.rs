@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    // (A AND B) OR (C AND NOT D)
+    fn expr_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[{"label": "hit", "rule": {"expr": {"any": [
+                {"all": [{"pattern": {"pattern": "\\balpha\\b"}}, {"pattern": {"pattern": "\\bbeta\\b"}}]},
+                {"all": [
+                    {"pattern": {"pattern": "\\bgamma\\b", "flags": ["i"]}},
+                    {"not": {"pattern": {"pattern": "\\bdelta\\b"}}}
+                ]}
+            ]}}}]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_nested_expression() {
+        let rulebox = expr_box();
+        let hit = vec!["hit".to_string()];
+        assert_eq!(rulebox.assign_labels("alpha and beta"), hit);
+        assert_eq!(rulebox.assign_labels("GAMMA ray"), hit);
+        assert_eq!(rulebox.assign_labels("alpha beta gamma delta"), hit);
+        assert!(rulebox.assign_labels("alpha only").is_empty());
+        assert!(rulebox.assign_labels("gamma and delta").is_empty());
+    }
+
+    #[test]
+    fn test_expr_combines_with_flat_patterns() {
+        let rulebox = RuleBox::from_json(
+            r#"[{"label": "hit", "rule": {
+                "or_patterns": [{"pattern": "cat"}],
+                "not_patterns": [{"pattern": "dog"}],
+                "expr": {"not": {"pattern": {"pattern": "bird"}}}
+            }}]"#,
+        )
+        .unwrap();
+        assert!(rulebox.assign_labels("cat").contains(&"hit".to_string()));
+        assert!(rulebox.assign_labels("cat and bird").is_empty());
+        assert!(rulebox.assign_labels("cat and dog").is_empty());
+        assert!(rulebox.assign_labels("bird").is_empty());
+    }
+
+    #[test]
+    fn test_expr_roundtrips_and_compiles_every_pattern() {
+        let rulebox = expr_box();
+        assert!(rulebox.rules[0].rule.is_compiled());
+        let json = serde_json::to_string(&rulebox).unwrap();
+        assert!(json.contains(r#""not":{"pattern":{"pattern""#));
+        let reloaded = RuleBox::from_json(&json).unwrap();
+        assert_eq!(
+            reloaded.assign_labels("gamma"),
+            rulebox.assign_labels("gamma")
+        );
+
+        let err = RuleBox::from_json(
+            r#"[{"label": "x", "rule": {"expr": {"all": [{"pattern": {"pattern": "(bad"}}]}}}]"#,
+        );
+        assert!(matches!(err, Err(RuleBoxError::Compile(_))));
+    }
+
+    #[test]
+    fn test_expr_patterns_give_spans() {
+        let rulebox = expr_box();
+        let matches = rulebox.all_matches("gamma ray");
+        let patterns: Vec<&str> = matches.iter().map(|m| m.pattern.as_str()).collect();
+        assert_eq!(patterns, vec!["\\bgamma\\b"]);
+    }
+}
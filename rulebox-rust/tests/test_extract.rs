@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use serde_json::json;
+
+    fn invoice_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {
+                    "uuid": "inv",
+                    "label": "invoice",
+                    "rule": {"or_patterns": [{"pattern": "invoice (?P<number>\\w+) for (?P<amount>\\d+) due (?P<due>[\\d-]+)(?: ref (?P<ref>\\w+))?"}]},
+                    "extract_schema": {"number": "string", "amount": "int", "due": "date", "ref": "string"}
+                },
+                {"label": "plain", "rule": {"or_patterns": [{"pattern": "invoice"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_extract_typed_records() {
+        let records = invoice_box().extract_typed(
+            "invoice A1 for 250 due 2024-02-29 ref X9; invoice B2 for 10 due 2024-03-01",
+        );
+        assert_eq!(
+            records,
+            vec![
+                json!({"number": "A1", "amount": 250, "due": "2024-02-29", "ref": "X9"}),
+                json!({"number": "B2", "amount": 10, "due": "2024-03-01"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_failures_skip_or_fail() {
+        let rulebox = invoice_box();
+        let text = "invoice A1 for 250 due 2023-02-29; invoice B2 for 10 due 2024-03-01";
+
+        let records = rulebox.extract_typed(text);
+        assert_eq!(
+            records,
+            vec![json!({"number": "B2", "amount": 10, "due": "2024-03-01"})]
+        );
+
+        let err = rulebox
+            .extract_typed_with(text, OnParseError::Fail)
+            .expect_err("Invalid date should fail");
+        assert_eq!(err.uuid, "inv");
+        assert_eq!(err.field, "due");
+        assert_eq!(err.value, "2023-02-29");
+        assert_eq!(err.expected, FieldType::Date);
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use rulebox_rust::*;
+
+    #[cfg(feature = "yaml")]
+    const YAML: &str = r#"
+# Analyst-maintained rules
+default_flags: [i]
+rules:
+  - label: greeting
+    uuid: greet-1
+    rule:
+      or_patterns:
+        - pattern: '\bhello\b'
+        - pattern: '\bhi\b'
+  - label: farewell  # no uuid, one is generated
+    rule:
+      and_patterns:
+        - pattern: bye
+"#;
+
+    #[cfg(feature = "toml")]
+    const TOML: &str = r#"
+# Analyst-maintained rules
+default_flags = ["i"]
+
+[[rules]]
+label = "greeting"
+uuid = "greet-1"
+rule = { or_patterns = [{ pattern = '\bhello\b' }, { pattern = '\bhi\b' }] }
+
+[[rules]]
+label = "farewell"
+rule = { and_patterns = [{ pattern = "bye" }] }
+"#;
+
+    #[allow(dead_code)]
+    const JSON: &str = r#"{"default_flags": ["i"], "rules": [
+        {"label": "greeting", "uuid": "greet-1", "rule": {"or_patterns": [{"pattern": "\\bhello\\b"}, {"pattern": "\\bhi\\b"}]}},
+        {"label": "farewell", "rule": {"and_patterns": [{"pattern": "bye"}]}}
+    ]}"#;
+
+    #[allow(dead_code)]
+    fn assert_same_as_json(rulebox: &RuleBox) {
+        let json = RuleBox::from_json(JSON).unwrap();
+        let texts: Vec<String> = ["HELLO there", "Hi, bye", "nothing"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(rulebox.check_many(&texts), json.check_many(&texts));
+        assert_eq!(rulebox.fingerprint(), json.fingerprint());
+        assert_eq!(rulebox.rules[0].uuid, "greet-1");
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_loads_like_json() {
+        assert_same_as_json(&RuleBox::from_yaml_str(YAML).expect("Failed to load YAML"));
+        assert!(matches!(
+            RuleBox::from_yaml_str("rules: [unclosed"),
+            Err(RuleBoxError::Yaml(_))
+        ));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_loads_like_json() {
+        assert_same_as_json(&RuleBox::from_toml_str(TOML).expect("Failed to load TOML"));
+        assert!(matches!(
+            RuleBox::from_toml_str("rules = ["),
+            Err(RuleBoxError::Toml(_))
+        ));
+    }
+
+    #[cfg(all(feature = "fs", feature = "yaml", feature = "toml"))]
+    #[test]
+    fn test_from_path_detects_format_by_extension() {
+        let dir = std::env::temp_dir().join(format!("rulebox-formats-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, text) in [("a.yml", YAML), ("b.toml", TOML), ("c.json", JSON)] {
+            std::fs::write(dir.join(name), text).unwrap();
+        }
+
+        for name in ["a.yml", "b.toml", "c.json"] {
+            let path = dir.join(name);
+            assert_same_as_json(&RuleBox::from_path(path.to_str().unwrap()).unwrap());
+        }
+        let results = validate_dir(dir.to_str().unwrap());
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+        assert!(RuleBox::canonicalize_file(dir.join("a.yml").to_str().unwrap()).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
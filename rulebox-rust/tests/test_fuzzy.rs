@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    const RULES: &str = r#"[
+        {
+            "label": "viagra",
+            "rule": {
+                "or_patterns": [
+                    {"pattern": "viagra", "flags": ["i"], "match_type": "fuzzy", "max_distance": 1}
+                ]
+            }
+        }
+    ]"#;
+
+    #[cfg(feature = "fuzzy")]
+    #[test]
+    fn test_fuzzy_pattern_allows_bounded_edits() {
+        let rulebox = RuleBox::from_json(RULES).expect("Failed to load rules");
+        let texts = vec![
+            "Cheap VIAGRA here".to_string(),
+            "Cheap viagrra here".to_string(),
+            "Cheap v1agra here".to_string(),
+            "Cheap vagr here".to_string(),
+            "Nothing to see".to_string(),
+        ];
+        let results = rulebox.assign_labels_vector(&texts);
+        assert_eq!(results[0], vec!["viagra".to_string()]);
+        assert_eq!(results[1], vec!["viagra".to_string()]);
+        assert_eq!(results[2], vec!["viagra".to_string()]);
+        assert!(results[3].is_empty());
+        assert!(results[4].is_empty());
+    }
+
+    #[cfg(feature = "fuzzy")]
+    #[test]
+    fn test_fuzzy_matcher_reports_span() {
+        let matcher = FuzzyMatcher::new("refund", 1, false);
+        let text = "please refnd me";
+        let (start, end) = matcher.find(text).expect("Expected a fuzzy match");
+        assert_eq!(&text[start..end], "refnd");
+        assert!(FuzzyMatcher::new("refund", 0, false).find(text).is_none());
+    }
+
+    #[cfg(feature = "fuzzy")]
+    #[test]
+    fn test_fuzzy_distance_must_leave_something_to_match() {
+        let err = RuleBox::from_json(&RULES.replace("\"max_distance\": 1", "\"max_distance\": 6"))
+            .expect_err("max_distance covering the whole pattern should be rejected");
+        assert!(err.to_string().contains("would match anything"));
+    }
+
+    #[cfg(not(feature = "fuzzy"))]
+    #[test]
+    fn test_fuzzy_pattern_requires_feature() {
+        let err = RuleBox::from_json(RULES).expect_err("Fuzzy patterns need the feature");
+        assert!(err.to_string().contains("`fuzzy` feature"));
+    }
+}
@@ -0,0 +1,46 @@
+// Golden label sets shared with rulebox-python/tests/test_golden.py, so the
+// core and the bindings are held to the same expected outputs.
+
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use serde_json::Value;
+
+    fn load_golden() -> (RuleBox, Vec<(String, Vec<String>)>) {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden.json");
+        let fixture: Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let rulebox =
+            RuleBox::from_json(&fixture["rules"].to_string()).expect("Failed to load rules");
+        let cases = fixture["cases"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|case| {
+                let text = case["text"].as_str().unwrap().to_string();
+                let labels = serde_json::from_value(case["labels"].clone()).unwrap();
+                (text, labels)
+            })
+            .collect();
+        (rulebox, cases)
+    }
+
+    #[test]
+    fn test_golden_assign_labels() {
+        let (rulebox, cases) = load_golden();
+        for (text, expected) in cases {
+            let mut labels = rulebox.assign_labels(&text);
+            labels.sort();
+            let mut expected = expected.clone();
+            expected.sort();
+            assert_eq!(labels, expected, "labels for {:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_golden_assign_labels_vector() {
+        let (rulebox, cases) = load_golden();
+        let texts: Vec<String> = cases.iter().map(|(text, _)| text.clone()).collect();
+        let expected: Vec<Vec<String>> = cases.into_iter().map(|(_, labels)| labels).collect();
+        assert_eq!(rulebox.assign_labels_vector(&texts), expected);
+    }
+}
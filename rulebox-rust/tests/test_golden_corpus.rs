@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use serde_json::Value;
+
+    fn spam_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"label": "spam", "rule": {"or_patterns": [{"pattern": "(?i)prize"}]}},
+                {"label": "ham", "rule": {"or_patterns": [{"pattern": "(?i)meeting"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_scores_and_mismatches() {
+        let corpus = concat!(
+            "{\"text\": \"You won a prize\", \"labels\": [\"spam\"]}\n",
+            "{\"text\": \"Claim your reward\", \"labels\": [\"spam\"], \"id\": 7}\n",
+            "\n",
+            "{\"text\": \"Prize meeting at noon\", \"labels\": [\"ham\"]}\n",
+            "{\"text\": \"Lunch?\", \"labels\": []}\n",
+        );
+        let report = spam_box().test_corpus(corpus.as_bytes()).unwrap();
+        assert_eq!(report.texts, 4);
+
+        let spam = &report.labels[1];
+        assert_eq!(spam.label, "spam");
+        assert_eq!(
+            (
+                spam.true_positives,
+                spam.false_positives,
+                spam.false_negatives,
+                spam.true_negatives
+            ),
+            (1, 1, 1, 1)
+        );
+        assert_eq!(spam.precision(), 0.5);
+        assert_eq!(spam.recall(), 0.5);
+
+        let ham = &report.labels[0];
+        assert_eq!(ham.label, "ham");
+        assert_eq!((ham.true_positives, ham.true_negatives), (1, 3));
+        assert_eq!(ham.precision(), 1.0);
+
+        assert_eq!(report.mismatches.len(), 2);
+        assert_eq!(report.mismatches[0].line, 2);
+        assert_eq!(report.mismatches[0].missing, vec!["spam"]);
+        assert_eq!(report.mismatches[1].line, 4);
+        assert_eq!(report.mismatches[1].unexpected, vec!["spam"]);
+
+        let failing: Vec<&str> = report
+            .below(0.9, 0.9)
+            .iter()
+            .map(|s| s.label.as_str())
+            .collect();
+        assert_eq!(failing, vec!["spam"]);
+        assert!(report.below(0.5, 0.5).is_empty());
+    }
+
+    #[test]
+    fn test_confusion_and_bad_lines() {
+        let corpus = "{\"text\": \"prize\", \"labels\": [\"ham\"]}\n";
+        let report = spam_box().test_corpus(corpus.as_bytes()).unwrap();
+        assert_eq!(report.labels[0].confused_with["spam"], 1);
+
+        let err = spam_box()
+            .test_corpus("{\"text\": \"prize\"}\n".as_bytes())
+            .unwrap_err();
+        assert!(err.to_string().starts_with("line 1:"));
+    }
+
+    #[test]
+    fn test_shared_golden_fixture_passes() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden.json");
+        let fixture: Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let rulebox = RuleBox::from_json(&fixture["rules"].to_string()).unwrap();
+        let corpus: String = fixture["cases"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|case| format!("{}\n", case))
+            .collect();
+        let report = rulebox.test_corpus(corpus.as_bytes()).unwrap();
+        assert!(report.mismatches.is_empty());
+        assert!(report.below(1.0, 1.0).is_empty());
+    }
+}
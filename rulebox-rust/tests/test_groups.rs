@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    const RULES: &str = r#"[
+        {"label": "ham", "group": "verdict", "rule": {"or_patterns": [{"pattern": "meeting"}]}},
+        {"label": "spam", "group": "verdict", "priority": 5, "rule": {"or_patterns": [{"pattern": "free"}]}},
+        {"label": "promo", "group": "verdict", "priority": 5, "rule": {"or_patterns": [{"pattern": "offer"}]}},
+        {"label": "urgent", "rule": {"or_patterns": [{"pattern": "now"}]}}
+    ]"#;
+
+    fn texts() -> Vec<String> {
+        [
+            "meeting now",
+            "free meeting",
+            "free offer",
+            "offer for the meeting",
+            "nothing",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+
+    fn expected() -> Vec<Vec<String>> {
+        vec![
+            vec!["ham".to_string(), "urgent".to_string()],
+            vec!["spam".to_string()],
+            // Equal priority: the earlier rule wins
+            vec!["spam".to_string()],
+            vec!["promo".to_string()],
+            vec![],
+        ]
+    }
+
+    #[test]
+    fn test_group_picks_one_label() {
+        let rulebox = RuleBox::from_json(RULES).expect("Failed to load rules");
+        let mut batch = rulebox.assign_labels_vector(&texts());
+        for labels in batch.iter_mut() {
+            labels.sort();
+        }
+        assert_eq!(batch, expected());
+
+        let checked: Vec<Vec<String>> = rulebox
+            .check_many(&texts())
+            .into_iter()
+            .map(|labels| {
+                let mut labels: Vec<String> = labels.into_iter().collect();
+                labels.sort();
+                labels
+            })
+            .collect();
+        assert_eq!(checked, expected());
+    }
+
+    #[test]
+    fn test_group_survives_round_trip() {
+        let rulebox = RuleBox::from_json(RULES).unwrap();
+        assert_eq!(rulebox.rules[0].group.as_deref(), Some("verdict"));
+        assert_eq!(rulebox.rules[3].group, None);
+        let reloaded = RuleBox::from_json(&rulebox.to_json_string().unwrap()).unwrap();
+        assert_eq!(reloaded.fingerprint(), rulebox.fingerprint());
+        assert_eq!(reloaded.assign_labels("free offer"), vec!["spam"]);
+    }
+}
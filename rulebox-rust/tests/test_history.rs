@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::chrono::{DateTime, Utc};
+    use rulebox_rust::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn rule(uuid: &str, pattern: &str) -> LabelRule {
+        serde_json::from_str(&format!(
+            r#"{{"uuid": "{}", "label": "greeting", "rule": {{"or_patterns": [{{"pattern": "{}"}}]}}}}"#,
+            uuid, pattern
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_update_rule_bumps_version_and_keeps_history() {
+        let mut rulebox = RuleBox::from_json("[]")
+            .unwrap()
+            .with_clock(Clock::fixed(at("2024-05-01T10:20:30.750Z")));
+        rulebox.add_rule(rule("g", "hello")).unwrap();
+        assert_eq!(rulebox.rules[0].version, 1);
+        assert!(rulebox.changelog().is_empty());
+
+        let old = rulebox.update_rule("g", rule("g", "hi")).unwrap().unwrap();
+        assert!(old.history.is_empty());
+        rulebox.update_rule("g", rule("g", "hey")).unwrap();
+
+        let current = &rulebox.rules[0];
+        assert_eq!(current.version, 3);
+        let versions: Vec<u32> = current.history.iter().map(|r| r.version).collect();
+        assert_eq!(versions, vec![1, 2]);
+        assert_eq!(
+            current.history[0].rule["rule"]["or_patterns"][0]["pattern"],
+            "hello"
+        );
+        assert!(current.history[0].rule.get("history").is_none());
+        // Timed by the box's clock, to the second
+        assert_eq!(current.history[1].replaced, at("2024-05-01T10:20:30Z"));
+        let saved = rulebox.to_json_string().unwrap();
+        assert!(saved.contains(r#""replaced": "2024-05-01T10:20:30Z""#));
+
+        let changelog = rulebox.changelog();
+        assert_eq!(changelog.len(), 2);
+        assert_eq!((changelog[0].version, changelog[1].version), (2, 3));
+        assert_eq!(
+            changelog[1].previous["rule"]["or_patterns"][0]["pattern"],
+            "hi"
+        );
+    }
+
+    #[test]
+    fn test_history_round_trip_and_revision_at() {
+        let json = r#"[{"uuid": "g", "label": "greeting", "version": 3,
+            "rule": {"or_patterns": [{"pattern": "hey"}]},
+            "history": [
+                {"version": 1, "replaced": "2024-01-10T09:00:00Z",
+                 "rule": {"label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}}},
+                {"version": 2, "replaced": "2024-03-01T12:00:00Z",
+                 "rule": {"label": "greeting", "rule": {"or_patterns": [{"pattern": "hi"}]}}}
+            ]}]"#;
+        let rulebox = RuleBox::from_json(json).unwrap();
+        let rule = &rulebox.rules[0];
+        assert_eq!(
+            rule.revision_at(at("2023-12-31T00:00:00Z"))
+                .unwrap()
+                .version,
+            1
+        );
+        assert_eq!(
+            rule.revision_at(at("2024-02-01T00:00:00Z"))
+                .unwrap()
+                .version,
+            2
+        );
+        // Any RFC 3339 offset works, not just UTC
+        assert_eq!(
+            rule.revision_at(at("2024-03-01T13:30:00+02:00"))
+                .unwrap()
+                .version,
+            2
+        );
+        assert!(rule.revision_at(at("2024-06-01T00:00:00Z")).is_none());
+
+        let reloaded = RuleBox::from_json(&rulebox.to_json_string().unwrap()).unwrap();
+        assert_eq!(reloaded.rules[0].version, 3);
+        assert_eq!(reloaded.rules[0].history, rule.history);
+        assert_eq!(reloaded.changelog()[0].changed, at("2024-01-10T09:00:00Z"));
+
+        // A rule that was never updated doesn't write either field
+        let fresh =
+            RuleBox::from_json(r#"[{"label": "x", "rule": {"or_patterns": [{"pattern": "x"}]}}]"#)
+                .unwrap()
+                .to_json_string()
+                .unwrap();
+        assert!(!fresh.contains("version") && !fresh.contains("history"));
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    fn hit(label: &str, rules: &[&str]) -> LabelHit {
+        LabelHit {
+            label: label.to_string(),
+            rules: rules.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_hits_name_the_rules_behind_each_label() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "implies": {"email": ["contact"]},
+                "score_thresholds": {"spam": 1.5},
+                "count_rules": [{"if_labels_count_at_least": 2, "then": "busy"}],
+                "rules": [
+                    {"uuid": "e1", "label": "email", "rule": {"or_patterns": [{"pattern": "@"}]}},
+                    {"uuid": "e2", "label": "email", "rule": {"or_patterns": [{"pattern": "mailto"}]}},
+                    {"uuid": "s1", "label": "spam", "rule": {"or_patterns": [{"pattern": "free"}]}},
+                    {"uuid": "s2", "label": "spam", "rule": {"or_patterns": [{"pattern": "win"}]}},
+                    {"uuid": "g1", "label": "low", "group": "tone", "rule": {"or_patterns": [{"pattern": "free"}]}},
+                    {"uuid": "g2", "label": "high", "group": "tone", "priority": 1, "rule": {"or_patterns": [{"pattern": "win"}]}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rulebox.check_hits("mailto:a@b free win"),
+            vec![
+                hit("email", &["e1", "e2"]),
+                hit("spam", &["s1", "s2"]),
+                hit("high", &["g2"]),
+                hit("contact", &["e1", "e2"]),
+                hit("busy", &[]),
+            ]
+        );
+        // One spam rule isn't enough for the threshold, so it isn't credited
+        assert_eq!(rulebox.check_hits("free"), vec![hit("low", &["g1"])]);
+    }
+
+    #[test]
+    fn test_check_many_hits_follows_input_order() {
+        let rulebox = RuleBox::from_json(
+            r#"[{"uuid": "r1", "label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}}]"#,
+        )
+        .unwrap();
+        let texts = vec!["nothing".to_string(), "hello".to_string()];
+        assert_eq!(
+            rulebox.check_many_hits(&texts),
+            vec![vec![], vec![hit("greeting", &["r1"])]]
+        );
+    }
+
+    #[test]
+    fn test_rule_ids_tell_apart_rules_sharing_a_label() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "implies": {"email": ["contact"]},
+                "rules": [
+                    {"uuid": "e1", "label": "email", "rule": {"or_patterns": [{"pattern": "@"}]}},
+                    {"uuid": "p1", "label": "phone", "rule": {"or_patterns": [{"pattern": "tel:"}]}},
+                    {"uuid": "e2", "label": "email", "rule": {"or_patterns": [{"pattern": "mailto"}]}},
+                    {"uuid": "off", "label": "email", "active": false, "rule": {"or_patterns": [{"pattern": "@"}]}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(rulebox.assign_rule_ids("mailto:a@b"), vec!["e1", "e2"]);
+        assert_eq!(
+            rulebox
+                .assign_rule_ids_vector(&["tel: 123, mailto:x".to_string(), "nothing".to_string()]),
+            vec![vec!["p1".to_string(), "e2".to_string()], vec![]]
+        );
+    }
+}
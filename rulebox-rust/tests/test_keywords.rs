@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::collections::HashMap;
+
+    fn keyword_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"label": "fruit", "rule": {"keyword_patterns": {
+                    "keywords": ["apple", "banana", "cherry"], "ignore_case": true
+                }}},
+                {"label": "tool", "rule": {
+                    "keyword_patterns": {"keywords": ["saw", "hammer"], "whole_words": true},
+                    "or_patterns": [{"pattern": "screw ?driver"}],
+                    "not_patterns": [{"pattern": "toy"}]
+                }}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_keywords_match_any_literal() {
+        let rulebox = keyword_box();
+        assert_eq!(rulebox.assign_labels("BANANA bread"), vec!["fruit"]);
+        assert_eq!(rulebox.assign_labels("pineapple"), vec!["fruit"]);
+        assert!(rulebox.assign_labels("grape").is_empty());
+    }
+
+    #[test]
+    fn test_whole_words_and_or_patterns() {
+        let rulebox = keyword_box();
+        assert_eq!(rulebox.assign_labels("I saw it"), vec!["tool"]);
+        assert!(rulebox.assign_labels("a sawmill").is_empty());
+        assert_eq!(rulebox.assign_labels("hammers, saw."), vec!["tool"]);
+        assert_eq!(rulebox.assign_labels("a screwdriver"), vec!["tool"]);
+        assert!(rulebox.assign_labels("toy hammer").is_empty());
+        assert_eq!(rulebox.assign_labels("émsaw saw"), vec!["tool"]);
+        assert!(rulebox.assign_labels("émsaw").is_empty());
+    }
+
+    #[test]
+    fn test_keywords_round_trip_and_validate() {
+        let rulebox = keyword_box();
+        let json = rulebox.to_json_string().unwrap();
+        assert!(json.contains("\"ignore_case\": true"));
+        assert!(!json.contains("\"compiled\""));
+        let reloaded = RuleBox::from_json(&json).unwrap();
+        assert!(reloaded.rules[0].rule.is_compiled());
+        assert_eq!(reloaded.assign_labels("cherry"), vec!["fruit"]);
+
+        let empty = RuleBox::from_json(
+            r#"[{"label": "x", "rule": {"keyword_patterns": {"keywords": ["ok", ""]}}}]"#,
+        );
+        assert!(matches!(
+            empty,
+            Err(RuleBoxError::Compile(e)) if matches!(e.root(), CompileError::InvalidKeywords { .. })
+        ));
+        let mixed = RuleBox::from_json(
+            r#"[{"label": "x", "rule": {"and_patterns": [{"pattern": "a"}], "keyword_patterns": {"keywords": ["b"]}}}]"#,
+        );
+        assert!(mixed.is_err());
+    }
+
+    #[test]
+    fn test_keywords_in_records_and_explain() {
+        let rulebox = keyword_box();
+        let record = HashMap::from([
+            ("title".to_string(), "Shopping".to_string()),
+            ("body".to_string(), "an Apple a day".to_string()),
+        ]);
+        assert!(rulebox.check_record(&record).labels().contains("fruit"));
+        let explained = rulebox.explain("cherry pie");
+        assert_eq!(explained[0].keywords, Some(true));
+        assert_eq!(explained[1].keywords, Some(false));
+    }
+
+    #[test]
+    fn test_keyword_matches_have_spans() {
+        let rulebox = RuleBox::from_json(
+            r#"[{"uuid": "kw", "label": "refund", "rule": {"keyword_patterns": {"keywords": ["refund"]}}}]"#,
+        )
+        .unwrap();
+        let text = "refund please";
+        let detailed = rulebox.check_detailed(text);
+        let span = &detailed["refund"].spans[0];
+        assert_eq!((span.start, span.end), (0, 6));
+        assert_eq!(
+            (span.uuid.as_str(), span.pattern.as_str()),
+            ("kw", "refund")
+        );
+        assert_eq!(span.text, "refund");
+
+        let segments = rulebox.segment(text);
+        assert_eq!((segments[0].start, segments[0].end), (0, 6));
+        assert!(segments[0].labels.contains("refund"));
+
+        // The keyword as written, at the case-folded or whole-word match
+        let rulebox = keyword_box();
+        let matches = rulebox.all_matches("BANANA bread");
+        assert_eq!(matches[0].pattern, "banana");
+        assert_eq!(matches[0].text("BANANA bread"), Some("BANANA"));
+        let matches = rulebox.all_matches("hammers, saw.");
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].start, matches[0].end), (9, 12));
+    }
+}
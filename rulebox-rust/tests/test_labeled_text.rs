@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::collections::HashSet;
+
+    fn animals() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"uuid": "1", "label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"uuid": "2", "label": "dog", "rule": {"or_patterns": [{"pattern": "dog"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    fn tone() -> RuleBox {
+        RuleBox::from_json(
+            r#"{
+                "implies": {"angry": ["negative"]},
+                "rules": [
+                    {"uuid": "a", "label": "angry", "rule": {"or_patterns": [{"pattern": "(?i)grr"}]}},
+                    {"uuid": "s", "label": "cat", "kind": "suppress",
+                     "rule": {"or_patterns": [{"pattern": "grr"}]}}
+                ]
+            }"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_accessors() {
+        let labeled = animals().check("a cat");
+        assert_eq!(labeled.content(), "a cat");
+        assert!(labeled.has_label("cat"));
+        assert!(!labeled.has_label("dog"));
+
+        let (content, labels) = labeled.into_parts();
+        assert_eq!(content, "a cat");
+        assert_eq!(labels, HashSet::from(["cat".to_string()]));
+    }
+
+    #[test]
+    fn test_chaining_boxes() {
+        let mut labeled = LabeledText::new("Grr, a cat".to_string());
+        animals().check_into(&mut labeled);
+        tone().check_into(&mut labeled);
+
+        // The second box's suppressor doesn't remove the first box's label
+        let labels: HashSet<&str> = labeled.labels().iter().map(String::as_str).collect();
+        assert_eq!(labels, HashSet::from(["cat", "angry", "negative"]));
+    }
+
+    #[test]
+    fn test_pre_seeded_labels_are_kept() {
+        let mut labeled = LabeledText::with_labels("a dog".to_string(), ["reviewed"]);
+        assert!(labeled.has_label("reviewed"));
+
+        animals().check_into(&mut labeled);
+        let labels: HashSet<&str> = labeled.labels().iter().map(String::as_str).collect();
+        assert_eq!(labels, HashSet::from(["reviewed", "dog"]));
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    fn kinds(rulebox: &RuleBox, uuid: &str) -> Vec<LintKind> {
+        rulebox
+            .lint()
+            .into_iter()
+            .filter(|w| w.uuid == uuid)
+            .map(|w| w.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_finds_suspicious_rules() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "empty", "label": "all", "rule": {}},
+                {"uuid": "blank", "label": "all", "rule": {"or_patterns": [{"pattern": ""}]}},
+                {"uuid": "dup", "label": "pet", "rule": {"or_patterns": [
+                    {"pattern": "dog"}, {"pattern": "fish"}, {"pattern": "dog"}
+                ]}},
+                {"uuid": "copy", "label": "pet", "rule": {"or_patterns": [
+                    {"pattern": "dog"}, {"pattern": "fish"}, {"pattern": "dog"}
+                ]}},
+                {"uuid": "flags", "label": "num", "rule": {"or_patterns": [
+                    {"pattern": "\\d{3}", "flags": ["i", "m"]},
+                    {"pattern": "^id$", "flags": ["i", "m"]}
+                ]}},
+                {"uuid": "or", "label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}, {"pattern": "cats"}]}},
+                {"uuid": "and", "label": "cat", "rule": {"and_patterns": [{"pattern": "cat"}, {"pattern": "cats"}]}},
+                {"uuid": "clean", "label": "cat", "rule": {"or_patterns": [
+                    {"pattern": "cat", "flags": ["i"]}, {"pattern": "kitten"}
+                ]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        assert_eq!(kinds(&rulebox, "empty"), vec![LintKind::MatchesEverything]);
+        assert_eq!(kinds(&rulebox, "blank"), vec![LintKind::MatchesEverything]);
+        assert_eq!(kinds(&rulebox, "dup"), vec![LintKind::DuplicatePattern]);
+        assert_eq!(
+            kinds(&rulebox, "copy"),
+            vec![LintKind::DuplicatePattern, LintKind::DuplicateRule]
+        );
+        assert_eq!(
+            kinds(&rulebox, "flags"),
+            vec![LintKind::UnusedFlag, LintKind::UnusedFlag]
+        );
+        assert!(kinds(&rulebox, "clean").is_empty());
+
+        let messages: Vec<String> = rulebox.lint().iter().map(|w| w.to_string()).collect();
+        assert!(messages
+            .contains(&"rule copy: has the same label and patterns as rule dup".to_string()));
+        assert!(messages.contains(&"rule flags: flag i has no effect on \"\\\\d{3}\"".to_string()));
+        assert!(messages.contains(&"rule flags: flag m has no effect on \"\\\\d{3}\"".to_string()));
+        assert!(messages
+            .contains(&"rule or: \"cats\" in or_patterns is redundant given \"cat\"".to_string()));
+        assert!(messages.contains(
+            &"rule and: \"cat\" in and_patterns is redundant given \"cats\"".to_string()
+        ));
+    }
+
+    #[test]
+    fn test_lint_ignores_box_default_flags() {
+        let rulebox = RuleBox::from_json(
+            r#"{"default_flags": ["i"], "rules": [
+                {"uuid": "r1", "label": "num", "rule": {"or_patterns": [{"pattern": "\\d+"}]}}
+            ]}"#,
+        )
+        .expect("Failed to load rules");
+        assert!(rulebox.lint().is_empty());
+    }
+}
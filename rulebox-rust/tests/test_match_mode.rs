@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    fn matches(pattern: &str, mode: &str, text: &str) -> bool {
+        let rulebox = RuleBox::from_json(&format!(
+            r#"[{{"label": "hit", "rule": {{"or_patterns": [{{"pattern": {}, "match_mode": "{}"}}]}}}}]"#,
+            serde_json::to_string(pattern).unwrap(),
+            mode
+        ))
+        .unwrap();
+        !rulebox.assign_labels(text).is_empty()
+    }
+
+    #[test]
+    fn test_pattern_is_a_literal() {
+        assert!(matches("1+1", "contains", "is 1+1 two?"));
+        assert!(!matches("1+1", "contains", "11"));
+        assert!(matches("a.b", "contains", "xa.bx"));
+        assert!(!matches("a.b", "contains", "axb"));
+    }
+
+    #[test]
+    fn test_word_mode() {
+        assert!(matches("cat", "word", "the cat sat"));
+        assert!(matches("cat", "word", "cat"));
+        assert!(!matches("cat", "word", "concatenate"));
+        assert!(!matches("cat", "word", "cats"));
+        // Punctuation at the edges still has to stand apart from words
+        assert!(matches("C++", "word", "I write C++ daily"));
+        assert!(matches("C++", "word", "C++"));
+        assert!(!matches("C++", "word", "C++x"));
+        assert!(!matches("C++", "word", "XC++"));
+        assert!(matches("café", "word", "un café noir"));
+        assert!(!matches("café", "word", "cafés"));
+    }
+
+    #[test]
+    fn test_anchored_modes() {
+        assert!(matches("yes", "exact", "yes"));
+        assert!(!matches("yes", "exact", "yes please"));
+        assert!(!matches("yes", "exact", "line\nyes"));
+        assert!(matches("Re:", "starts_with", "Re: your order"));
+        assert!(!matches("Re:", "starts_with", "Fwd: Re: your order"));
+        assert!(matches("?", "ends_with", "really?"));
+        assert!(!matches("?", "ends_with", "really? no"));
+    }
+
+    #[test]
+    fn test_match_mode_works_with_flags_and_prefilter() {
+        // Enough rules for the pre-filter to be built
+        let mut rules = vec![
+            r#"{"label": "a", "rule": {"or_patterns": [{"pattern": "HELLO", "match_mode": "word", "flags": ["i"]}]}}"#.to_string(),
+        ];
+        rules.extend((0..40).map(|i| {
+            format!(
+                r#"{{"label": "n{i}", "rule": {{"or_patterns": [{{"pattern": "needle{i}"}}]}}}}"#
+            )
+        }));
+        let rulebox = RuleBox::from_json(&format!("[{}]", rules.join(","))).unwrap();
+        assert_eq!(rulebox.assign_labels("say hello"), vec!["a".to_string()]);
+        assert!(rulebox.assign_labels("othello").is_empty());
+
+        let json = serde_json::to_string(&rulebox).unwrap();
+        assert!(json.contains(r#""match_mode":"word""#));
+    }
+}
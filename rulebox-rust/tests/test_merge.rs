@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    const HEALTH: &str = r#"{
+        "default_flags": ["i"],
+        "implies": {"nhs": ["public"]},
+        "count_rules": [{"if_labels_count_at_least": 2, "then": "many"}],
+        "rules": [
+            {"uuid": "h1", "label": "nhs", "rule": {"or_patterns": [{"pattern": "nhs"}]}},
+            {"uuid": "h2", "label": "other", "rule": {"or_patterns": [{"pattern": "doctor"}]}}
+        ]
+    }"#;
+    const SCHOOLS: &str = r#"[
+        {"uuid": "s1", "label": "ofsted", "rule": {"or_patterns": [{"pattern": "Ofsted"}]}},
+        {"uuid": "s2", "label": "other", "rule": {"or_patterns": [{"pattern": "teacher"}]}}
+    ]"#;
+
+    #[test]
+    fn test_merge_keeps_each_box_matching_as_before() {
+        let mut merged = RuleBox::from_json(SCHOOLS).unwrap();
+        merged.merge(RuleBox::from_json(HEALTH).unwrap()).unwrap();
+        assert_eq!(merged.rules.len(), 4);
+        // The health box's default `i` went with its rules, not to schools'
+        assert_eq!(
+            merged.assign_labels("NHS"),
+            vec!["nhs".to_string(), "public".to_string(), "many".to_string()]
+        );
+        assert!(merged.assign_labels("OFSTED").is_empty());
+        assert_eq!(merged.assign_labels("Ofsted"), vec!["ofsted".to_string()]);
+    }
+
+    #[test]
+    fn test_label_prefix_namespaces_every_label() {
+        let health = RuleBox::from_json(HEALTH)
+            .unwrap()
+            .with_label_prefix("health.");
+        let mut merged = RuleBox::from_json(SCHOOLS)
+            .unwrap()
+            .with_label_prefix("schools.");
+        merged.merge(health).unwrap();
+
+        let mut labels = merged.assign_labels("nhs doctor and a teacher");
+        labels.sort();
+        assert_eq!(
+            labels,
+            vec![
+                "health.many",
+                "health.nhs",
+                "health.other",
+                "health.public",
+                "schools.other"
+            ]
+        );
+        // The health count rule only counts health labels
+        assert!(!merged
+            .assign_labels("doctor and a teacher")
+            .contains(&"health.many".to_string()));
+    }
+
+    #[test]
+    fn test_label_prefix_covers_stages_and_groups() {
+        let staged = |prefix: &str| {
+            let json = format!(
+                r#"[
+                    {{"uuid": "{p}a", "label": "a", "rule": {{"or_patterns": [{{"pattern": "a"}}]}}}},
+                    {{"uuid": "{p}x", "label": "x", "group": "g", "rule": {{"or_patterns": [{{"pattern": "x"}}]}}}},
+                    {{"uuid": "{p}b", "label": "b", "stage": 1, "requires_labels": ["a"], "rule": {{"or_patterns": [{{"pattern": "b"}}]}}}},
+                    {{"uuid": "{p}c", "label": "c", "stage": 1, "excludes_labels": ["a"], "rule": {{"or_patterns": [{{"pattern": "c"}}]}}}}
+                ]"#,
+                p = prefix
+            );
+            RuleBox::from_json(&json).unwrap()
+        };
+        assert_eq!(staged("").assign_labels("a x b c"), vec!["a", "x", "b"]);
+        assert_eq!(
+            staged("").with_label_prefix("h.").assign_labels("a x b c"),
+            vec!["h.a", "h.x", "h.b"]
+        );
+
+        let mut merged = staged("h").with_label_prefix("h.");
+        merged
+            .merge(
+                RuleBox::from_json(
+                    r#"[{"uuid": "fy", "label": "y", "group": "g", "rule": {"or_patterns": [{"pattern": "y"}]}}]"#,
+                )
+                .unwrap()
+                .with_label_prefix("f."),
+            )
+            .unwrap();
+        // Each file's group `g` is its own, so neither winner knocks out the
+        // other
+        assert_eq!(merged.assign_labels("x y c"), vec!["h.x", "f.y", "h.c"]);
+    }
+
+    #[test]
+    fn test_merge_conflicts_leave_the_box_unchanged() {
+        let mut merged = RuleBox::from_json(SCHOOLS).unwrap();
+        let err = merged
+            .merge(RuleBox::from_json(SCHOOLS).unwrap())
+            .unwrap_err();
+        assert!(matches!(err, RuleBoxError::MergeConflict(_)));
+        assert_eq!(
+            err.to_string(),
+            "Can't merge rules: rule s1 is in both boxes"
+        );
+
+        let lowercased = RuleBox::from_json(
+            r#"{"preprocess": {"lowercase": true}, "rules": [{"uuid": "x", "label": "x", "rule": {"or_patterns": [{"pattern": "x"}]}}]}"#,
+        )
+        .unwrap();
+        assert!(merged.merge(lowercased).is_err());
+
+        let thresholds = |threshold: f64| {
+            let json = format!(
+                r#"{{"score_thresholds": {{"spam": {}}}, "rules": []}}"#,
+                threshold
+            );
+            RuleBox::from_json(&json).unwrap()
+        };
+        let mut spam = thresholds(1.0);
+        assert!(spam.merge(thresholds(1.0)).is_ok());
+        assert!(spam.merge(thresholds(2.0)).is_err());
+        assert_eq!(merged.rules.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "fs")]
+    fn test_from_paths_with_prefixes() {
+        let dir = std::env::temp_dir().join(format!("rulebox-merge-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let health = dir.join("health.json");
+        let schools = dir.join("schools.json");
+        std::fs::write(&health, HEALTH).unwrap();
+        std::fs::write(&schools, SCHOOLS).unwrap();
+        let (health, schools) = (health.to_str().unwrap(), schools.to_str().unwrap());
+
+        let merged =
+            RuleBox::from_paths_with_prefixes(&[(health, "health/"), (schools, "schools/")])
+                .unwrap();
+        assert_eq!(
+            merged.assign_labels("a teacher"),
+            vec!["schools/other".to_string()]
+        );
+
+        // Without prefixes both files' `other` rules assign the same label.
+        // Health's default `i` stays with health's patterns either way round
+        let merged = RuleBox::from_paths(&[health, schools]).unwrap();
+        assert!(merged.default_flags.is_empty());
+        assert!(merged.assign_labels("OFSTED").is_empty());
+        assert_eq!(merged.assign_labels("NHS")[0], "nhs");
+        assert_eq!(merged.assign_labels("a teacher"), vec!["other".to_string()]);
+        assert_eq!(merged.assign_labels("a doctor"), vec!["other".to_string()]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    const RULES: &str = r#"[
+        {
+            "uuid": "pii-1",
+            "label": "pii",
+            "tags": ["gdpr"],
+            "metadata": {
+                "description": "UK National Insurance numbers",
+                "author": "analyst@example.org",
+                "created": "2024-05-01T12:00:00Z",
+                "updated": "2024-06-02T09:30:00Z",
+                "severity": "high"
+            },
+            "rule": {"or_patterns": [{"pattern": "[A-Z]{2}\\d{6}[A-D]"}]}
+        },
+        {"uuid": "plain", "label": "other", "rule": {"or_patterns": [{"pattern": "x"}]}}
+    ]"#;
+
+    #[test]
+    fn test_metadata_is_queryable() {
+        let rulebox = RuleBox::from_json(RULES).expect("Failed to load rules");
+        let metadata = rulebox.rule_metadata("pii-1").unwrap();
+        assert_eq!(metadata.author.as_deref(), Some("analyst@example.org"));
+        assert_eq!(metadata.severity.as_deref(), Some("high"));
+        assert_eq!(metadata.created.as_deref(), Some("2024-05-01T12:00:00Z"));
+        assert!(rulebox.rules[1].metadata().is_empty());
+        assert!(rulebox.rule_metadata("missing").is_none());
+
+        let report = rulebox.report_markdown();
+        assert!(report.contains("UK National Insurance numbers\n"));
+        assert!(report.contains("- Severity: high"));
+    }
+
+    #[test]
+    fn test_metadata_round_trips() {
+        let rulebox = RuleBox::from_json(RULES).unwrap();
+        let json = rulebox.to_json_string().unwrap();
+        let reloaded = RuleBox::from_json(&json).unwrap();
+        assert_eq!(reloaded.rules[0].metadata(), rulebox.rules[0].metadata());
+        // Rules without metadata don't grow an empty object
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(value[1].get("metadata").is_none());
+
+        let described =
+            RuleBox::from_json(&json.replace("UK National", "British National")).unwrap();
+        assert_eq!(described.fingerprint(), rulebox.fingerprint());
+    }
+}
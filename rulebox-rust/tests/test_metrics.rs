@@ -0,0 +1,69 @@
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use rulebox_rust::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_label_counters_increment_per_check() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"label": "dog", "rule": {"or_patterns": [{"pattern": "dog"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            rulebox.check("cat");
+            rulebox.assign_labels("cat and dog");
+            rulebox.assign_labels_vector(&["dog".to_string(), "neither".to_string()]);
+        });
+
+        let counts: HashMap<String, u64> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .filter(|(key, ..)| key.key().name() == "rulebox_label_assigned_total")
+            .map(|(key, _, _, value)| {
+                let label = key.key().labels().next().unwrap().value().to_string();
+                let DebugValue::Counter(count) = value else {
+                    panic!("Expected a counter");
+                };
+                (label, count)
+            })
+            .collect();
+        assert_eq!(
+            counts,
+            HashMap::from([("cat".to_string(), 2), ("dog".to_string(), 2)])
+        );
+    }
+
+    #[test]
+    fn test_shadow_hits_are_counted() {
+        let rulebox = RuleBox::from_json(
+            r#"[{"uuid": "canary", "label": "pet", "shadow": true, "rule": {"or_patterns": [{"pattern": "cat"}]}}]"#,
+        )
+        .expect("Failed to load rules");
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            assert!(rulebox.assign_labels("cat").is_empty());
+        });
+
+        let snapshot = snapshotter.snapshot().into_vec();
+        let (key, _, _, value) = snapshot
+            .iter()
+            .find(|(key, ..)| key.key().name() == "rulebox_shadow_hit_total")
+            .expect("Shadow hit should be counted");
+        let labels: Vec<(&str, &str)> = key.key().labels().map(|l| (l.key(), l.value())).collect();
+        assert_eq!(labels, vec![("label", "pet"), ("uuid", "canary")]);
+        assert_eq!(value, &DebugValue::Counter(1));
+        assert!(!snapshot
+            .iter()
+            .any(|(key, ..)| key.key().name() == "rulebox_label_assigned_total"));
+    }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    fn spam_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"label": "spam", "rule": {"or_patterns": [
+                    {"pattern": "\\bfree\\b", "flags": ["i"], "min_count": 4},
+                    {"pattern": "\\bwinner\\b", "min_count": 2}
+                ]}},
+                {"label": "offer", "rule": {"and_patterns": [
+                    {"pattern": "\\boffer\\b", "min_count": 1},
+                    {"pattern": "!", "min_count": 3}
+                ]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_too_few_occurrences_do_not_match() {
+        let rulebox = spam_box();
+        assert!(rulebox.assign_labels("Free, free, FREE!").is_empty());
+        assert!(rulebox.assign_labels("winner takes the offer!!").is_empty());
+    }
+
+    #[test]
+    fn test_enough_occurrences_match() {
+        let rulebox = spam_box();
+        assert_eq!(
+            rulebox.assign_labels("Free free FREE free"),
+            vec!["spam".to_string()]
+        );
+        assert_eq!(
+            rulebox.assign_labels("winner, winner"),
+            vec!["spam".to_string()]
+        );
+        assert_eq!(
+            rulebox.assign_labels("An offer!!!"),
+            vec!["offer".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_min_count_in_not_patterns_and_spans() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "contact_list", "rule": {
+                    "or_patterns": [{"pattern": "\\b\\d{5} ?\\d{6}\\b", "min_count": 3}],
+                    "not_patterns": [{"pattern": "\\bexample\\b", "min_count": 2}]
+                }}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        let three = "01632 960001, 01632 960002 and 01632960003";
+        assert_eq!(
+            rulebox.assign_labels(three),
+            vec!["contact_list".to_string()]
+        );
+        assert!(rulebox
+            .assign_labels("01632 960001, 01632 960002")
+            .is_empty());
+
+        // One "example" isn't enough to veto
+        let vetoed = format!("{} example example", three);
+        assert_eq!(
+            rulebox.assign_labels(&format!("{} example", three)),
+            vec!["contact_list".to_string()]
+        );
+        assert!(rulebox.assign_labels(&vetoed).is_empty());
+
+        // Every occurrence is reported, not just the first
+        let detail = rulebox.check_detailed(three);
+        assert_eq!(detail["contact_list"].spans.len(), 3);
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    fn rule(json: &str) -> LabelRule {
+        serde_json::from_str(json).expect("Failed to parse rule")
+    }
+
+    fn base_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"{"default_flags": ["i"], "rules": [
+                {"uuid": "cat", "label": "cat", "rule": {"or_patterns": [{"pattern": "\\bcat\\b"}]}},
+                {"uuid": "dog", "label": "dog", "rule": {"or_patterns": [{"pattern": "\\bdog\\b"}]}}
+            ]}"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_add_and_remove_rules() {
+        let mut rulebox = base_box();
+        rulebox
+            .add_rule(rule(
+                r#"{"uuid": "bird", "label": "bird", "rule": {"or_patterns": [{"pattern": "bird"}]}}"#,
+            ))
+            .unwrap();
+        // Compiled against the box settings, so the default `i` applies
+        assert_eq!(rulebox.assign_labels("BIRD"), vec!["bird"]);
+
+        let removed = rulebox.remove_rule("cat").unwrap();
+        assert_eq!(removed.label, "cat");
+        assert!(rulebox.assign_labels("cat").is_empty());
+        assert!(rulebox.remove_rule("cat").is_none());
+
+        let err = rulebox.add_rule(rule(
+            r#"{"uuid": "bad", "label": "bad", "rule": {"or_patterns": [{"pattern": "(bad"}]}}"#,
+        ));
+        assert_eq!(err.unwrap_err().uuid(), Some("bad"));
+        assert_eq!(rulebox.rules.len(), 2);
+    }
+
+    #[test]
+    fn test_update_rule_keeps_position() {
+        let mut rulebox = base_box();
+        let old = rulebox
+            .update_rule(
+                "cat",
+                rule(r#"{"uuid": "cat", "label": "cat", "rule": {"or_patterns": [{"pattern": "kitten"}]}}"#),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(old.rule.or_patterns[0].pattern, "\\bcat\\b");
+        assert_eq!(rulebox.rules[0].uuid, "cat");
+        assert_eq!(rulebox.assign_labels("Kitten"), vec!["cat"]);
+        assert!(rulebox.assign_labels("cat").is_empty());
+
+        let missing = rulebox.update_rule("nope", rule(r#"{"label": "x", "rule": {}}"#));
+        assert!(matches!(missing, Ok(None)));
+        let broken = rulebox.update_rule(
+            "dog",
+            rule(r#"{"label": "dog", "rule": {"not_patterns": [{"pattern": "x"}]}}"#),
+        );
+        assert!(broken.is_err());
+        assert_eq!(rulebox.assign_labels("dog"), vec!["dog"]);
+    }
+
+    #[test]
+    fn test_set_active() {
+        let mut rulebox = base_box();
+        assert!(rulebox.set_active("dog", false));
+        assert!(rulebox.assign_labels("dog").is_empty());
+        assert!(rulebox.set_active("dog", true));
+        assert_eq!(rulebox.assign_labels("dog"), vec!["dog"]);
+        assert!(!rulebox.set_active("nope", true));
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    fn refund_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"uuid": "w", "label": "refund_demand", "rule": {"near_patterns": [
+                    {"first": {"pattern": "\\brefund\\b", "flags": ["i"]},
+                     "second": {"pattern": "\\bdemand", "flags": ["i"]},
+                     "within": 3, "unit": "words"}
+                ]}},
+                {"uuid": "c", "label": "postcode_pair", "rule": {
+                    "or_patterns": [{"pattern": "address"}],
+                    "near_patterns": [
+                        {"first": {"pattern": "[A-Z]{2}\\d"}, "second": {"pattern": "\\d[A-Z]{2}"}, "within": 1}
+                    ]
+                }}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_words_between_matches() {
+        let rulebox = refund_box();
+        assert_eq!(
+            rulebox.assign_labels("I demand a full refund"),
+            vec!["refund_demand".to_string()]
+        );
+        assert_eq!(
+            rulebox.assign_labels("Refund, as I demanded"),
+            vec!["refund_demand".to_string()]
+        );
+        assert!(rulebox
+            .assign_labels("Refund policy is on our website. We don't take demands.")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_chars_between_matches_alongside_other_patterns() {
+        let rulebox = refund_box();
+        assert_eq!(
+            rulebox.assign_labels("address: SW1 2AA"),
+            vec!["postcode_pair".to_string()]
+        );
+        assert!(rulebox.assign_labels("address: SW1   2AA").is_empty());
+        // The or_patterns still have to match too
+        assert!(rulebox.assign_labels("SW1 2AA").is_empty());
+    }
+
+    #[test]
+    fn test_near_patterns_in_explain_and_round_trip() {
+        let rulebox = refund_box();
+        let explained = rulebox.explain("refund, then a long wait and finally a demand");
+        assert_eq!(explained[0].near_patterns, vec![false]);
+        assert_eq!(explained[0].outcome, RuleOutcome::NoMatch);
+
+        let json = rulebox.to_json_string().unwrap();
+        assert!(json.contains(r#""unit": "words""#));
+        let reloaded = RuleBox::from_json(&json).unwrap();
+        assert_eq!(
+            reloaded.rules[1].rule.near_patterns[0].unit,
+            Proximity::Chars
+        );
+        assert_eq!(reloaded.rules[1].rule.near_patterns[0].within, 1);
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::collections::HashMap;
+
+    fn complaint_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"uuid": "c", "label": "complaint", "rule": {
+                    "or_patterns": [{"pattern": "(?i)complain"}],
+                    "not_patterns": [{"pattern": "(?i)not a complaint"}],
+                    "not_within": 20
+                }}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_veto_only_near_a_positive_match() {
+        let rulebox = complaint_box();
+        // "complain" inside the vetoing phrase itself is as near as can be
+        assert!(rulebox.assign_labels("This is not a complaint.").is_empty());
+        assert!(rulebox
+            .assign_labels("I wish to complain. Honestly, not a complaint.")
+            .is_empty());
+
+        let text = format!(
+            "I wish to complain about the bins.{}This is not a complaint.",
+            " Lorem ipsum dolor sit amet.".repeat(5)
+        );
+        // The veto's own "complain" is still a positive match next to it
+        assert!(rulebox.assign_labels(&text).is_empty());
+    }
+
+    #[test]
+    fn test_far_veto_is_ignored() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "b", "label": "bins", "rule": {
+                    "or_patterns": [{"pattern": "(?i)\\bbins?\\b"}],
+                    "not_patterns": [{"pattern": "(?i)newsletter"}],
+                    "not_within": 30
+                }}
+            ]"#,
+        )
+        .unwrap();
+        let text = format!(
+            "My bins were not collected.{}You are receiving this newsletter because you signed up.",
+            " Lorem ipsum dolor sit amet.".repeat(5)
+        );
+        assert_eq!(rulebox.assign_labels(&text), vec!["bins".to_string()]);
+        assert!(rulebox
+            .assign_labels("Bins newsletter: collection days")
+            .is_empty());
+
+        let record = HashMap::from([("body".to_string(), text.clone())]);
+        assert!(rulebox.check_record(&record).has_label("bins"));
+
+        let json = rulebox.to_json_string().unwrap();
+        assert!(json.contains(r#""not_within": 30"#));
+        assert!(rulebox.report_markdown().contains("within 30 characters"));
+    }
+
+    #[test]
+    fn test_veto_near_a_keyword_match() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "r", "label": "refund", "rule": {
+                    "keyword_patterns": {"keywords": ["refund"]},
+                    "not_patterns": [{"pattern": "joke"}],
+                    "not_within": 5
+                }}
+            ]"#,
+        )
+        .unwrap();
+        assert!(rulebox.assign_labels("refund joke").is_empty());
+        assert_eq!(
+            rulebox.assign_labels("refund please, this is no laughing matter, not a joke"),
+            vec!["refund".to_string()]
+        );
+    }
+}
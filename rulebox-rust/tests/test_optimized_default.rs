@@ -12,13 +12,14 @@ mod tests {
                         pattern: r"\bemail\b".to_string(),
                         flags: vec!["i".to_string()],
                         compiled: None,
+                        ..Default::default()
                     }],
-                    and_patterns: vec![],
-                    not_patterns: vec![],
+                    ..Default::default()
                 },
                 label: "contains_email".to_string(),
                 proto_text: "".to_string(),
                 active: true,
+                ..Default::default()
             },
             LabelRule {
                 uuid: "test2".to_string(),
@@ -27,13 +28,14 @@ mod tests {
                         pattern: r"\bphone\b".to_string(),
                         flags: vec!["i".to_string()],
                         compiled: None,
+                        ..Default::default()
                     }],
-                    and_patterns: vec![],
-                    not_patterns: vec![],
+                    ..Default::default()
                 },
                 label: "contains_phone".to_string(),
                 proto_text: "".to_string(),
                 active: true,
+                ..Default::default()
             },
             // Add an inactive rule to test filtering
             LabelRule {
@@ -43,17 +45,18 @@ mod tests {
                         pattern: r"\binactive\b".to_string(),
                         flags: vec!["i".to_string()],
                         compiled: None,
+                        ..Default::default()
                     }],
-                    and_patterns: vec![],
-                    not_patterns: vec![],
+                    ..Default::default()
                 },
                 label: "inactive_rule".to_string(),
                 proto_text: "".to_string(),
                 active: false,
+                ..Default::default()
             },
         ];
 
-        let mut rulebox = RuleBox(rules);
+        let mut rulebox = RuleBox::new(rules);
         rulebox.compile().expect("Failed to compile rules");
 
         let texts = vec![
@@ -92,4 +95,93 @@ mod tests {
         println!("✅ All tests passed! The optimized default implementation works correctly.");
         println!("Results: {:?}", results);
     }
+
+    #[test]
+    fn test_assign_labels_vector_into_reuses_buffer() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"label": "dog", "rule": {"or_patterns": [{"pattern": "dog"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let mut out = vec![Vec::with_capacity(8), vec!["stale".to_string()], Vec::new()];
+        let first_ptr = out[0].as_ptr();
+        let texts = vec!["cat and dog".to_string(), "fish".to_string()];
+        rulebox.assign_labels_vector_into(&texts, &mut out);
+
+        assert_eq!(out, rulebox.assign_labels_vector(&texts));
+        assert_eq!(
+            out,
+            vec![vec!["cat".to_string(), "dog".to_string()], vec![]]
+        );
+        // The first inner Vec had room, so it was refilled in place
+        assert_eq!(out[0].as_ptr(), first_ptr);
+
+        let more = vec!["dog".to_string(), "cat".to_string(), "bird".to_string()];
+        rulebox.assign_labels_vector_into(&more, &mut out);
+        assert_eq!(
+            out,
+            vec![vec!["dog".to_string()], vec!["cat".to_string()], vec![]]
+        );
+    }
+
+    #[test]
+    fn test_assign_labels_is_in_rule_order() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "implies": {"zebra": ["animal"]},
+                "rules": [
+                    {"label": "zebra", "rule": {"or_patterns": [{"pattern": "stripes"}]}},
+                    {"label": "apple", "rule": {"or_patterns": [{"pattern": "fruit"}]}},
+                    {"label": "mango", "rule": {"or_patterns": [{"pattern": "fruit"}]}},
+                    {"label": "zebra", "rule": {"or_patterns": [{"pattern": "fruit"}]}}
+                ]
+            }"#,
+        )
+        .expect("Failed to load rules");
+
+        let text = "fruit with stripes";
+        let expected = vec!["zebra", "apple", "mango", "animal"];
+        // The same order every time, whatever the hash seed
+        for _ in 0..20 {
+            assert_eq!(rulebox.assign_labels(text), expected);
+        }
+        assert_eq!(
+            rulebox.assign_labels_vector(&[text.to_string()]),
+            vec![expected]
+        );
+    }
+
+    #[test]
+    fn test_threshold_and_group_labels_keep_a_fixed_order() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "score_thresholds": {"t1": 0.5, "t2": 0.5, "t3": 0.5, "t4": 0.5},
+                "rules": [
+                    {"label": "t3", "rule": {"or_patterns": [{"pattern": "x"}]}},
+                    {"label": "g3", "group": "c", "rule": {"or_patterns": [{"pattern": "x"}]}},
+                    {"label": "t1", "rule": {"or_patterns": [{"pattern": "x"}]}},
+                    {"label": "g1", "group": "a", "rule": {"or_patterns": [{"pattern": "x"}]}},
+                    {"label": "t4", "rule": {"or_patterns": [{"pattern": "x"}]}},
+                    {"label": "g4", "group": "d", "rule": {"or_patterns": [{"pattern": "x"}]}},
+                    {"label": "t2", "rule": {"or_patterns": [{"pattern": "x"}]}},
+                    {"label": "g2", "group": "b", "rule": {"or_patterns": [{"pattern": "x"}]}}
+                ]
+            }"#,
+        )
+        .expect("Failed to load rules");
+
+        // Thresholded labels in `score_thresholds` order, then group winners
+        // in rule order
+        let expected = vec!["t1", "t2", "t3", "t4", "g3", "g1", "g4", "g2"];
+        for _ in 0..20 {
+            assert_eq!(rulebox.assign_labels("x"), expected);
+        }
+        assert_eq!(
+            rulebox.assign_labels_vector(&["x", "x"]),
+            vec![expected.clone(), expected]
+        );
+    }
 }
@@ -0,0 +1,30 @@
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use rulebox_rust::*;
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let rulebox = RuleBox::from_json(
+            r#"{"count_rules": [{"if_labels_count_at_least": 2, "then": "both"}],
+                "rules": [
+                    {"label": "cat", "rule": {"or_patterns": [{"pattern": "\\bcat\\b", "flags": ["i"]}]}},
+                    {"label": "dog", "rule": {"or_patterns": [{"pattern": "\\bdog\\b"}]}},
+                    {"label": "off", "active": false, "rule": {"or_patterns": [{"pattern": "cat"}]}}
+                ]}"#,
+        )
+        .expect("Failed to load rules");
+        let texts: Vec<String> = (0..1000)
+            .map(|i| match i % 4 {
+                0 => format!("Cat {i}"),
+                1 => format!("dog {i}"),
+                2 => format!("cat and dog {i}"),
+                _ => format!("nothing {i}"),
+            })
+            .collect();
+
+        let parallel = rulebox.assign_labels_vector_parallel(&texts);
+        assert_eq!(parallel, rulebox.assign_labels_vector(&texts));
+        assert_eq!(parallel[2], vec!["cat", "dog", "both"]);
+        assert!(parallel[3].is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::collections::HashSet;
+
+    // Enough rules for the box-wide pre-filter to kick in, plus a few it has
+    // to leave alone
+    fn large_box() -> RuleBox {
+        let mut rules: Vec<String> = (0..50)
+            .map(|i| {
+                format!(
+                    r#"{{"label": "word{i}", "rule": {{"or_patterns": [{{"pattern": "\\bword{i}\\b", "flags": ["i"]}}]}}}}"#
+                )
+            })
+            .collect();
+        rules.extend([
+            r#"{"label": "both", "rule": {"and_patterns": [{"pattern": "alpha"}, {"pattern": "beta"}]}}"#.to_string(),
+            r#"{"label": "repeated", "rule": {"or_patterns": [{"pattern": "ha", "min_count": 3}]}}"#.to_string(),
+            r#"{"label": "phone", "rule": {"digit_normalize": true, "or_patterns": [{"pattern": "5551234"}]}}"#.to_string(),
+            r#"{"label": "no_bird", "rule": {"expr": {"not": {"pattern": {"pattern": "bird"}}}}}"#.to_string(),
+        ]);
+        RuleBox::from_json(&format!("[{}]", rules.join(","))).expect("Failed to load rules")
+    }
+
+    fn unfiltered_labels(rulebox: &RuleBox, text: &str) -> HashSet<String> {
+        rulebox
+            .rules
+            .iter()
+            .filter(|rule| rule.assigns_labels() && rule.rule.check(text))
+            .map(|rule| rule.label.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_prefiltered_check_matches_rule_by_rule() {
+        let rulebox = large_box();
+        let texts: Vec<String> = [
+            "WORD7 and word42",
+            "alpha beta",
+            "alpha only",
+            "ha ha ha",
+            "ha ha",
+            "call 555-12 34",
+            "a bird",
+            "",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        for text in &texts {
+            assert_eq!(
+                rulebox.check(text).labels(),
+                &unfiltered_labels(&rulebox, text),
+                "{text}"
+            );
+        }
+        let batch = rulebox.assign_labels_vector(&texts);
+        for (text, labels) in texts.iter().zip(batch) {
+            let labels: HashSet<String> = labels.into_iter().collect();
+            assert_eq!(labels, unfiltered_labels(&rulebox, text));
+        }
+    }
+
+    #[test]
+    fn test_rules_added_after_compile_are_still_checked() {
+        let mut rulebox = large_box();
+        let extra: LabelRule = serde_json::from_str(
+            r#"{"label": "late", "rule": {"or_patterns": [{"pattern": "late"}]}}"#,
+        )
+        .unwrap();
+        rulebox.rules.push(extra);
+        rulebox.recompile_dirty().unwrap();
+        assert!(rulebox.check("too late").labels().contains("late"));
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    const RULES: &str = r#"{
+        "preprocess": {"strip_html": true, "lowercase": true, "collapse_whitespace": true},
+        "rules": [
+            {"label": "greeting", "rule": {"or_patterns": [{"pattern": "hello world"}]},
+             "should_match": ["HELLO   World"]},
+            {"label": "tag", "rule": {"or_patterns": [{"pattern": "<b>"}]}}
+        ]
+    }"#;
+
+    #[test]
+    fn test_preprocess_applies_before_matching() {
+        let rulebox = RuleBox::from_json(RULES).expect("Failed to load rules");
+        assert_eq!(
+            rulebox.preprocessed("<p>Hello,\n\tWORLD</p>"),
+            " hello, world "
+        );
+
+        let texts: Vec<String> = ["<b>Hello</b>\n  World!", "<b>bold</b>", "hello"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            rulebox.assign_labels_vector(&texts),
+            vec![vec!["greeting".to_string()], vec![], vec![]]
+        );
+        assert_eq!(rulebox.assign_labels(&texts[0]), vec!["greeting"]);
+        assert_eq!(rulebox.classify(&texts[0]).as_deref(), Some("greeting"));
+        assert_eq!(rulebox.score(&texts[0])["greeting"], 1.0);
+    }
+
+    #[test]
+    fn test_spans_refer_to_original_text() {
+        let rulebox = RuleBox::from_json(RULES).unwrap();
+        let text = "Say <i>HELLO</i>  World now";
+        let matches = rulebox.all_matches(text);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].text(text), Some("HELLO</i>  World"));
+        let detail = rulebox.check_detailed(text);
+        assert_eq!(detail["greeting"].spans[0].text, "HELLO</i>  World");
+    }
+
+    #[test]
+    fn test_preprocess_round_trips() {
+        let rulebox = RuleBox::from_json(RULES).unwrap();
+        assert!(rulebox.preprocess.strip_html && !rulebox.preprocess.nfkc);
+        let reloaded = RuleBox::from_json(&rulebox.to_json_string().unwrap()).unwrap();
+        assert_eq!(reloaded.preprocess, rulebox.preprocess);
+
+        let plain =
+            RuleBox::from_json(r#"[{"label": "a", "rule": {"or_patterns": [{"pattern": "A"}]}}]"#)
+                .unwrap();
+        assert!(plain
+            .to_json_string()
+            .unwrap()
+            .trim_start()
+            .starts_with('['));
+        let lowered = plain
+            .with_preprocess(Preprocess {
+                lowercase: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(lowered.assign_labels("A").is_empty());
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_nfkc() {
+        let rulebox = RuleBox::from_json(
+            r#"{"preprocess": {"nfkc": true, "lowercase": true}, "rules": [
+                {"label": "finance", "rule": {"or_patterns": [{"pattern": "\\bfinance\\b"}]}}
+            ]}"#,
+        )
+        .unwrap();
+        // Ligature, full-width letters and a decomposed accent
+        assert_eq!(rulebox.preprocessed("ﬁＮａnce cafe\u{301}"), "finance café");
+        let text = "ﬁＮａnce";
+        assert_eq!(rulebox.all_matches(text)[0].text(text), Some(text));
+    }
+
+    #[cfg(not(feature = "unicode"))]
+    #[test]
+    fn test_nfkc_needs_unicode_feature() {
+        let err = RuleBox::from_json(r#"{"preprocess": {"nfkc": true}, "rules": []}"#).unwrap_err();
+        assert!(matches!(
+            err,
+            RuleBoxError::Compile(CompileError::InvalidPreprocess { .. })
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::collections::HashMap;
+
+    fn record(subject: &str, body: &str) -> HashMap<String, String> {
+        HashMap::from([
+            ("subject".to_string(), subject.to_string()),
+            ("body".to_string(), body.to_string()),
+        ])
+    }
+
+    fn mail_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"label": "invoice_reply", "rule": {"and_patterns": [
+                    {"pattern": "^Re:", "field": "subject"},
+                    {"pattern": "invoice", "flags": ["i"], "field": "body"}
+                ]}},
+                {"label": "urgent", "rule": {
+                    "or_patterns": [{"pattern": "urgent", "flags": ["i"]}],
+                    "not_patterns": [{"pattern": "newsletter", "field": "subject"}]
+                }}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    fn labels(labeled: &LabeledText) -> Vec<String> {
+        let mut labels: Vec<String> = labeled.labels().iter().cloned().collect();
+        labels.sort();
+        labels
+    }
+
+    #[test]
+    fn test_conditions_across_two_fields() {
+        let rulebox = mail_box();
+        let matched = rulebox.check_record(&record("Re: payment", "Invoice attached"));
+        assert_eq!(labels(&matched), vec!["invoice_reply"]);
+
+        // Right words, wrong fields
+        let swapped = rulebox.check_record(&record("invoice", "Re: payment"));
+        assert!(labels(&swapped).is_empty());
+        let missing_body = rulebox.check_record(&HashMap::from([(
+            "subject".to_string(),
+            "Re: invoice".to_string(),
+        )]));
+        assert!(labels(&missing_body).is_empty());
+    }
+
+    #[test]
+    fn test_unfielded_patterns_see_every_field() {
+        let rulebox = mail_box();
+        assert_eq!(
+            labels(&rulebox.check_record(&record("Hello", "This is URGENT"))),
+            vec!["urgent"]
+        );
+        assert_eq!(
+            labels(&rulebox.check_record(&record("Urgent", "hi"))),
+            vec!["urgent"]
+        );
+        // The veto only looks at the subject
+        assert!(labels(&rulebox.check_record(&record("Weekly newsletter", "urgent"))).is_empty());
+        assert_eq!(
+            labels(&rulebox.check_record(&record("Hi", "urgent newsletter"))),
+            vec!["urgent"]
+        );
+    }
+
+    #[test]
+    fn test_check_document_with_several_fields() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "refund", "rule": {"or_patterns": [
+                    {"pattern": "refund", "flags": ["i"], "fields": ["subject", "body"]}
+                ]}},
+                {"label": "internal", "rule": {"and_patterns": [
+                    {"pattern": "@example\\.org$", "field": "sender"}
+                ]}}
+            ]"#,
+        )
+        .unwrap();
+
+        let document = Document::new()
+            .with_field("subject", "Question")
+            .with_field("body", "Can I get a Refund?")
+            .with_field("sender", "someone@example.org");
+        let labeled = rulebox.check_document(&document);
+        assert_eq!(labels(&labeled), vec!["internal", "refund"]);
+        assert_eq!(
+            document.joined(),
+            "Can I get a Refund?\nsomeone@example.org\nQuestion"
+        );
+
+        // "refund" in a field the pattern doesn't list doesn't count
+        let document = Document::new()
+            .with_field("subject", "Hi")
+            .with_field("sender", "refund@example.com");
+        assert!(labels(&rulebox.check_document(&document)).is_empty());
+
+        let parsed: Document = serde_json::from_str(r#"{"subject": "refund"}"#).unwrap();
+        assert_eq!(parsed.field("subject"), Some("refund"));
+        assert_eq!(labels(&rulebox.check_document(&parsed)), vec!["refund"]);
+    }
+
+    #[test]
+    fn test_check_document_applies_thresholds_groups_and_shadow() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "score_thresholds": {"spam": 1.5},
+                "rules": [
+                    {"label": "spam", "rule": {"or_patterns": [{"pattern": "free"}]}},
+                    {"label": "a", "group": "g", "priority": 1, "rule": {"or_patterns": [{"pattern": "x"}]}},
+                    {"label": "b", "group": "g", "priority": 2, "rule": {"or_patterns": [{"pattern": "x"}]}},
+                    {"label": "canary", "shadow": true, "rule": {"or_patterns": [{"pattern": "free"}]}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let document = Document::new().with_field("body", "free x");
+        let labeled = rulebox.check_document(&document);
+        assert_eq!(labels(&labeled), vec!["b"]);
+        assert_eq!(labeled.labels(), rulebox.check("free x").labels());
+        assert!(labeled.shadow_labels().contains("canary"));
+    }
+}
@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::sync::Arc;
+
+    fn load(json: &str, cache: &RegexCache) -> RuleBox {
+        let mut rulebox: RuleBox = serde_json::from_str(json).unwrap();
+        rulebox
+            .compile_with_cache(cache)
+            .expect("Failed to compile");
+        rulebox
+    }
+
+    #[test]
+    fn test_identical_patterns_share_one_regex_across_boxes() {
+        let cache = RegexCache::new();
+        let first = load(
+            r#"[{"label": "a", "rule": {"or_patterns": [{"pattern": "hello", "flags": ["i", "m"]}, {"pattern": "bye"}]}}]"#,
+            &cache,
+        );
+        let second = load(
+            r#"[{"label": "b", "rule": {"and_patterns": [{"pattern": "hello", "flags": ["m", "i"]}, {"pattern": "hello"}]}}]"#,
+            &cache,
+        );
+
+        // "hello" with i+m, "bye", and case-sensitive "hello"
+        assert_eq!(cache.len(), 3);
+        let shared = |rb: &RuleBox, i: usize| {
+            let patterns = [
+                &rb.rules[0].rule.or_patterns,
+                &rb.rules[0].rule.and_patterns,
+            ];
+            let pattern = patterns.iter().find(|p| !p.is_empty()).unwrap();
+            Arc::clone(pattern[i].compiled.as_ref().unwrap())
+        };
+        assert!(Arc::ptr_eq(&shared(&first, 0), &shared(&second, 0)));
+        assert!(!Arc::ptr_eq(&shared(&second, 0), &shared(&second, 1)));
+        assert_eq!(second.assign_labels("HELLO"), Vec::<String>::new());
+        assert_eq!(first.assign_labels("HELLO"), vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn test_cache_reports_invalid_patterns() {
+        let cache = RegexCache::new();
+        let mut rulebox: RuleBox = serde_json::from_str(
+            r#"[{"label": "a", "rule": {"or_patterns": [{"pattern": "("}]}}]"#,
+        )
+        .unwrap();
+        assert!(rulebox.compile_with_cache(&cache).is_err());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_compile_shares_patterns_repeated_across_rules() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "a", "rule": {"or_patterns": [{"pattern": "hello"}]}},
+                {"label": "b", "rule": {"or_patterns": [{"pattern": "hello"}]}}
+            ]"#,
+        )
+        .unwrap();
+        let compiled = |i: usize| {
+            rulebox.rules[i].rule.or_patterns[0]
+                .compiled
+                .clone()
+                .unwrap()
+        };
+        assert!(Arc::ptr_eq(&compiled(0), &compiled(1)));
+    }
+
+    #[test]
+    fn test_reload_reuses_unchanged_patterns_and_prune_drops_stale_ones() {
+        let cache = RegexCache::new();
+        let old = load(
+            r#"[
+                {"label": "a", "rule": {"or_patterns": [{"pattern": "hello"}]}},
+                {"label": "b", "rule": {"or_patterns": [{"pattern": "bye"}]}}
+            ]"#,
+            &cache,
+        );
+        // One rule edited
+        let new = load(
+            r#"[
+                {"label": "a", "rule": {"or_patterns": [{"pattern": "hello"}]}},
+                {"label": "b", "rule": {"or_patterns": [{"pattern": "goodbye"}]}}
+            ]"#,
+            &cache,
+        );
+        let compiled =
+            |rb: &RuleBox, i: usize| rb.rules[i].rule.or_patterns[0].compiled.clone().unwrap();
+        assert!(Arc::ptr_eq(&compiled(&old, 0), &compiled(&new, 0)));
+        assert_eq!(cache.len(), 3);
+
+        assert_eq!(cache.prune(), 0);
+        drop(old);
+        assert_eq!(cache.prune(), 1);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(new.assign_labels("goodbye"), vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn test_added_rules_share_the_box_pattern_table() {
+        let mut rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "a", "label": "email", "rule": {"or_patterns": [{"pattern": "\\w+@\\w+"}]}},
+                {"uuid": "b", "label": "contact", "rule": {"or_patterns": [{"pattern": "\\w+@\\w+"}, {"pattern": "phone"}]}}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(rulebox.distinct_patterns(), 2);
+
+        let rule: LabelRule = serde_json::from_str(
+            r#"{"uuid": "c", "label": "reply", "rule": {"or_patterns": [{"pattern": "\\w+@\\w+"}]}}"#,
+        )
+        .unwrap();
+        rulebox.add_rule(rule).unwrap();
+        assert_eq!(rulebox.distinct_patterns(), 2);
+        let compiled = |i: usize| {
+            rulebox.rules[i].rule.or_patterns[0]
+                .compiled
+                .clone()
+                .unwrap()
+        };
+        assert!(Arc::ptr_eq(&compiled(0), &compiled(2)));
+
+        // The replaced rule still holds "phone" until it's dropped
+        let rule: LabelRule = serde_json::from_str(
+            r#"{"uuid": "b", "label": "contact", "rule": {"or_patterns": [{"pattern": "mobile"}]}}"#,
+        )
+        .unwrap();
+        let old = rulebox.update_rule("b", rule).unwrap();
+        assert_eq!(rulebox.distinct_patterns(), 3);
+        drop(old);
+        rulebox.remove_rule("c");
+        assert_eq!(rulebox.distinct_patterns(), 2);
+        assert_eq!(rulebox.assign_labels("mobile"), vec!["contact".to_string()]);
+    }
+}
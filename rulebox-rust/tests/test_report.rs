@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    #[test]
+    fn test_report_markdown_groups_by_label() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {
+                    "uuid": "greet-1",
+                    "label": "greeting",
+                    "proto_text": "hello there",
+                    "rule": {"or_patterns": [{"pattern": "\\bhello\\b", "flags": ["i"]}]}
+                },
+                {
+                    "uuid": "urgent-1",
+                    "label": "urgent",
+                    "active": false,
+                    "proto_text": "whenever",
+                    "rule": {
+                        "and_patterns": [{"pattern": "urgent"}, {"pattern": "now"}],
+                        "not_patterns": [{"pattern": "`test`"}]
+                    }
+                },
+                {
+                    "uuid": "greet-2",
+                    "label": "greeting",
+                    "rule": {"or_patterns": [{"pattern": "\\bhi\\b"}]}
+                }
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let report = rulebox.report_markdown();
+        assert!(report.starts_with("# RuleBox report\n"));
+        assert!(report.contains("3 rules (2 active) across 2 labels."));
+
+        let greeting = report.find("## greeting").unwrap();
+        let urgent = report.find("## urgent").unwrap();
+        assert!(greeting < urgent);
+        assert!(report.find("`greet-2`").unwrap() < urgent);
+
+        assert!(report.contains("- Example: `hello there` (passes self-test)"));
+        assert!(report.contains("- Status: **inactive**"));
+        assert!(report.contains("- Example: `whenever` (**fails self-test**)"));
+        assert!(report.contains("  - `\\bhello\\b` (flags: i)"));
+        assert!(report.contains("- All of:\n  - `urgent`\n  - `now`"));
+        assert!(report.contains("- None of:\n  - `` `test` ``"));
+    }
+
+    #[test]
+    fn test_report_markdown_uses_schedules_and_inline_examples() {
+        let mut rulebox = RuleBox::from_json(
+            r#"[
+                {
+                    "uuid": "ended",
+                    "label": "campaign",
+                    "active_until": "2000-01-01T00:00:00Z",
+                    "rule": {"or_patterns": [{"pattern": "sale"}]}
+                },
+                {
+                    "uuid": "greet",
+                    "label": "greeting",
+                    "should_match": ["hello"],
+                    "rule": {"or_patterns": [{"pattern": "hello"}]}
+                }
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        rulebox.rules[1]
+            .should_not_match
+            .push("hello again".to_string());
+
+        let report = rulebox.report_markdown();
+        assert!(report.contains("2 rules (1 active) across 2 labels."));
+        assert!(report.contains(
+            "### Rule `ended`\n\n- Status: **inactive**\n- Active from - until 2000-01-01T00:00:00Z"
+        ));
+        assert!(report.contains("- **Fails self-test**: should not match `hello again`"));
+        assert!(!report.contains("should match `hello`"));
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+    use rulebox_rust::*;
+    use std::fs;
+
+    const RULES: &str = r#"[
+        {"label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}}
+    ]"#;
+
+    #[test]
+    fn test_fingerprint_ignores_generated_uuids() {
+        let first = RuleBox::from_json(RULES).unwrap();
+        let second = RuleBox::from_json(RULES).unwrap();
+        assert_ne!(first.rules[0].uuid, second.rules[0].uuid);
+        assert_eq!(first.fingerprint(), second.fingerprint());
+
+        let other = RuleBox::from_json(&RULES.replace("hello", "goodbye")).unwrap();
+        assert_ne!(first.fingerprint(), other.fingerprint());
+    }
+
+    #[test]
+    fn test_check_many_cached_reuses_and_invalidates() {
+        let path = std::env::temp_dir().join(format!("rulebox-cache-{}.json", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        let _ = fs::remove_file(&path);
+
+        let texts = vec!["hello there".to_string(), "nothing".to_string()];
+        let rulebox = RuleBox::from_json(RULES).unwrap();
+
+        let results = rulebox.check_many_cached(&texts, path_str).unwrap();
+        assert_eq!(results, rulebox.check_many(&texts));
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains(&rulebox.fingerprint()));
+
+        // A second run with the same rules leaves the file untouched
+        let again = rulebox.check_many_cached(&texts, path_str).unwrap();
+        assert_eq!(again, results);
+        assert_eq!(fs::read_to_string(&path).unwrap(), written);
+
+        // Changed rules invalidate the stored results
+        let changed = RuleBox::from_json(&RULES.replace("hello", "nothing")).unwrap();
+        let results = changed.check_many_cached(&texts, path_str).unwrap();
+        assert!(results[0].is_empty());
+        assert!(results[1].contains("greeting"));
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains(&changed.fingerprint()));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_check_many_cached_compares_texts_not_just_hashes() {
+        let path = std::env::temp_dir().join(format!(
+            "rulebox-cache-collision-{}.json",
+            std::process::id()
+        ));
+        let path_str = path.to_str().unwrap();
+        let _ = fs::remove_file(&path);
+
+        let texts = vec!["hello there".to_string()];
+        let rulebox = RuleBox::from_json(RULES).unwrap();
+        rulebox.check_many_cached(&texts, path_str).unwrap();
+
+        // Pretend another text with the same hash was cached under it
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains("hello there") && written.contains("greeting"));
+        let collided = written
+            .replace("hello there", "another text")
+            .replace("greeting", "stale");
+        fs::write(&path, collided).unwrap();
+
+        let results = rulebox.check_many_cached(&texts, path_str).unwrap();
+        assert_eq!(results, rulebox.check_many(&texts));
+        let rewritten = fs::read_to_string(&path).unwrap();
+        assert!(rewritten.contains("another text") && rewritten.contains("hello there"));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
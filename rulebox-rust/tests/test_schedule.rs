@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::chrono::{DateTime, Utc};
+    use rulebox_rust::*;
+
+    const RULES: &str = r#"[
+        {"uuid": "sale", "label": "spring_sale",
+         "active_from": "2024-03-01T00:00:00Z", "active_until": "2024-04-01T00:00:00Z",
+         "rule": {"or_patterns": [{"pattern": "(?i)discount"}]}},
+        {"uuid": "always", "label": "offer", "rule": {"or_patterns": [{"pattern": "(?i)discount"}]}}
+    ]"#;
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        timestamp.parse().unwrap()
+    }
+
+    fn labels_at(timestamp: &str, text: &str) -> Vec<String> {
+        RuleBox::from_json(RULES)
+            .unwrap()
+            .with_clock(Clock::fixed(at(timestamp)))
+            .assign_labels(text)
+    }
+
+    #[test]
+    fn test_rule_only_assigns_inside_its_window() {
+        assert_eq!(
+            labels_at("2024-02-29T23:59:59Z", "Discount!"),
+            vec!["offer".to_string()]
+        );
+        assert_eq!(
+            labels_at("2024-03-01T00:00:00Z", "Discount!"),
+            vec!["spring_sale".to_string(), "offer".to_string()]
+        );
+        // `active_until` is exclusive
+        assert_eq!(
+            labels_at("2024-04-01T00:00:00Z", "Discount!"),
+            vec!["offer".to_string()]
+        );
+
+        let rulebox = RuleBox::from_json(RULES)
+            .unwrap()
+            .with_clock(Clock::fixed(at("2024-03-15T12:00:00Z")));
+        assert!(rulebox.check("discount").has_label("spring_sale"));
+        assert!(rulebox.rules[0].is_active());
+        assert!(!rulebox.rules[0].is_active_at(at("2025-01-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_fingerprint_follows_the_window() {
+        let fingerprint = |timestamp: &str| {
+            RuleBox::from_json(RULES)
+                .unwrap()
+                .with_clock(Clock::fixed(at(timestamp)))
+                .fingerprint()
+        };
+        // Cached results from before the sale mustn't be reused during it
+        assert_ne!(
+            fingerprint("2024-02-01T00:00:00Z"),
+            fingerprint("2024-03-15T00:00:00Z")
+        );
+        assert_eq!(
+            fingerprint("2024-02-01T00:00:00Z"),
+            fingerprint("2024-05-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_clock_reaches_added_rules_and_round_trips() {
+        let mut rulebox = RuleBox::from_json("[]")
+            .unwrap()
+            .with_clock(Clock::fixed(at("2030-01-01T00:00:00Z")));
+        let expired: LabelRule = serde_json::from_str(
+            r#"{"label": "old", "active_until": "2025-01-01T00:00:00Z",
+                "rule": {"or_patterns": [{"pattern": "x"}]}}"#,
+        )
+        .unwrap();
+        rulebox.add_rule(expired).unwrap();
+        assert!(rulebox.assign_labels("x").is_empty());
+
+        let json = rulebox.to_json_string().unwrap();
+        assert!(json.contains(r#""active_until": "2025-01-01T00:00:00Z""#));
+        assert!(!json.contains("active_from"));
+    }
+
+    #[test]
+    fn test_empty_window_fails_to_load() {
+        let err = RuleBox::from_json(
+            r#"[{"label": "x", "active_from": "2024-05-01T00:00:00Z", "active_until": "2024-05-01T00:00:00Z",
+                 "rule": {"or_patterns": [{"pattern": "x"}]}}]"#,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("active_until must be after active_from"));
+    }
+}
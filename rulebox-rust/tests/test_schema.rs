@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    const TYPO: &str = r#"{"rules": [
+        {"uuid": "r1", "label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}},
+        {"uuid": "r2", "label": "farewell", "rule": {"or_pattern": [{"pattern": "bye"}]}, "prioirty": 2}
+    ], "defualt_flags": ["i"]}"#;
+
+    #[test]
+    fn test_lenient_load_ignores_unknown_fields() {
+        // The misspelt or_pattern leaves rule r2 with nothing to match on
+        let rulebox = RuleBox::from_json(TYPO).unwrap();
+        assert!(rulebox.rules[1].rule.or_patterns.is_empty());
+    }
+
+    #[test]
+    fn test_strict_load_reports_every_unknown_field() {
+        let err = RuleBox::from_json_strict(TYPO).unwrap_err();
+        let RuleBoxError::Schema(problems) = &err else {
+            panic!("expected schema problems, got {:?}", err);
+        };
+        let paths: Vec<&str> = problems.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "$.rules[1].rule.or_pattern",
+                "$.rules[1].prioirty",
+                "$.defualt_flags"
+            ]
+        );
+        assert!(err
+            .to_string()
+            .contains("$.rules[1].rule.or_pattern: unknown field"));
+    }
+
+    #[test]
+    fn test_strict_load_reports_path_of_bad_value() {
+        let json = r#"[
+            {"uuid": "r1", "labl": "x", "label": "greeting", "rule": {"or_patterns": [{"pattern": 5}]}}
+        ]"#;
+        let Err(RuleBoxError::Schema(problems)) = RuleBox::from_json_strict(json) else {
+            panic!("expected schema problems");
+        };
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].path, "$[0].labl");
+        assert_eq!(problems[1].path, "$[0].rule.or_patterns[0].pattern");
+        assert!(problems[1].message.contains("invalid type"));
+    }
+
+    #[test]
+    fn test_strict_load_accepts_valid_file() {
+        let json = r#"{"default_flags": ["i"], "rules": [
+            {"uuid": "r1", "label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]},
+             "positive_examples": ["Hello"]}
+        ]}"#;
+        let rulebox = RuleBox::from_json_strict(json).unwrap();
+        assert_eq!(rulebox.assign_labels("HELLO there"), vec!["greeting"]);
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_schema_describes_rule_files() {
+        let schema = RuleBox::schema();
+        assert_eq!(schema["title"], "RuleBox");
+        let rule = &schema["definitions"]["LabelRule"];
+        assert!(rule["properties"]["rule"].is_object());
+        let required: Vec<&str> = rule["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        assert_eq!(required, vec!["label", "rule"]);
+        assert!(schema["definitions"]["Rule"]["properties"]["or_patterns"].is_object());
+    }
+}
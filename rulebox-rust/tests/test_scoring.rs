@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    const RULES: &str = r#"[
+        {"label": "complaint", "weight": 2.0, "rule": {"or_patterns": [{"pattern": "refund"}]}},
+        {"label": "complaint", "weight": 1.5, "rule": {"or_patterns": [{"pattern": "angry"}]}},
+        {"label": "complaint", "weight": -2.0, "rule": {"or_patterns": [{"pattern": "just asking"}]}},
+        {"label": "question", "rule": {"or_patterns": [{"pattern": "\\?"}]}}
+    ]"#;
+
+    #[test]
+    fn test_negative_weights_net_out() {
+        let rulebox = RuleBox::from_json(RULES).expect("Failed to load rules");
+
+        let scores = rulebox.score("I want a refund, and I'm angry?");
+        assert_eq!(scores["complaint"], 3.5);
+        assert_eq!(scores["question"], 1.0);
+
+        let scores = rulebox.score("just asking about a refund");
+        assert_eq!(scores["complaint"], 0.0);
+        assert!(!scores.contains_key("question"));
+    }
+
+    #[test]
+    fn test_negative_scores_clamp_unless_allowed() {
+        let rulebox = RuleBox::from_json(RULES).expect("Failed to load rules");
+        assert_eq!(rulebox.score("just asking")["complaint"], 0.0);
+
+        let rulebox = rulebox.with_negative_scores(true);
+        assert_eq!(rulebox.score("just asking")["complaint"], -2.0);
+
+        let from_file = RuleBox::from_json(&format!(
+            r#"{{"allow_negative_scores": true, "rules": {}}}"#,
+            RULES
+        ))
+        .expect("Failed to load rules");
+        assert_eq!(from_file.score("just asking")["complaint"], -2.0);
+    }
+
+    const WEAK_SIGNALS: &str = r#"{"score_thresholds": {"complaint": 2.5}, "rules": [
+        {"label": "complaint", "weight": 2.0, "rule": {"or_patterns": [{"pattern": "refund"}]}},
+        {"label": "complaint", "weight": 1.0, "rule": {"or_patterns": [{"pattern": "angry"}]}},
+        {"label": "complaint", "weight": 0.5, "rule": {"or_patterns": [{"pattern": "again"}]}},
+        {"label": "question", "rule": {"or_patterns": [{"pattern": "\\?"}]}}
+    ]}"#;
+
+    #[test]
+    fn test_score_threshold_labels() {
+        let rulebox = RuleBox::from_json(WEAK_SIGNALS).expect("Failed to load rules");
+        let texts: Vec<String> = ["refund?", "refund, angry", "refund again", "angry again"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let expected = vec![
+            vec!["question".to_string()],
+            vec!["complaint".to_string()],
+            vec![],
+            vec![],
+        ];
+        assert_eq!(rulebox.assign_labels_vector(&texts), expected);
+        let checked: Vec<Vec<String>> = rulebox
+            .check_many(&texts)
+            .into_iter()
+            .map(|labels| labels.into_iter().collect())
+            .collect();
+        assert_eq!(checked, expected);
+
+        // Thresholds round-trip and can also be set in code
+        let reloaded = RuleBox::from_json(&rulebox.to_json_string().unwrap()).unwrap();
+        assert_eq!(reloaded.score_thresholds["complaint"], 2.5);
+        let lowered = reloaded.with_score_threshold("complaint", 2.0);
+        assert_eq!(lowered.assign_labels("refund again"), vec!["complaint"]);
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::collections::HashSet;
+
+    fn shadow_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"uuid": "live", "label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}]}},
+                {"uuid": "canary", "label": "pet", "shadow": true, "priority": 10, "weight": 5.0,
+                 "rule": {"or_patterns": [{"pattern": "cat|dog"}]}},
+                {"uuid": "retired", "label": "old", "shadow": true, "active": false,
+                 "rule": {"or_patterns": [{"pattern": "cat"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_shadow_rules_never_assign_labels() {
+        let rulebox = shadow_box();
+        assert_eq!(rulebox.assign_labels("cat"), vec!["cat".to_string()]);
+        assert!(rulebox.assign_labels("dog").is_empty());
+        assert_eq!(
+            rulebox.assign_labels_vector(&["cat and dog".to_string()]),
+            vec![vec!["cat"]]
+        );
+        assert_eq!(rulebox.classify("cat"), Some("cat".to_string()));
+        assert!(!rulebox.score("cat").contains_key("pet"));
+        assert!(rulebox.all_matches("dog").is_empty());
+    }
+
+    #[test]
+    fn test_shadow_hits_reports_matching_shadow_rules() {
+        let rulebox = shadow_box();
+        assert_eq!(
+            rulebox.shadow_hits("a cat"),
+            vec![("pet".to_string(), "canary".to_string())]
+        );
+        assert!(rulebox.shadow_hits("fish").is_empty());
+        assert!(rulebox.report_markdown().contains("- Status: **shadow**"));
+    }
+
+    #[test]
+    fn test_check_reports_shadow_labels() {
+        let rulebox = shadow_box();
+        let labeled = rulebox.check("cat");
+        assert_eq!(labeled.labels(), &HashSet::from(["cat".to_string()]));
+        assert_eq!(labeled.shadow_labels(), &HashSet::from(["pet".to_string()]));
+        assert!(rulebox.check("fish").shadow_labels().is_empty());
+    }
+
+    #[test]
+    fn test_shadow_inactive_evaluates_inactive_rules() {
+        let rulebox = shadow_box().with_shadow_inactive(true);
+        let labeled = rulebox.check("cat");
+        assert_eq!(labeled.labels(), &HashSet::from(["cat".to_string()]));
+        assert_eq!(
+            labeled.shadow_labels(),
+            &HashSet::from(["pet".to_string(), "old".to_string()])
+        );
+        assert_eq!(rulebox.assign_labels("cat"), vec!["cat".to_string()]);
+        assert_eq!(
+            rulebox.shadow_hits("cat"),
+            vec![
+                ("pet".to_string(), "canary".to_string()),
+                ("old".to_string(), "retired".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shadow_inactive_round_trips_through_the_file() {
+        let rulebox = RuleBox::from_json(
+            r#"{"shadow_inactive": true, "rules": [
+                {"uuid": "draft", "label": "dog", "active": false,
+                 "rule": {"or_patterns": [{"pattern": "dog"}]}}
+            ]}"#,
+        )
+        .expect("Failed to load rules");
+        assert!(rulebox.check("dog").labels().is_empty());
+        assert_eq!(
+            rulebox.check("dog").shadow_labels(),
+            &HashSet::from(["dog".to_string()])
+        );
+        let json = rulebox.to_json_string().unwrap();
+        assert!(RuleBox::from_json(&json).unwrap().shadow_inactive);
+    }
+}
@@ -0,0 +1,277 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::collections::BTreeSet;
+
+    fn labels(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_segment_splits_at_every_boundary() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "greeting", "rule": {"or_patterns": [{"pattern": "hello world"}]}},
+                {"label": "planet", "rule": {"or_patterns": [{"pattern": "world"}]}},
+                {"label": "number", "rule": {"or_patterns": [{"pattern": "555-12 34"}]}},
+                {"label": "phone", "rule": {"or_patterns": [{"pattern": "5551234"}], "digit_normalize": true}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let text = "hello world, call 555-12 34";
+        let segments = rulebox.segment(text);
+        let summary: Vec<(&str, BTreeSet<String>)> = segments
+            .iter()
+            .map(|s| (&text[s.start..s.end], s.labels.clone()))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("hello ", labels(&["greeting"])),
+                ("world", labels(&["greeting", "planet"])),
+                (", call ", labels(&[])),
+                ("555-12 34", labels(&["number", "phone"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_segment_ignores_vetoed_and_inactive_rules() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "vetoed", "rule": {"or_patterns": [{"pattern": "cat"}], "not_patterns": [{"pattern": "dog"}]}},
+                {"label": "inactive", "active": false, "rule": {"or_patterns": [{"pattern": "cat"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let segments = rulebox.segment("cat and dog");
+        assert_eq!(segments.len(), 1);
+        assert_eq!((segments[0].start, segments[0].end), (0, 11));
+        assert!(segments[0].labels.is_empty());
+        assert!(rulebox.segment("").is_empty());
+    }
+
+    #[test]
+    fn test_all_matches_in_document_order() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"uuid": "r1", "label": "animal", "rule": {"or_patterns": [{"pattern": "cat"}, {"pattern": "dog"}]}},
+                {"uuid": "r2", "label": "pet", "rule": {"and_patterns": [{"pattern": "dog"}, {"pattern": "walk"}]}},
+                {"uuid": "r3", "label": "vetoed", "rule": {"or_patterns": [{"pattern": "cat"}], "not_patterns": [{"pattern": "walk"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let matches = rulebox.all_matches("dog walk, cat, dog");
+        let summary: Vec<(&str, &str, usize, usize)> = matches
+            .iter()
+            .map(|m| (m.uuid.as_str(), m.pattern.as_str(), m.start, m.end))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("r1", "dog", 0, 3),
+                ("r2", "dog", 0, 3),
+                ("r2", "walk", 4, 8),
+                ("r1", "cat", 10, 13),
+                ("r1", "dog", 15, 18),
+                ("r2", "dog", 15, 18),
+            ]
+        );
+        assert_eq!(matches[2].label, "pet");
+    }
+
+    #[test]
+    fn test_segment_overlap_policies() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "place", "rule": {"or_patterns": [{"pattern": "New York City"}]}},
+                {"label": "state", "priority": 5, "rule": {"or_patterns": [{"pattern": "New York"}]}},
+                {"label": "city", "priority": 5, "rule": {"or_patterns": [{"pattern": "York City"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+        let text = "in New York City";
+        let layer = |policy| -> Vec<(String, Vec<String>)> {
+            rulebox
+                .segment_with(text, policy)
+                .into_iter()
+                .map(|s| {
+                    (
+                        text[s.start..s.end].to_string(),
+                        s.labels.into_iter().collect(),
+                    )
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            rulebox.segment_with(text, OverlapPolicy::KeepAll),
+            rulebox.segment(text)
+        );
+        assert_eq!(rulebox.segment(text).len(), 4);
+        // Both priority-5 rules overlap; the earlier rule wins the tie
+        assert_eq!(
+            layer(OverlapPolicy::HighestPriority),
+            vec![
+                ("in ".to_string(), vec![]),
+                ("New York".to_string(), vec!["state".to_string()]),
+                (" City".to_string(), vec![]),
+            ]
+        );
+        assert_eq!(
+            layer(OverlapPolicy::Longest),
+            vec![
+                ("in ".to_string(), vec![]),
+                ("New York City".to_string(), vec!["place".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_label_by_position() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "billing", "rule": {"or_patterns": [{"pattern": "invoice"}, {"pattern": "refund"}]}},
+                {"label": "support", "rule": {"or_patterns": [{"pattern": "help"}]}},
+                {"label": "urgent_help", "priority": 3, "rule": {"or_patterns": [{"pattern": "help"}]}},
+                {"label": "off", "active": false, "rule": {"or_patterns": [{"pattern": "I"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        assert_eq!(
+            rulebox.first_label_by_position("I need a refund for this invoice"),
+            Some(("billing".to_string(), 9))
+        );
+        assert_eq!(
+            rulebox.first_label_by_position("I need help with an invoice"),
+            Some(("urgent_help".to_string(), 7))
+        );
+        assert_eq!(rulebox.first_label_by_position("nothing here"), None);
+    }
+
+    #[test]
+    fn test_check_detailed_reports_spans_per_label() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "rules": [
+                    {"uuid": "e1", "label": "email", "rule": {"or_patterns": [{"pattern": "\\w+@\\w+\\.com"}]}},
+                    {"uuid": "g1", "label": "greeting", "rule": {"or_patterns": [{"pattern": "héllo"}, {"pattern": "hi"}]}},
+                    {"uuid": "g2", "label": "greeting", "rule": {"and_patterns": [{"pattern": "👋"}]}}
+                ],
+                "count_rules": [{"if_labels_count_at_least": 2, "then": "busy"}]
+            }"#,
+        )
+        .expect("Failed to load rules");
+
+        let text = "👋 héllo, mail bob@example.com";
+        let details = rulebox.check_detailed(text);
+        let labels: Vec<&String> = details.keys().collect();
+        assert_eq!(labels, vec!["busy", "email", "greeting"]);
+        assert!(details["busy"].spans.is_empty());
+
+        let greeting: Vec<(&str, &str, usize, usize, usize, usize)> = details["greeting"]
+            .spans
+            .iter()
+            .map(|s| {
+                (
+                    s.uuid.as_str(),
+                    s.text.as_str(),
+                    s.start,
+                    s.end,
+                    s.char_start,
+                    s.char_end,
+                )
+            })
+            .collect();
+        assert_eq!(
+            greeting,
+            vec![("g2", "👋", 0, 4, 0, 1), ("g1", "héllo", 5, 11, 2, 7)]
+        );
+        let email = &details["email"].spans[0];
+        assert_eq!(
+            (email.text.as_str(), email.char_start, email.char_end),
+            ("bob@example.com", 14, 29)
+        );
+        assert_eq!(email.pattern, "\\w+@\\w+\\.com");
+    }
+
+    #[test]
+    fn test_check_detailed_captures() {
+        let rulebox = RuleBox::from_json(
+            r#"[
+                {"label": "contact", "captures": ["user", "phone"], "rule": {"digit_normalize": true, "or_patterns": [
+                    {"pattern": "(?P<user>\\w+)@example\\.com"},
+                    {"pattern": "tel:(?P<phone>\\d{7})"}
+                ]}},
+                {"label": "plain", "rule": {"or_patterns": [{"pattern": "(?P<word>see)"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules");
+
+        let details =
+            rulebox.check_detailed("see bob@example.com, amy@example.com or tel:555-1234");
+        let contact = &details["contact"];
+        assert_eq!(contact.captures["user"], vec!["bob", "amy"]);
+        // Digit-normalized matches capture the original text
+        assert_eq!(contact.captures["phone"], vec!["555-1234"]);
+        assert_eq!(contact.spans[0].captures.len(), 1);
+        assert_eq!(contact.spans[0].captures["user"], "bob");
+        // Groups the rule doesn't list aren't reported
+        assert!(details["plain"].captures.is_empty());
+        assert!(details["plain"].spans[0].captures.is_empty());
+    }
+
+    #[test]
+    fn test_spans_only_cover_labels_check_assigns() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "implies": {"child": ["parent"]},
+                "score_thresholds": {"weak": 1.5},
+                "rules": [
+                    {"uuid": "c", "label": "child", "rule": {"or_patterns": [{"pattern": "email"}]}},
+                    {"uuid": "ham", "label": "ham", "group": "verdict", "rule": {"or_patterns": [{"pattern": "footer"}]}},
+                    {"uuid": "spam", "label": "spam", "group": "verdict", "priority": 5, "rule": {"or_patterns": [{"pattern": "email"}]}},
+                    {"uuid": "w", "label": "weak", "rule": {"or_patterns": [{"pattern": "footer"}]}},
+                    {"uuid": "pd", "label": "pd", "rule": {"or_patterns": [{"pattern": "email"}]}},
+                    {"uuid": "no-pd", "label": "pd", "kind": "suppress", "rule": {"or_patterns": [{"pattern": "footer"}]}},
+                    {"uuid": "later", "label": "later", "stage": 1, "requires_labels": ["ham"], "rule": {"or_patterns": [{"pattern": "email"}]}}
+                ]
+            }"#,
+        )
+        .expect("Failed to load rules");
+
+        let text = "email in footer";
+        let mut checked: Vec<String> = rulebox.check(text).labels().iter().cloned().collect();
+        checked.sort();
+        assert_eq!(checked, vec!["child", "parent", "spam"]);
+
+        let details = rulebox.check_detailed(text);
+        assert_eq!(details.keys().cloned().collect::<Vec<_>>(), checked);
+        // The implied parent is highlighted where its child matched
+        let uuids = |label: &str| -> Vec<String> {
+            details[label]
+                .spans
+                .iter()
+                .map(|s| s.uuid.clone())
+                .collect()
+        };
+        assert_eq!(uuids("child"), vec!["c"]);
+        assert_eq!(uuids("parent"), vec!["c"]);
+        assert_eq!(uuids("spam"), vec!["spam"]);
+
+        let matched: Vec<String> = rulebox
+            .all_matches(text)
+            .into_iter()
+            .map(|m| m.uuid)
+            .collect();
+        assert_eq!(matched, vec!["c", "spam"]);
+        let segments = rulebox.segment(text);
+        assert_eq!(segments[0].labels, labels(&["child", "spam"]));
+        assert!(segments[1..].iter().all(|s| s.labels.is_empty()));
+    }
+}
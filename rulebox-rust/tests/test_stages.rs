@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::collections::HashMap;
+
+    fn privacy_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"uuid": "e", "label": "contains_email", "rule": {"or_patterns": [{"pattern": "\\w+@\\w+\\.\\w+"}]}},
+                {"uuid": "n", "label": "newsletter", "rule": {"or_patterns": [{"pattern": "(?i)unsubscribe"}]}},
+                {"uuid": "p", "label": "privacy_risk", "stage": 1,
+                 "requires_labels": ["contains_email"], "excludes_labels": ["newsletter"],
+                 "rule": {"or_patterns": [{"pattern": "."}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    fn sorted(mut labels: Vec<String>) -> Vec<String> {
+        labels.sort();
+        labels
+    }
+
+    #[test]
+    fn test_later_stage_sees_earlier_labels() {
+        let rulebox = privacy_box();
+        assert!(rulebox.is_staged());
+        assert_eq!(
+            sorted(rulebox.assign_labels("Write to bob@example.com")),
+            vec!["contains_email", "privacy_risk"]
+        );
+        assert_eq!(
+            sorted(rulebox.assign_labels("bob@example.com - unsubscribe here")),
+            vec!["contains_email", "newsletter"]
+        );
+        assert!(rulebox.assign_labels("No address here").is_empty());
+
+        let labeled = rulebox.check("Write to bob@example.com");
+        assert!(labeled.has_label("privacy_risk"));
+        assert!(rulebox.check_for("Write to bob@example.com", &["privacy_risk"]));
+
+        let record = HashMap::from([("body".to_string(), "bob@example.com".to_string())]);
+        assert!(rulebox.check_record(&record).has_label("privacy_risk"));
+    }
+
+    #[test]
+    fn test_round_trip_and_first_stage_conditions() {
+        let json = privacy_box().to_json_string().unwrap();
+        assert!(json.contains(r#""requires_labels": ["#));
+        let reloaded = RuleBox::from_json(&json).unwrap();
+        assert_eq!(reloaded.rules[2].stage, 1);
+        assert_eq!(reloaded.rules[2].excludes_labels, vec!["newsletter"]);
+        assert_eq!(json.matches(r#""stage""#).count(), 1);
+
+        let err = RuleBox::from_json(
+            r#"[{"label": "x", "requires_labels": ["y"], "rule": {"or_patterns": [{"pattern": "x"}]}}]"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("need a stage after 0"));
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    const RULES: &str = r#"[
+        {"label": "greeting", "uuid": "greet", "rule": {
+            "or_patterns": [{"pattern": "hello"}],
+            "not_patterns": [{"pattern": "spam"}, {"pattern": "junk"}, {"pattern": "never"}]
+        }},
+        {"label": "dead", "uuid": "dead", "rule": {"or_patterns": [{"pattern": "zzz"}]}}
+    ]"#;
+
+    fn texts() -> Vec<String> {
+        ["hello", "hello spam", "hello spam junk", "bye"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_stats_count_matches_and_vetoes() {
+        let rulebox = RuleBox::from_json(RULES).unwrap().with_stats();
+        rulebox.assign_labels_vector(&texts());
+
+        let stats = rulebox.stats().expect("stats enabled");
+        assert_eq!(stats.len(), 2);
+        let greet = &stats[0];
+        assert_eq!(greet.uuid, "greet");
+        assert_eq!(greet.evaluations, 4);
+        assert_eq!(greet.matches, 1);
+        assert_eq!(greet.vetoes, 2);
+        assert_eq!(
+            greet.not_pattern_vetoes,
+            vec![
+                ("spam".to_string(), 2),
+                ("junk".to_string(), 1),
+                ("never".to_string(), 0)
+            ]
+        );
+        assert_eq!(stats[1].label, "dead");
+        assert_eq!(stats[1].matches, 0);
+
+        // `check` counts too, and reset zeroes everything
+        rulebox.check("hello");
+        assert_eq!(rulebox.stats().unwrap()[0].matches, 2);
+        rulebox.reset_stats();
+        let stats = rulebox.stats().unwrap();
+        assert!(stats.iter().all(|s| s.evaluations == 0 && s.matches == 0));
+        assert_eq!(stats[0].not_pattern_vetoes.len(), 3);
+    }
+
+    #[test]
+    fn test_stats_off_by_default() {
+        let rulebox = RuleBox::from_json(RULES).unwrap();
+        rulebox.assign_labels_vector(&texts());
+        assert!(rulebox.stats().is_none());
+    }
+}
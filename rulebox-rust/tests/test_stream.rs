@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::io::Cursor;
+
+    fn pets_box() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"label": "dog", "rule": {"or_patterns": [{"pattern": "\\bdog\\b"}]}},
+                {"label": "cat", "rule": {"or_patterns": [{"pattern": "\\bcat\\b"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    #[test]
+    fn test_label_stream_writes_a_line_per_text() {
+        let input = concat!(
+            "\"a dog and a cat\"\n",
+            "\n",
+            "{\"id\": 7, \"text\": \"just a cat\"}\n",
+            "\"nothing\"\n",
+        );
+        let mut output = Vec::new();
+        let count = pets_box()
+            .label_stream(Cursor::new(input), &mut output)
+            .expect("Failed to label stream");
+        assert_eq!(count, 3);
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                serde_json::json!({"text": "a dog and a cat", "labels": ["cat", "dog"]}),
+                serde_json::json!({"id": 7, "text": "just a cat", "labels": ["cat"]}),
+                serde_json::json!({"text": "nothing", "labels": []}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_label_stream_reports_bad_lines() {
+        let rulebox = pets_box();
+        for input in [
+            "\"ok\"\nnot json\n",
+            "\"ok\"\n{\"body\": \"x\"}\n",
+            "\"ok\"\n42\n",
+        ] {
+            let err = rulebox
+                .label_stream(Cursor::new(input), Vec::new())
+                .unwrap_err();
+            assert!(
+                matches!(err, RuleBoxError::InvalidLine { line: 2, .. }),
+                "{err}"
+            );
+        }
+    }
+}
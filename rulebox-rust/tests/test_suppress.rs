@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::collections::HashSet;
+
+    fn rulebox() -> RuleBox {
+        RuleBox::from_json(
+            r#"{
+                "implies": {"personal_data": ["sensitive"]},
+                "rules": [
+                    {"uuid": "email", "label": "personal_data", "rule": {"or_patterns": [{"pattern": "[a-z]+@[a-z]+\\.com"}]}},
+                    {"uuid": "greeting", "label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}},
+                    {"uuid": "footer", "label": "personal_data", "kind": "suppress", "rule": {"or_patterns": [{"pattern": "unsubscribe"}]}}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn labels(labels: &[&str]) -> HashSet<String> {
+        labels.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_suppressor_removes_label() {
+        let rulebox = rulebox();
+        assert_eq!(
+            rulebox.check("hello, write to ann@example.com").labels(),
+            &labels(&["greeting", "personal_data", "sensitive"])
+        );
+        // The footer suppresses personal_data, but not the label it implied
+        assert_eq!(
+            rulebox
+                .check("hello, news@example.com, unsubscribe here")
+                .labels(),
+            &labels(&["greeting", "sensitive"])
+        );
+        // A suppressor never assigns its own label
+        assert_eq!(rulebox.check("unsubscribe").labels(), &labels(&[]));
+    }
+
+    #[test]
+    fn test_suppression_applies_to_other_paths() {
+        let rulebox = rulebox();
+        let text = "hello news@example.com unsubscribe";
+        assert_eq!(
+            rulebox.assign_labels(text),
+            vec!["greeting".to_string(), "sensitive".to_string()]
+        );
+        assert_eq!(
+            rulebox.assign_labels_vector(&[text.to_string()]),
+            vec![vec!["greeting".to_string(), "sensitive".to_string()]]
+        );
+        assert_eq!(
+            rulebox.check_topk(text, 1).labels(),
+            &labels(&["sensitive"])
+        );
+        assert_eq!(rulebox.classify("news@example.com unsubscribe"), None);
+        let document = Document::new().with_field("body", text);
+        assert_eq!(
+            rulebox.check_document(&document).labels(),
+            &labels(&["greeting", "sensitive"])
+        );
+    }
+
+    #[test]
+    fn test_inactive_suppressor_is_ignored_and_kind_round_trips() {
+        let mut rulebox = rulebox();
+        let json = serde_json::to_string(&rulebox).unwrap();
+        assert!(json.contains(r#""kind":"suppress""#));
+        assert_eq!(json.matches(r#""kind""#).count(), 1);
+
+        rulebox.rules[2].active = false;
+        assert!(rulebox
+            .check("news@example.com unsubscribe")
+            .labels()
+            .contains("personal_data"));
+    }
+}
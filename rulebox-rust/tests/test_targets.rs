@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+
+    #[test]
+    fn test_check_for_matches_assign_labels() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "implies": {"spam.pharma": ["spam"]},
+                "rules": [
+                    {"label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}},
+                    {"label": "spam.pharma", "rule": {"or_patterns": [{"pattern": "viagra"}]}},
+                    {"label": "spam", "rule": {"or_patterns": [{"pattern": "free money"}]}},
+                    {"label": "spam", "kind": "suppress", "rule": {"or_patterns": [{"pattern": "unsubscribe"}]}},
+                    {"label": "spam", "active": false, "rule": {"or_patterns": [{"pattern": "hello"}]}}
+                ]
+            }"#,
+        )
+        .expect("Failed to load rules");
+
+        assert!(rulebox.has_label("free money", "spam"));
+        assert!(rulebox.has_label("cheap viagra", "spam"));
+        assert!(rulebox.has_label("cheap viagra", "spam.pharma"));
+        assert!(!rulebox.has_label("hello", "spam"));
+        assert!(!rulebox.has_label("free money", "spam.pharma"));
+        assert!(rulebox.check_for("hello", &["spam", "greeting"]));
+        assert!(!rulebox.check_for("hello", &[]));
+
+        // The suppressor removes `spam` but not its child label
+        assert!(!rulebox.has_label("free money, unsubscribe", "spam"));
+        assert!(!rulebox.has_label("viagra, unsubscribe", "spam"));
+        assert!(rulebox.has_label("viagra, unsubscribe", "spam.pharma"));
+    }
+
+    #[test]
+    fn test_check_for_labels_from_the_post_pass() {
+        let rulebox = RuleBox::from_json(
+            r#"{
+                "score_thresholds": {"complaint": 1.5},
+                "count_rules": [{"if_labels_count_at_least": 2, "then": "busy"}],
+                "rules": [
+                    {"label": "complaint", "rule": {"or_patterns": [{"pattern": "refund"}]}},
+                    {"label": "complaint", "rule": {"or_patterns": [{"pattern": "angry"}]}},
+                    {"label": "low", "group": "tone", "rule": {"or_patterns": [{"pattern": "calm"}]}},
+                    {"label": "high", "group": "tone", "priority": 1, "rule": {"or_patterns": [{"pattern": "angry"}]}}
+                ]
+            }"#,
+        )
+        .expect("Failed to load rules");
+
+        for text in ["refund", "angry about the refund", "calm", "calm but angry"] {
+            let labels = rulebox.assign_labels(text);
+            for label in ["complaint", "busy", "low", "high"] {
+                assert_eq!(
+                    rulebox.has_label(text, label),
+                    labels.contains(&label.to_string()),
+                    "{} in {:?}",
+                    label,
+                    text
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::*;
+    use std::collections::HashSet;
+
+    const RULES: &str = r#"{
+        "implies": {
+            "privacy.email": ["privacy"],
+            "privacy.phone": ["privacy"],
+            "privacy": ["sensitive"]
+        },
+        "count_rules": [{"if_labels_count_at_least": 2, "among": ["privacy.*"], "then": "privacy.bulk"}],
+        "rules": [
+            {"label": "privacy.email", "rule": {"or_patterns": [{"pattern": "@example\\.com"}]}},
+            {"label": "privacy.phone", "rule": {"or_patterns": [{"pattern": "\\d{3}-\\d{4}"}]}},
+            {"label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}}
+        ]
+    }"#;
+
+    fn set(labels: &[&str]) -> HashSet<String> {
+        labels.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parent_labels_are_added() {
+        let rulebox = RuleBox::from_json(RULES).expect("Failed to load rules");
+        assert_eq!(
+            rulebox.implied_labels("privacy.email"),
+            vec!["privacy", "sensitive"]
+        );
+
+        let texts: Vec<String> = ["hello", "mail me@example.com", "me@example.com or 555-1234"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let expected = vec![
+            set(&["greeting"]),
+            set(&["privacy.email", "privacy", "sensitive"]),
+            set(&[
+                "privacy.email",
+                "privacy.phone",
+                "privacy.bulk",
+                "privacy",
+                "sensitive",
+            ]),
+        ];
+        assert_eq!(rulebox.check_many(&texts), expected);
+        let batch: Vec<HashSet<String>> = rulebox
+            .assign_labels_vector(&texts)
+            .into_iter()
+            .map(|labels| labels.into_iter().collect())
+            .collect();
+        assert_eq!(batch, expected);
+    }
+
+    #[test]
+    fn test_implied_label_cycles_terminate() {
+        let rulebox =
+            RuleBox::from_json(r#"[{"label": "a", "rule": {"or_patterns": [{"pattern": "x"}]}}]"#)
+                .unwrap()
+                .with_implied_labels("a", &["b"])
+                .with_implied_labels("b", &["a", "c"]);
+        assert_eq!(rulebox.implied_labels("a"), vec!["b", "c"]);
+        assert_eq!(
+            rulebox.check_many(&["x".to_string()])[0],
+            set(&["a", "b", "c"])
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_from_path_filtered_keeps_child_rules() {
+        let path =
+            std::env::temp_dir().join(format!("rulebox-taxonomy-{}.json", std::process::id()));
+        std::fs::write(&path, RULES).unwrap();
+
+        let rulebox = RuleBox::from_path_filtered(path.to_str().unwrap(), &["privacy"]).unwrap();
+        let labels: Vec<&str> = rulebox.rules.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(labels, vec!["privacy.email", "privacy.phone"]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,128 @@
+// Span APIs fed awkward but valid UTF-8: emoji, ZWJ sequences and combining
+// marks right at match edges. Nothing may panic and every span must land on
+// character boundaries.
+
+#[cfg(test)]
+mod tests {
+    use rulebox_rust::normalize::NormalizedText;
+    use rulebox_rust::*;
+
+    const TEXTS: &[&str] = &[
+        "👩‍👩‍👧cat👍🏽",
+        "cafe\u{301} cat",
+        "cat\u{301}\u{302}",
+        "🐈cat🐈cat🐈",
+        "5\u{2009}5\u{2009}5 📞 555 🙂",
+        "e\u{301}\u{301}\u{301}",
+        "",
+        "🇬🇧",
+    ];
+
+    fn rulebox() -> RuleBox {
+        RuleBox::from_json(
+            r#"[
+                {"label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}, {"pattern": ".\\u0301"}]}},
+                {"label": "emoji", "priority": 1, "rule": {"or_patterns": [{"pattern": "[\\x{1F300}-\\x{1FAFF}]+"}]}},
+                {"label": "phone", "rule": {"or_patterns": [{"pattern": "555"}], "digit_normalize": true}},
+                {"label": "any", "rule": {"or_patterns": [{"pattern": "."}]},
+                 "extract_schema": {"x": "string"}},
+                {"label": "edge", "rule": {"or_patterns": [{"pattern": "\\b|$"}]}}
+            ]"#,
+        )
+        .expect("Failed to load rules")
+    }
+
+    fn assert_boundaries(text: &str, start: usize, end: usize) {
+        assert!(
+            start <= end && end <= text.len(),
+            "{:?} {}..{}",
+            text,
+            start,
+            end
+        );
+        assert!(
+            text.get(start..end).is_some(),
+            "{:?} {}..{}",
+            text,
+            start,
+            end
+        );
+    }
+
+    #[test]
+    fn test_match_and_segment_spans_are_char_aligned() {
+        let rulebox = rulebox();
+        for text in TEXTS {
+            for m in rulebox.all_matches(text) {
+                assert_boundaries(text, m.start, m.end);
+                assert!(m.text(text).is_some());
+            }
+            for policy in [
+                OverlapPolicy::KeepAll,
+                OverlapPolicy::HighestPriority,
+                OverlapPolicy::Longest,
+            ] {
+                let segments = rulebox.segment_with(text, policy);
+                let covered: String = segments.iter().map(|s| s.text(text).unwrap()).collect();
+                assert_eq!(&covered, text);
+            }
+            if let Some((_, start)) = rulebox.first_label_by_position(text) {
+                assert!(text.is_char_boundary(start));
+            }
+            rulebox.extract_typed(text);
+        }
+    }
+
+    #[test]
+    fn test_digit_normalized_spans_map_back_to_boundaries() {
+        let text = "5\u{2009}5\u{2009}5 📞 555 🙂";
+        let matches = rulebox().all_matches(text);
+        let phones: Vec<&str> = matches
+            .iter()
+            .filter(|m| m.label == "phone")
+            .map(|m| m.text(text).unwrap())
+            .collect();
+        assert_eq!(phones, vec!["5\u{2009}5\u{2009}5", "555"]);
+
+        // Offsets inside a multibyte character snap back to its start
+        let normalized = NormalizedText::digits("a📞b");
+        for start in 0..=normalized.text.len() + 2 {
+            for end in start..=normalized.text.len() + 2 {
+                let (s, e) = normalized.original_span(start, end);
+                assert_boundaries("a📞b", s, e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_span_helpers_reject_foreign_texts() {
+        let text = "🐈cat";
+        let m = &rulebox().all_matches(text)[0];
+        assert_eq!(m.text(text), Some("🐈"));
+        assert_eq!(m.text("ab"), None);
+        assert_eq!(m.text("🐈"), Some("🐈"));
+        assert_eq!(m.text("é🐈"), None);
+    }
+
+    #[cfg(feature = "fuzzy")]
+    #[test]
+    fn test_fuzzy_spans_around_emoji() {
+        let rulebox = RuleBox::from_json(
+            r#"[{"label": "spam", "rule": {"or_patterns": [
+                {"pattern": "viagra", "match_type": "fuzzy", "max_distance": 2, "flags": ["i"]}
+            ]}}]"#,
+        )
+        .expect("Failed to load rules");
+        for text in ["v🙂agra", "🙂viagr🙂", "VİAGRA", "vi\u{301}agra👍🏽"] {
+            for m in rulebox.all_matches(text) {
+                assert_boundaries(text, m.start, m.end);
+            }
+            let covered: String = rulebox
+                .segment(text)
+                .iter()
+                .map(|s| s.text(text).unwrap())
+                .collect();
+            assert_eq!(covered, text);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+    use rulebox_rust::*;
+    use std::fs;
+
+    #[test]
+    fn test_validate_dir_reports_each_file() {
+        let dir = std::env::temp_dir().join(format!("rulebox-validate-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        fs::write(
+            dir.join("a_good.json"),
+            r#"[{"label": "ok", "rule": {"or_patterns": [{"pattern": "fine"}]}}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("b_bad_regex.json"),
+            r#"[{"label": "broken", "rule": {"or_patterns": [{"pattern": "(unclosed"}]}}]"#,
+        )
+        .unwrap();
+        fs::write(dir.join("c_bad_json.json"), "not json").unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let results = validate_dir(dir.to_str().unwrap());
+        let names: Vec<String> = results
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["a_good.json", "b_bad_regex.json", "c_bad_json.json"]
+        );
+
+        assert!(results[0].1.is_ok());
+        assert!(matches!(results[1].1, Err(RuleBoxError::Compile(_))));
+        assert!(results[1]
+            .1
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("(unclosed"));
+        assert!(matches!(results[2].1, Err(RuleBoxError::Parse(_))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate_dir_missing_directory() {
+        let results = validate_dir("/nonexistent/rulebox/rules");
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].1, Err(RuleBoxError::Io(_))));
+    }
+
+    #[test]
+    fn test_canonicalize_file_is_stable_and_keeps_labels() {
+        let path =
+            std::env::temp_dir().join(format!("rulebox-canonical-{}.json", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        std::fs::write(
+            &path,
+            r#"[ {"uuid": "b", "rule": {"or_patterns": [{"flags": ["m", "i", "i"], "pattern": "^dog"}]}, "label": "dog"},
+                {"label": "cat", "uuid": "z", "rule": {"or_patterns": [{"pattern": "kitten"}]}},
+                {"label": "cat", "uuid": "a", "rule": {"or_patterns": [{"pattern": "cat"}]}} ]"#,
+        )
+        .unwrap();
+        let texts: Vec<String> = ["cat", "Dog", "a\ndog", "kitten"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let before = RuleBox::from_path(path_str).unwrap().check_many(&texts);
+
+        RuleBox::canonicalize_file(path_str).expect("Failed to canonicalize");
+        let canonical = std::fs::read_to_string(&path).unwrap();
+        RuleBox::canonicalize_file(path_str).unwrap();
+        let again = std::fs::read_to_string(&path).unwrap();
+        let after = RuleBox::from_path(path_str).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(canonical, again);
+        assert!(canonical.starts_with("[\n  {\n    \"active\": true,"));
+        let value: serde_json::Value = serde_json::from_str(&canonical).unwrap();
+        assert_eq!(
+            value[0]["rule"]["or_patterns"][0]["flags"],
+            serde_json::json!(["i", "m"])
+        );
+        // All three share the default priority, so file order stays
+        let order: Vec<&str> = after.rules.iter().map(|r| r.uuid.as_str()).collect();
+        assert_eq!(order, vec!["b", "z", "a"]);
+        assert_eq!(after.check_many(&texts), before);
+    }
+
+    #[test]
+    fn test_canonicalize_file_keeps_results_in_order() {
+        let path = std::env::temp_dir().join(format!(
+            "rulebox-canonical-order-{}.json",
+            std::process::id()
+        ));
+        let path_str = path.to_str().unwrap();
+        std::fs::write(
+            &path,
+            r#"[
+                {"uuid": "z", "label": "zebra", "rule": {"or_patterns": [{"pattern": "stripes", "flags": ["i", "i"]}]}},
+                {"uuid": "y", "label": "ham", "group": "verdict", "rule": {"or_patterns": [{"pattern": "free"}]}},
+                {"uuid": "x", "label": "spam", "group": "verdict", "rule": {"or_patterns": [{"pattern": "free"}]}},
+                {"uuid": "b", "label": "apple", "rule": {"or_patterns": [{"pattern": "fruit"}]}},
+                {"uuid": "a", "label": "apple", "rule": {"or_patterns": [{"pattern": "stripes"}]}}
+            ]"#,
+        )
+        .unwrap();
+        let texts: Vec<String> = ["Stripes and fruit", "free fruit", "stripes", "nothing"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let results = |rulebox: &RuleBox| {
+            let classified: Vec<Option<String>> = texts
+                .iter()
+                .map(|text| rulebox.classify_detailed(text).map(|c| c.uuid))
+                .collect();
+            let hits: Vec<Vec<LabelHit>> = rulebox.check_many_hits(&texts);
+            (rulebox.assign_labels_vector(&texts), classified, hits)
+        };
+        let before = results(&RuleBox::from_path(path_str).unwrap());
+
+        RuleBox::canonicalize_file(path_str).unwrap();
+        let after = results(&RuleBox::from_path(path_str).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(after, before);
+        assert_eq!(before.0[0], vec!["zebra", "apple"]);
+        assert_eq!(before.0[1], vec!["apple", "ham"]);
+    }
+
+    #[test]
+    fn test_canonicalize_file_sorts_rules_without_ties() {
+        let path = std::env::temp_dir().join(format!(
+            "rulebox-canonical-sorted-{}.json",
+            std::process::id()
+        ));
+        let path_str = path.to_str().unwrap();
+        std::fs::write(
+            &path,
+            r#"[
+                {"uuid": "b", "label": "dog", "priority": 1, "rule": {"or_patterns": [{"pattern": "dog"}]}},
+                {"uuid": "z", "label": "cat", "priority": 2, "rule": {"or_patterns": [{"pattern": "kitten"}]}},
+                {"uuid": "a", "label": "cat", "priority": 3, "rule": {"or_patterns": [{"pattern": "cat"}]}}
+            ]"#,
+        )
+        .unwrap();
+        let texts: Vec<String> = ["cat and dog", "kitten", "dog", "nothing"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let results = |rulebox: &RuleBox| {
+            texts
+                .iter()
+                .map(|text| {
+                    let mut labels = rulebox.assign_labels(text);
+                    labels.sort();
+                    (labels, rulebox.classify_detailed(text).map(|c| c.uuid))
+                })
+                .collect::<Vec<_>>()
+        };
+        let before = results(&RuleBox::from_path(path_str).unwrap());
+
+        RuleBox::canonicalize_file(path_str).unwrap();
+        let after = RuleBox::from_path(path_str).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let order: Vec<&str> = after.rules.iter().map(|r| r.uuid.as_str()).collect();
+        assert_eq!(order, vec!["a", "z", "b"]);
+        assert_eq!(results(&after), before);
+    }
+
+    #[test]
+    fn test_to_path_round_trips_edits() {
+        let path = std::env::temp_dir().join(format!("rulebox-save-{}.json", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        let mut rulebox = RuleBox::from_json(
+            r#"{"default_flags": ["i"], "rules": [
+                {"uuid": "b", "label": "dog", "rule": {"or_patterns": [{"pattern": "dog", "flags": ["m", "i"]}]}},
+                {"uuid": "a", "label": "cat", "rule": {"or_patterns": [{"pattern": "cat"}]}}
+            ]}"#,
+        )
+        .unwrap();
+        rulebox.rules[0].active = false;
+        let mut added: LabelRule = serde_json::from_str(
+            r#"{"label": "bird", "rule": {"or_patterns": [{"pattern": "bird"}]}}"#,
+        )
+        .unwrap();
+        added.compile().unwrap();
+        rulebox.rules.push(added);
+
+        rulebox.to_path(path_str).expect("Failed to save");
+        let saved = std::fs::read_to_string(&path).unwrap();
+        let reloaded = RuleBox::from_path(path_str).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(saved, rulebox.to_json_string().unwrap() + "\n");
+        assert_eq!(
+            reloaded.to_json_string().unwrap(),
+            rulebox.to_json_string().unwrap()
+        );
+        let uuids: Vec<&str> = reloaded.rules.iter().map(|r| r.uuid.as_str()).collect();
+        assert_eq!(uuids, vec!["b", "a", rulebox.rules[2].uuid.as_str()]);
+        assert_eq!(reloaded.rules[0].rule.or_patterns[0].flags, vec!["m", "i"]);
+        let mut labels = reloaded.assign_labels("CAT dog bird");
+        labels.sort();
+        assert_eq!(labels, vec!["bird", "cat"]);
+        assert!(saved.find("\"uuid\"").unwrap() < saved.find("\"label\"").unwrap());
+
+        assert!(rulebox.to_path("rules.yaml").is_err());
+    }
+}
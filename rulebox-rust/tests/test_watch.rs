@@ -0,0 +1,68 @@
+#[cfg(test)]
+#[cfg(feature = "watch")]
+mod tests {
+    use rulebox_rust::*;
+    use std::time::{Duration, Instant};
+
+    const HELLO: &str =
+        r#"[{"uuid": "r1", "label": "greeting", "rule": {"or_patterns": [{"pattern": "hello"}]}}]"#;
+    const BYE: &str =
+        r#"[{"uuid": "r1", "label": "farewell", "rule": {"or_patterns": [{"pattern": "bye"}]}}]"#;
+
+    fn wait_for(condition: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        false
+    }
+
+    #[test]
+    fn test_watched_rulebox_reloads_on_change() {
+        let dir = std::env::temp_dir().join(format!("rulebox-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rules.json");
+        std::fs::write(&path, HELLO).unwrap();
+
+        let watched = WatchedRuleBox::new(path.to_str().unwrap()).unwrap();
+        let before = watched.current();
+        assert_eq!(before.assign_labels("hello, bye"), vec!["greeting"]);
+
+        std::fs::write(&path, BYE).unwrap();
+        assert!(wait_for(|| watched.generation() == 1));
+        assert_eq!(
+            watched.current().assign_labels("hello, bye"),
+            vec!["farewell"]
+        );
+        // Boxes handed out earlier keep their rules
+        assert_eq!(before.assign_labels("hello, bye"), vec!["greeting"]);
+
+        // A broken edit keeps the last good box serving
+        std::fs::write(
+            &path,
+            r#"[{"label": "x", "rule": {"or_patterns": [{"pattern": "("}]}}]"#,
+        )
+        .unwrap();
+        assert!(wait_for(|| watched.last_error().is_some()));
+        assert_eq!(watched.generation(), 1);
+        assert_eq!(watched.current().assign_labels("bye"), vec!["farewell"]);
+
+        // Rewriting the same contents doesn't swap in a new box
+        std::fs::write(&path, HELLO).unwrap();
+        assert!(wait_for(|| watched.generation() == 2));
+        assert!(watched.last_error().is_none());
+        assert!(!watched.reload().unwrap());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_watched_rulebox_first_load_must_succeed() {
+        let path =
+            std::env::temp_dir().join(format!("rulebox-watch-missing-{}.json", std::process::id()));
+        assert!(WatchedRuleBox::new(path.to_str().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+// HTTP API over a rule file, for services that would rather call the labeller
+// over the network than embed it:
+//
+//   POST /label   {"text": "..."} or {"texts": ["...", ...]}
+//   GET  /rules   the rules currently serving, in the rule file format
+//   POST /reload  re-read the rule file now
+//
+// The file is also reloaded whenever it changes on disk.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use rulebox_rust::{RuleBoxError, WatchedRuleBox};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Build the router for the rule file behind `rules`.
+pub fn app(rules: Arc<WatchedRuleBox>) -> Router {
+    Router::new()
+        .route("/label", post(label))
+        .route("/rules", get(rules_json))
+        .route("/reload", post(reload))
+        .with_state(rules)
+}
+
+/// A single text or a batch.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LabelRequest {
+    Text { text: String },
+    Texts { texts: Vec<String> },
+}
+
+/// Labels in the shape of the request: a sorted list for a text, a list of
+/// sorted lists (in input order) for a batch.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum LabelResponse {
+    Text { labels: Vec<String> },
+    Texts { labels: Vec<Vec<String>> },
+}
+
+async fn label(
+    State(rules): State<Arc<WatchedRuleBox>>,
+    Json(request): Json<LabelRequest>,
+) -> Result<Json<LabelResponse>, Response> {
+    let rulebox = rules.current();
+    // Labelling is CPU-bound, so keep it off the async workers
+    let response = tokio::task::spawn_blocking(move || match request {
+        LabelRequest::Text { text } => {
+            let mut labels = rulebox.assign_labels(&text);
+            labels.sort();
+            LabelResponse::Text { labels }
+        }
+        LabelRequest::Texts { texts } => {
+            let mut labels = rulebox.assign_labels_vector(&texts);
+            labels.iter_mut().for_each(|labels| labels.sort());
+            LabelResponse::Texts { labels }
+        }
+    })
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(response))
+}
+
+async fn rules_json(State(rules): State<Arc<WatchedRuleBox>>) -> Response {
+    match serde_json::to_value(&*rules.current()) {
+        Ok(value) => Json(value).into_response(),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// `{"reloaded": bool, "generation": n}`, where `reloaded` is false when the
+/// file hadn't changed. A file that fails to load is a 422 and the previous
+/// rules keep serving.
+async fn reload(State(rules): State<Arc<WatchedRuleBox>>) -> Response {
+    let result = tokio::task::spawn_blocking({
+        let rules = Arc::clone(&rules);
+        move || rules.reload()
+    })
+    .await;
+    match result {
+        Ok(Ok(reloaded)) => Json(json!({
+            "reloaded": reloaded,
+            "generation": rules.generation(),
+        }))
+        .into_response(),
+        Ok(Err(e)) => reload_error(&e),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+fn reload_error(e: &RuleBoxError) -> Response {
+    let status = match e {
+        RuleBoxError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::UNPROCESSABLE_ENTITY,
+    };
+    error(status, e.to_string())
+}
+
+fn error(status: StatusCode, message: String) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
@@ -0,0 +1,48 @@
+// `rulebox-server`: serve a rule file over HTTP. See the library for the API.
+
+use clap::Parser;
+use rulebox_rust::WatchedRuleBox;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+#[derive(Parser)]
+#[command(
+    name = "rulebox-server",
+    version,
+    about = "Serve RuleBox labelling over HTTP"
+)]
+struct Args {
+    /// Rule file (JSON, YAML or TOML), reloaded whenever it changes.
+    #[arg(long)]
+    rules: PathBuf,
+    /// Address to listen on.
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    let rules = match WatchedRuleBox::new(&args.rules.to_string_lossy()) {
+        Ok(rules) => Arc::new(rules),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let listener = match tokio::net::TcpListener::bind(args.listen).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("error: can't listen on {}: {}", args.listen, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("serving {} on http://{}", args.rules.display(), args.listen);
+    if let Err(e) = axum::serve(listener, rulebox_server::app(rules)).await {
+        eprintln!("error: {}", e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
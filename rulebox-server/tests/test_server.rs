@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use http_body_util::BodyExt;
+    use rulebox_rust::WatchedRuleBox;
+    use serde_json::{json, Value};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    const RULES: &str = r#"[
+        {"uuid": "r1", "label": "greeting", "rule": {"or_patterns": [{"pattern": "(?i)hello"}]}},
+        {"uuid": "r2", "label": "farewell", "rule": {"or_patterns": [{"pattern": "(?i)bye"}]}}
+    ]"#;
+
+    fn rules_file(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rulebox-server-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rules.json");
+        fs::write(&path, RULES).unwrap();
+        path
+    }
+
+    fn app(path: &Path) -> axum::Router {
+        let rules = WatchedRuleBox::new(path.to_str().unwrap()).unwrap();
+        rulebox_server::app(Arc::new(rules))
+    }
+
+    async fn call(app: axum::Router, method: &str, uri: &str, body: Value) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_label_text_and_texts() {
+        let app = app(&rules_file("label"));
+
+        let (status, body) = call(app.clone(), "POST", "/label", json!({"text": "Hello"})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({"labels": ["greeting"]}));
+
+        let texts = json!({"texts": ["hello and bye", "nothing", "BYE"]});
+        let (status, body) = call(app, "POST", "/label", texts).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            json!({"labels": [["farewell", "greeting"], [], ["farewell"]]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_label_rejects_bad_body() {
+        let app = app(&rules_file("bad-body"));
+        let request = Request::builder()
+            .method("POST")
+            .uri("/label")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"txt": "hello"}"#))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_get_rules() {
+        let app = app(&rules_file("rules"));
+        let request = Request::builder()
+            .uri("/rules")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let rules: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(rules[0]["uuid"], "r1");
+        assert_eq!(rules[1]["label"], "farewell");
+    }
+
+    #[tokio::test]
+    async fn test_reload() {
+        let path = rules_file("reload");
+        let app = app(&path);
+
+        let (status, body) = call(app.clone(), "POST", "/reload", json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["reloaded"], false);
+
+        fs::write(
+            &path,
+            r#"[{"label": "broken", "rule": {"or_patterns": [{"pattern": "("}]}}]"#,
+        )
+        .unwrap();
+        let (status, body) = call(app.clone(), "POST", "/reload", json!({})).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(body["error"].as_str().unwrap().contains("Invalid regex"));
+
+        // The previous rules are still serving
+        let (_, body) = call(app, "POST", "/label", json!({"text": "hello"})).await;
+        assert_eq!(body, json!({"labels": ["greeting"]}));
+    }
+}
@@ -0,0 +1,35 @@
+// WebAssembly bindings for the browser rule editor preview. Build with
+// `wasm-pack build rulebox-wasm --target web`.
+
+use rulebox_rust::RuleBox as RustRuleBox;
+use wasm_bindgen::prelude::*;
+
+/// A JavaScript wrapper for the Rust RuleBox
+#[wasm_bindgen]
+pub struct RuleBox {
+    inner: RustRuleBox,
+}
+
+#[wasm_bindgen]
+impl RuleBox {
+    /// Create a RuleBox from a JSON string, throwing an `Error` with the
+    /// load or compile failure if the rules are invalid
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<RuleBox, JsError> {
+        let inner = RustRuleBox::from_json(json).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(RuleBox { inner })
+    }
+
+    /// Assign labels to a single text and return them as an array of strings
+    #[wasm_bindgen(js_name = assignLabels)]
+    pub fn assign_labels(&self, text: &str) -> Vec<String> {
+        self.inner.assign_labels(text)
+    }
+
+    /// Assign labels to an array of texts and return an array of label arrays
+    #[wasm_bindgen(js_name = assignLabelsVector)]
+    pub fn assign_labels_vector(&self, texts: Vec<String>) -> Result<JsValue, JsError> {
+        let labels = self.inner.assign_labels_vector(&texts);
+        Ok(serde_wasm_bindgen::to_value(&labels)?)
+    }
+}
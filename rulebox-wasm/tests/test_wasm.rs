@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod tests {
+    use rulebox_wasm::RuleBox;
+
+    // Methods that build JS values only run under wasm32; these cover the rest
+    #[test]
+    fn test_from_json_and_assign_labels() {
+        let rulebox = RuleBox::from_json(
+            r#"[{"label": "greeting", "rule": {"or_patterns": [{"pattern": "\\bhello\\b", "flags": ["i"]}]}}]"#,
+        )
+        .expect("Failed to load rules");
+        assert_eq!(rulebox.assign_labels("Hello there"), vec!["greeting"]);
+        assert!(rulebox.assign_labels("goodbye").is_empty());
+    }
+}